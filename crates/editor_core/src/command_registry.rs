@@ -5,7 +5,12 @@ use bevy::prelude::*;
 use world::storage::{write_project_manifest, write_world_manifest};
 
 use crate::commands::CommandStack;
+use crate::editor_state::ProjectEditorStateResource;
 use crate::project::ProjectState;
+use crate::selection::{SelectionState, SelectionTarget};
+
+/// Upper bound for the force-load radius, in tiles around the camera.
+pub const MAX_FORCE_LOAD_RADIUS_TILES: u32 = 8;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CommandCategory {
@@ -22,6 +27,10 @@ pub enum CommandId {
     Redo,
     FocusSelection,
     ToggleOverlays,
+    TogglePinTile,
+    ClearPinnedTiles,
+    GrowForceLoadRadius,
+    ShrinkForceLoadRadius,
 }
 
 impl CommandId {
@@ -33,6 +42,10 @@ impl CommandId {
             CommandId::Redo => "redo",
             CommandId::FocusSelection => "focus_selection",
             CommandId::ToggleOverlays => "toggle_overlays",
+            CommandId::TogglePinTile => "toggle_pin_tile",
+            CommandId::ClearPinnedTiles => "clear_pinned_tiles",
+            CommandId::GrowForceLoadRadius => "grow_force_load_radius",
+            CommandId::ShrinkForceLoadRadius => "shrink_force_load_radius",
         }
     }
 }
@@ -97,6 +110,34 @@ impl CommandRegistry {
                     category: CommandCategory::View,
                     hotkey: Some("O"),
                 },
+                CommandDef {
+                    id: CommandId::TogglePinTile,
+                    name: "Pin/Unpin Tile",
+                    description: "Keep the selected tile loaded wherever the camera is",
+                    category: CommandCategory::View,
+                    hotkey: None,
+                },
+                CommandDef {
+                    id: CommandId::ClearPinnedTiles,
+                    name: "Clear Pinned Tiles",
+                    description: "Unpin every tile of the current world",
+                    category: CommandCategory::View,
+                    hotkey: None,
+                },
+                CommandDef {
+                    id: CommandId::GrowForceLoadRadius,
+                    name: "Grow Force-Load Radius",
+                    description: "Always load one more ring of tiles around the camera",
+                    category: CommandCategory::View,
+                    hotkey: None,
+                },
+                CommandDef {
+                    id: CommandId::ShrinkForceLoadRadius,
+                    name: "Shrink Force-Load Radius",
+                    description: "Always load one ring of tiles fewer around the camera",
+                    category: CommandCategory::View,
+                    hotkey: None,
+                },
             ],
         }
     }
@@ -152,6 +193,8 @@ pub fn handle_command_invoked(
     mut command_stack: ResMut<CommandStack>,
     mut overlays: ResMut<OverlayState>,
     mut focus_request: ResMut<FocusSelectionRequest>,
+    mut editor_state: ResMut<ProjectEditorStateResource>,
+    selection: Res<SelectionState>,
 ) {
     match event.event().id {
         CommandId::Save | CommandId::SaveAllDirty => {
@@ -204,5 +247,47 @@ pub fn handle_command_invoked(
             overlays.show_overlays = !overlays.show_overlays;
             info!("overlays enabled: {}", overlays.show_overlays);
         }
+        CommandId::TogglePinTile => {
+            let Some(SelectionTarget::Tile {
+                world_id, tile_id, ..
+            }) = &selection.selected
+            else {
+                info!("pin requested but no tile is selected");
+                return;
+            };
+            let pinned = editor_state
+                .state
+                .streaming
+                .toggle_pin(world_id, tile_id.coord);
+            info!(
+                "tile ({}, {}) pinned: {}",
+                tile_id.coord.x, tile_id.coord.y, pinned
+            );
+        }
+        CommandId::ClearPinnedTiles => {
+            let Some(world_id) = project_state
+                .current
+                .as_ref()
+                .and_then(|project| project.current_world_id.clone())
+            else {
+                return;
+            };
+            let cleared = editor_state.state.streaming.clear_pins(&world_id);
+            info!("unpinned {cleared} tile(s)");
+        }
+        CommandId::GrowForceLoadRadius | CommandId::ShrinkForceLoadRadius => {
+            let radius = editor_state
+                .state
+                .streaming
+                .force_load_radius_tiles
+                .min(MAX_FORCE_LOAD_RADIUS_TILES);
+            let radius = if matches!(event.event().id, CommandId::GrowForceLoadRadius) {
+                (radius + 1).min(MAX_FORCE_LOAD_RADIUS_TILES)
+            } else {
+                radius.saturating_sub(1)
+            };
+            editor_state.state.streaming.force_load_radius_tiles = radius;
+            info!("force-load radius: {radius} tile(s)");
+        }
     }
 }
@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use anyhow::Context;
 use bevy::log::warn;
 use bevy::prelude::{DetectChanges, Res, Resource};
+use foundation::ids::TileCoord;
 use serde::{Deserialize, Serialize};
 
 const EDITOR_STATE_FILE: &str = "editor_state.toml";
@@ -16,6 +17,7 @@ pub struct ProjectEditorState {
     pub last_world_id: Option<String>,
     pub autosave_enabled: bool,
     pub viewport_overlays: ViewportOverlayPrefs,
+    pub streaming: StreamingPrefs,
 }
 
 impl Default for ProjectEditorState {
@@ -25,6 +27,7 @@ impl Default for ProjectEditorState {
             last_world_id: None,
             autosave_enabled: true,
             viewport_overlays: ViewportOverlayPrefs::default(),
+            streaming: StreamingPrefs::default(),
         }
     }
 }
@@ -68,6 +71,59 @@ impl Default for ViewportOverlayPrefs {
     }
 }
 
+/// Persisted tile streaming controls stored per project.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct StreamingPrefs {
+    /// Tiles kept resident wherever the camera is. Only whole tiles are pinned from the editor;
+    /// chunk pins exist on the runtime streamer alone.
+    pub pinned_tiles: Vec<PinnedTile>,
+    /// Rings of tiles around the camera always loaded; 0 turns force-loading off. Values above
+    /// `MAX_FORCE_LOAD_RADIUS_TILES` are treated as the maximum.
+    pub force_load_radius_tiles: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PinnedTile {
+    pub world_id: String,
+    pub tile: TileCoord,
+}
+
+impl StreamingPrefs {
+    pub fn pinned_tiles<'a>(&'a self, world_id: &'a str) -> impl Iterator<Item = TileCoord> + 'a {
+        self.pinned_tiles
+            .iter()
+            .filter(move |pin| pin.world_id == world_id)
+            .map(|pin| pin.tile)
+    }
+
+    pub fn is_pinned(&self, world_id: &str, tile: TileCoord) -> bool {
+        self.pinned_tiles(world_id).any(|pinned| pinned == tile)
+    }
+
+    /// Pins the tile, or unpins it if it was pinned. Returns whether it is pinned now.
+    pub fn toggle_pin(&mut self, world_id: &str, tile: TileCoord) -> bool {
+        let before = self.pinned_tiles.len();
+        self.pinned_tiles
+            .retain(|pin| pin.world_id != world_id || pin.tile != tile);
+        if self.pinned_tiles.len() != before {
+            return false;
+        }
+        self.pinned_tiles.push(PinnedTile {
+            world_id: world_id.to_string(),
+            tile,
+        });
+        true
+    }
+
+    /// Unpins every tile of the world. Returns how many were pinned.
+    pub fn clear_pins(&mut self, world_id: &str) -> usize {
+        let before = self.pinned_tiles.len();
+        self.pinned_tiles.retain(|pin| pin.world_id != world_id);
+        before - self.pinned_tiles.len()
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct ProjectEditorStateResource {
     pub root: Option<PathBuf>,
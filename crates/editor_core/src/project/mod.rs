@@ -2,7 +2,9 @@ use bevy::log::warn;
 use bevy::prelude::*;
 use std::path::PathBuf;
 use world::schema::{ProjectManifest, RegionBounds, WorldManifest, WorldSpec};
-use world::storage::{project_layout, write_project_manifest, write_world_manifest};
+use world::storage::{
+    project_layout, write_project_manifest, write_world_manifest, QuarantineEntry,
+};
 
 use crate::autosave::{clear_recovery_state, refresh_recovery_state, RecoveryState};
 use crate::editor_state::ProjectEditorStateResource;
@@ -12,10 +14,12 @@ use crate::EditorConfig;
 mod create;
 mod helpers;
 mod open;
+mod quarantine;
 
 use create::{create_new_project, create_new_world};
 use helpers::{ensure_world_regions, update_config, world_has_tiles};
 use open::open_project;
+pub use quarantine::{QuarantineOutcome, QuarantineStatus};
 
 #[derive(Debug, Clone)]
 pub struct WorldInfo {
@@ -45,6 +49,7 @@ impl ProjectInfo {
 pub struct ProjectState {
     pub current: Option<ProjectInfo>,
     pub last_error: Option<String>,
+    pub quarantine: QuarantineStatus,
 }
 
 #[derive(Resource, Debug, Clone, Default)]
@@ -76,6 +81,18 @@ pub enum ProjectCommand {
     SetCurrentWorld {
         world_id: String,
     },
+    /// Moves a quarantined tile back into its region; `overwrite` replaces a newer tile there.
+    RestoreQuarantined {
+        root: PathBuf,
+        world_id: String,
+        entry: QuarantineEntry,
+        overwrite: bool,
+    },
+    PurgeQuarantined {
+        root: PathBuf,
+        world_id: String,
+        entry: QuarantineEntry,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -237,6 +254,17 @@ pub fn apply_project_commands(
                 }
             }
         }
+        ProjectCommand::RestoreQuarantined {
+            root,
+            world_id,
+            entry,
+            overwrite,
+        } => quarantine::apply_restore(&mut state, root, world_id, entry, *overwrite),
+        ProjectCommand::PurgeQuarantined {
+            root,
+            world_id,
+            entry,
+        } => quarantine::apply_purge(&mut state, root, world_id, entry),
     }
 }
 
@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+
+use world::storage::{
+    project_layout, purge_quarantined, restore_quarantined, world_layout, QuarantineEntry,
+    RestoreOutcome, WorldLayout,
+};
+
+use super::helpers::world_has_tiles;
+use super::ProjectState;
+
+/// Result of a quarantine restore or purge, for the panel that requested it.
+#[derive(Debug, Clone)]
+pub enum QuarantineOutcome {
+    Restored(PathBuf),
+    /// A newer tile is at the original location; restoring with `overwrite` replaces it.
+    Conflict {
+        entry: QuarantineEntry,
+        existing: PathBuf,
+    },
+    Purged(String),
+    Failed(String),
+}
+
+/// The last quarantine outcome; `revision` bumps with every restore or purge.
+#[derive(Debug, Clone, Default)]
+pub struct QuarantineStatus {
+    pub revision: u64,
+    pub outcome: Option<QuarantineOutcome>,
+}
+
+pub(super) fn apply_restore(
+    state: &mut ProjectState,
+    root: &Path,
+    world_id: &str,
+    entry: &QuarantineEntry,
+    overwrite: bool,
+) {
+    let outcome = match quarantine_layout(state, root, world_id) {
+        Ok(layout) => match restore_quarantined(&layout, entry, overwrite) {
+            Ok(RestoreOutcome::Restored(path)) => QuarantineOutcome::Restored(path),
+            Ok(RestoreOutcome::Conflict { existing }) => QuarantineOutcome::Conflict {
+                entry: entry.clone(),
+                existing,
+            },
+            Err(err) => QuarantineOutcome::Failed(format!("Restore failed: {err:#}")),
+        },
+        Err(err) => QuarantineOutcome::Failed(format!("Restore failed: {err}")),
+    };
+    if matches!(outcome, QuarantineOutcome::Restored(_)) {
+        refresh_has_tiles(state, world_id);
+    }
+    record(state, outcome);
+}
+
+pub(super) fn apply_purge(
+    state: &mut ProjectState,
+    root: &Path,
+    world_id: &str,
+    entry: &QuarantineEntry,
+) {
+    let outcome = match quarantine_layout(state, root, world_id) {
+        Ok(layout) => match purge_quarantined(&layout, entry) {
+            Ok(()) => QuarantineOutcome::Purged(entry.id.clone()),
+            Err(err) => QuarantineOutcome::Failed(format!("Purge failed: {err:#}")),
+        },
+        Err(err) => QuarantineOutcome::Failed(format!("Purge failed: {err}")),
+    };
+    record(state, outcome);
+}
+
+fn quarantine_layout(
+    state: &ProjectState,
+    root: &Path,
+    world_id: &str,
+) -> Result<WorldLayout, &'static str> {
+    let Some(current) = &state.current else {
+        return Err("no project open");
+    };
+    if current.root != root {
+        return Err("project mismatch");
+    }
+    if !current
+        .worlds
+        .iter()
+        .any(|world| world.manifest.world_id == world_id)
+    {
+        return Err("unknown world");
+    }
+    Ok(world_layout(
+        &project_layout(root, &current.manifest),
+        world_id,
+    ))
+}
+
+fn refresh_has_tiles(state: &mut ProjectState, world_id: &str) {
+    let Some(current) = &mut state.current else {
+        return;
+    };
+    let layout = project_layout(&current.root, &current.manifest);
+    if let Some(world) = current
+        .worlds
+        .iter_mut()
+        .find(|world| world.manifest.world_id == world_id)
+    {
+        world.has_tiles = world_has_tiles(&layout, &world.manifest);
+    }
+}
+
+fn record(state: &mut ProjectState, outcome: QuarantineOutcome) {
+    state.quarantine.revision = state.quarantine.revision.wrapping_add(1);
+    state.quarantine.outcome = Some(outcome);
+}
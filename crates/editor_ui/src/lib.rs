@@ -6,6 +6,7 @@ use viewport::update_prop_hover;
 
 pub mod panels;
 pub mod selection;
+pub mod streaming;
 pub mod viewport_overlays;
 
 pub struct EditorUiPlugin;
@@ -26,6 +27,7 @@ impl Plugin for EditorUiPlugin {
         .init_resource::<panels::viewport_overlay_options::ViewportOverlayPanelState>()
         .init_resource::<panels::viewport_overlay_hud::ViewportOverlayHudState>()
         .init_resource::<viewport_overlays::ViewportOverlaySyncState>()
+        .init_resource::<streaming::EditorTileStreaming>()
         .add_systems(Startup, setup_ui_camera)
         .add_systems(EguiPrimaryContextPass, panels::draw_root_panel)
         .add_systems(
@@ -42,6 +44,10 @@ impl Plugin for EditorUiPlugin {
                 selection::update_viewport_selection.after(update_prop_hover),
                 selection::sync_viewport_selection_overlay
                     .after(selection::update_viewport_selection),
+                streaming::sync_tile_streamer.before(streaming::update_tile_streaming),
+                streaming::update_tile_streaming
+                    .after(viewport::update_viewport_camera)
+                    .before(viewport::draw_streaming_overlay),
             ),
         );
     }
@@ -330,6 +330,22 @@ pub(crate) fn draw_root_panel(
                     });
                     ui.close();
                 }
+                ui.menu_button("Streaming", |ui| {
+                    for id in [
+                        editor_core::command_registry::CommandId::TogglePinTile,
+                        editor_core::command_registry::CommandId::ClearPinnedTiles,
+                        editor_core::command_registry::CommandId::GrowForceLoadRadius,
+                        editor_core::command_registry::CommandId::ShrinkForceLoadRadius,
+                    ] {
+                        let Some(command) = registry.find(id) else {
+                            continue;
+                        };
+                        if ui.button(command.name).clicked() {
+                            commands.trigger(editor_core::command_registry::CommandInvoked { id });
+                            ui.close();
+                        }
+                    }
+                });
             });
         });
     });
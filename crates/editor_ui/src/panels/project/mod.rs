@@ -2,5 +2,5 @@ mod helpers;
 mod state;
 mod ui;
 
-pub use state::{NewWorldState, ProjectPanelState};
+pub use state::{NewWorldState, ProjectPanelState, QuarantinePanelState, RestoreConflict};
 pub use ui::draw_project_panel;
@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use bevy::prelude::Resource;
 use world::schema::DEFAULT_WORLD_SPEC;
+use world::storage::QuarantineEntry;
+use world::validator::ValidationIssue;
 
 pub struct NewWorldState {
     pub name: String,
@@ -35,6 +39,29 @@ impl Default for NewWorldState {
     }
 }
 
+/// Quarantine browser for the current world.
+#[derive(Default)]
+pub struct QuarantinePanelState {
+    /// World root the entries were listed for; a different world reloads them.
+    pub world_key: Option<String>,
+    pub entries: Vec<QuarantineEntry>,
+    /// Id of the inspected entry and its current validation issues.
+    pub selected: Option<String>,
+    pub issues: Vec<ValidationIssue>,
+    pub conflict: Option<RestoreConflict>,
+    pub pending_purge: Option<String>,
+    pub status: Option<String>,
+    /// Revision of the last restore or purge outcome shown.
+    pub seen_revision: u64,
+}
+
+/// A restore that found a newer tile at the original location and awaits confirmation.
+#[derive(Debug, Clone)]
+pub struct RestoreConflict {
+    pub entry: QuarantineEntry,
+    pub existing: PathBuf,
+}
+
 #[derive(Resource)]
 pub struct ProjectPanelState {
     pub new_project_name: String,
@@ -57,6 +84,7 @@ pub struct ProjectPanelState {
     pub region_max_x: i32,
     pub region_max_y: i32,
     pub new_world: NewWorldState,
+    pub quarantine: QuarantinePanelState,
     pub pending_commands: Vec<editor_core::project::ProjectCommand>,
 }
 
@@ -83,6 +111,7 @@ impl Default for ProjectPanelState {
             region_max_x: 255,
             region_max_y: 255,
             new_world: NewWorldState::default(),
+            quarantine: QuarantinePanelState::default(),
             pending_commands: Vec::new(),
         }
     }
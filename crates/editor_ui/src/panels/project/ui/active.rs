@@ -1,5 +1,7 @@
 use bevy_egui::egui;
-use editor_core::project::{ActiveRegion, NewWorldRequest, ProjectCommand, ProjectInfo, WorldInfo};
+use editor_core::project::{
+    ActiveRegion, NewWorldRequest, ProjectCommand, ProjectInfo, QuarantineStatus, WorldInfo,
+};
 use world::schema::{RegionBounds, RegionManifest};
 
 use super::super::helpers::{
@@ -12,6 +14,7 @@ pub(super) fn draw_active_project(
     ui: &mut egui::Ui,
     state: &mut ProjectPanelState,
     info: &ProjectInfo,
+    quarantine: &QuarantineStatus,
     active_region: &mut ActiveRegion,
 ) {
     sync_from_project(state, info);
@@ -25,6 +28,14 @@ pub(super) fn draw_active_project(
         draw_world_settings(ui, state, info, world);
         draw_add_world(ui, state, info);
         draw_regions(ui, state, info, world, active_region);
+        super::quarantine::draw_quarantine(
+            ui,
+            &mut state.quarantine,
+            &mut state.pending_commands,
+            info,
+            world,
+            quarantine,
+        );
     }
 }
 
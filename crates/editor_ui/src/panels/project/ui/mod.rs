@@ -7,6 +7,7 @@ use super::ProjectPanelState;
 mod active;
 mod create;
 mod open;
+mod quarantine;
 mod recent;
 
 pub fn draw_project_panel(
@@ -25,7 +26,7 @@ pub fn draw_project_panel(
     }
 
     if let Some(info) = &project_state.current {
-        active::draw_active_project(ui, state, info, active_region);
+        active::draw_active_project(ui, state, info, &project_state.quarantine, active_region);
         ui.separator();
     } else {
         ui.label("No project open.");
@@ -0,0 +1,225 @@
+use bevy_egui::egui;
+use editor_core::project::{
+    ProjectCommand, ProjectInfo, QuarantineOutcome, QuarantineStatus, WorldInfo,
+};
+use world::storage::{
+    list_quarantined, project_layout, world_layout, QuarantineEntry, QuarantineKind, WorldLayout,
+};
+use world::tile_container::extensions::AddxRegistry;
+use world::validator::{validate_quarantined_tile, Severity};
+
+use super::super::state::{QuarantinePanelState, RestoreConflict};
+
+pub(super) fn draw_quarantine(
+    ui: &mut egui::Ui,
+    state: &mut QuarantinePanelState,
+    pending_commands: &mut Vec<ProjectCommand>,
+    info: &ProjectInfo,
+    world: &WorldInfo,
+    status: &QuarantineStatus,
+) {
+    ui.separator();
+    ui.heading("Quarantine");
+
+    let layout = world_layout(
+        &project_layout(&info.root, &info.manifest),
+        &world.manifest.world_id,
+    );
+    let world_key = layout.world_root.to_string_lossy().to_string();
+    if state.world_key.as_deref() != Some(&world_key) {
+        *state = QuarantinePanelState {
+            world_key: Some(world_key),
+            seen_revision: status.revision,
+            ..QuarantinePanelState::default()
+        };
+        refresh(state, &layout);
+    }
+    if state.seen_revision != status.revision {
+        state.seen_revision = status.revision;
+        apply_outcome(state, status.outcome.as_ref());
+        refresh(state, &layout);
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(format!("{} quarantined tile(s)", state.entries.len()));
+        if ui.button("Refresh").clicked() {
+            refresh(state, &layout);
+        }
+    });
+    if let Some(status) = &state.status {
+        ui.label(status);
+    }
+
+    let mut action = None;
+    let mut purge_request = None;
+    for entry in &state.entries {
+        ui.horizontal(|ui| {
+            let selected = state.selected.as_deref() == Some(entry.id.as_str());
+            let kind = match entry.kind {
+                QuarantineKind::TileFile => "tile",
+                QuarantineKind::TileDir => "legacy dir",
+            };
+            let label = format!(
+                "{} ({}, {}) {} - {}",
+                entry.region_id,
+                entry.tile.x,
+                entry.tile.y,
+                kind,
+                entry.reason.as_deref().unwrap_or("no reason recorded")
+            );
+            if ui.selectable_label(selected, label).clicked() {
+                action = Some(Action::Inspect(entry.clone()));
+            }
+            if ui.button("Restore").clicked() {
+                action = Some(Action::Restore(entry.clone(), false));
+            }
+            if ui.button("Purge").clicked() {
+                purge_request = Some(entry.id.clone());
+            }
+        });
+    }
+    if purge_request.is_some() {
+        state.pending_purge = purge_request;
+    }
+
+    if let Some(conflict) = state.conflict.clone() {
+        ui.colored_label(
+            egui::Color32::YELLOW,
+            format!(
+                "A newer tile exists at {}. Restoring moves it to quarantine.",
+                conflict.existing.display()
+            ),
+        );
+        ui.horizontal(|ui| {
+            if ui.button("Restore anyway").clicked() {
+                action = Some(Action::Restore(conflict.entry.clone(), true));
+            }
+            if ui.button("Cancel").clicked() {
+                state.conflict = None;
+            }
+        });
+    }
+
+    if let Some(id) = state.pending_purge.clone() {
+        ui.colored_label(
+            egui::Color32::LIGHT_RED,
+            format!("Permanently delete quarantine entry {id}?"),
+        );
+        ui.horizontal(|ui| {
+            if ui.button("Purge").clicked() {
+                if let Some(entry) = state.entries.iter().find(|entry| entry.id == id) {
+                    action = Some(Action::Purge(entry.clone()));
+                }
+                state.pending_purge = None;
+            }
+            if ui.button("Cancel").clicked() {
+                state.pending_purge = None;
+            }
+        });
+    }
+
+    if let Some(entry) = selected_entry(state) {
+        ui.separator();
+        ui.label(format!("Path: {}", entry.path.display()));
+        if !entry.details.is_empty() {
+            ui.label("Recorded issues:");
+            for detail in &entry.details {
+                ui.label(detail);
+            }
+        }
+        ui.label("Current issues:");
+        if state.issues.is_empty() {
+            ui.label("None");
+        }
+        for issue in &state.issues {
+            let color = match issue.severity {
+                Severity::Error => egui::Color32::LIGHT_RED,
+                Severity::Warning => egui::Color32::YELLOW,
+                Severity::Info => egui::Color32::GRAY,
+            };
+            let section = issue.section.as_deref().unwrap_or("");
+            ui.colored_label(
+                color,
+                format!("[{}] {} {}", issue.code, section, issue.message),
+            );
+        }
+    }
+
+    match action {
+        Some(Action::Inspect(entry)) => {
+            let extensions = AddxRegistry::default();
+            match validate_quarantined_tile(
+                &info.root,
+                &world.manifest.world_id,
+                &entry,
+                &extensions,
+            ) {
+                Ok(issues) => state.issues = issues,
+                Err(err) => {
+                    state.issues.clear();
+                    state.status = Some(format!("Inspect failed: {err:#}"));
+                }
+            }
+            state.selected = Some(entry.id);
+        }
+        Some(Action::Restore(entry, overwrite)) => {
+            state.conflict = None;
+            pending_commands.push(ProjectCommand::RestoreQuarantined {
+                root: info.root.clone(),
+                world_id: world.manifest.world_id.clone(),
+                entry,
+                overwrite,
+            });
+        }
+        Some(Action::Purge(entry)) => {
+            pending_commands.push(ProjectCommand::PurgeQuarantined {
+                root: info.root.clone(),
+                world_id: world.manifest.world_id.clone(),
+                entry,
+            });
+        }
+        None => {}
+    }
+}
+
+fn apply_outcome(state: &mut QuarantinePanelState, outcome: Option<&QuarantineOutcome>) {
+    match outcome {
+        Some(QuarantineOutcome::Restored(path)) => {
+            state.status = Some(format!("Restored {}", path.display()));
+        }
+        Some(QuarantineOutcome::Conflict { entry, existing }) => {
+            state.conflict = Some(RestoreConflict {
+                entry: entry.clone(),
+                existing: existing.clone(),
+            });
+        }
+        Some(QuarantineOutcome::Purged(id)) => state.status = Some(format!("Purged {id}")),
+        Some(QuarantineOutcome::Failed(message)) => state.status = Some(message.clone()),
+        None => {}
+    }
+}
+
+enum Action {
+    Inspect(QuarantineEntry),
+    Restore(QuarantineEntry, bool),
+    Purge(QuarantineEntry),
+}
+
+fn refresh(state: &mut QuarantinePanelState, layout: &WorldLayout) {
+    match list_quarantined(layout) {
+        Ok(entries) => state.entries = entries,
+        Err(err) => {
+            state.entries.clear();
+            state.status = Some(format!("Listing quarantine failed: {err:#}"));
+        }
+    }
+    if selected_entry(state).is_none() {
+        state.selected = None;
+        state.issues.clear();
+    }
+}
+
+fn selected_entry(state: &QuarantinePanelState) -> Option<&QuarantineEntry> {
+    let id = state.selected.as_deref()?;
+    state.entries.iter().find(|entry| entry.id == id)
+}
@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use editor_core::command_registry::MAX_FORCE_LOAD_RADIUS_TILES;
+use editor_core::editor_state::{ProjectEditorStateResource, StreamingPrefs};
+use editor_core::project::ProjectState;
+use editor_core::selection::{SelectionState, SelectionTarget};
+use runtime::streaming::{
+    StreamedTile, StreamingBudgets, StreamingFocus, TileLoadPool, TilePhase, TileStreamer,
+};
+use viewport::{
+    TileStreamingStatus, ViewportCameraController, ViewportCameraMode, ViewportTileStreamingState,
+};
+use world::schema::WorldManifest;
+use world::storage::{project_layout, world_layout};
+
+/// Read/decode threads; kept small so streaming does not compete with Bevy's task pools.
+const TILE_LOAD_THREADS: usize = 2;
+
+/// Streams the current world's tiles around the viewport camera and mirrors their states into
+/// [`ViewportTileStreamingState`] for the streaming overlay.
+#[derive(Resource, Default)]
+pub struct EditorTileStreaming {
+    streamer: Option<TileStreamer>,
+    pool: Option<TileLoadPool>,
+    /// World root and manifest the streamer was created for.
+    source: Option<(PathBuf, WorldManifest)>,
+}
+
+impl EditorTileStreaming {
+    pub fn streamer(&self) -> Option<&TileStreamer> {
+        self.streamer.as_ref()
+    }
+
+    /// Rescans the current world's tile files; call after tiles are created, restored or removed.
+    pub fn refresh_tile_files(&mut self) {
+        if let Some(streamer) = self.streamer.as_mut() {
+            streamer.refresh_tile_files();
+        }
+    }
+}
+
+/// Recreates the streamer when the open project, current world or its manifest changes (other
+/// project changes rescan its tile files), and applies the persisted pins and force-load radius
+/// and the selected tile's priority bias.
+pub fn sync_tile_streamer(
+    project_state: Res<ProjectState>,
+    editor_state: Res<ProjectEditorStateResource>,
+    selection: Res<SelectionState>,
+    mut streaming: ResMut<EditorTileStreaming>,
+    mut overlay: ResMut<ViewportTileStreamingState>,
+) {
+    let project_changed = project_state.is_changed();
+    let rebuilt = project_changed && rebuild_streamer(&project_state, &mut streaming);
+    if rebuilt {
+        overlay.clear();
+    } else if project_changed {
+        streaming.refresh_tile_files();
+    }
+    if !rebuilt && !editor_state.is_changed() && !selection.is_changed() {
+        return;
+    }
+    let streaming = &mut *streaming;
+    let (Some(streamer), Some((_, manifest))) =
+        (streaming.streamer.as_mut(), streaming.source.as_ref())
+    else {
+        return;
+    };
+    apply_streaming_controls(
+        streamer,
+        manifest,
+        &editor_state.state.streaming,
+        selection.selected.as_ref(),
+    );
+}
+
+/// Returns whether the streamer was replaced.
+fn rebuild_streamer(project_state: &ProjectState, streaming: &mut EditorTileStreaming) -> bool {
+    let current = project_state.current.as_ref().and_then(|project| {
+        let world = project.current_world()?;
+        Some((project, world))
+    });
+    let source = current.map(|(_, world)| (world.root.clone(), world.manifest.clone()));
+    if source == streaming.source {
+        return false;
+    }
+
+    streaming.streamer = current.map(|(project, world)| {
+        let layout = world_layout(
+            &project_layout(&project.root, &project.manifest),
+            &world.manifest.world_id,
+        );
+        TileStreamer::new(layout, &world.manifest, StreamingBudgets::default())
+    });
+    streaming.source = source;
+    // The pool is kept across worlds; `pump` drops results of jobs the old streamer issued.
+    if streaming.streamer.is_some() && streaming.pool.is_none() {
+        match TileLoadPool::new(TILE_LOAD_THREADS) {
+            Ok(pool) => streaming.pool = Some(pool),
+            Err(err) => warn!("Tile streaming disabled: {err:#}"),
+        }
+    }
+    true
+}
+
+/// The editor only pins whole tiles: it has no chunk selection to pin from, so the streamer's
+/// chunk pins are left to runtime callers.
+fn apply_streaming_controls(
+    streamer: &mut TileStreamer,
+    manifest: &WorldManifest,
+    prefs: &StreamingPrefs,
+    selected: Option<&SelectionTarget>,
+) {
+    let world_id = manifest.world_id.as_str();
+    streamer.set_pinned_tiles(prefs.pinned_tiles(world_id));
+    // Prefs are hand-editable; the commands never go past the cap.
+    let radius_tiles = prefs
+        .force_load_radius_tiles
+        .min(MAX_FORCE_LOAD_RADIUS_TILES);
+    streamer.set_force_load_radius(radius_tiles as f32 * manifest.world_spec.tile_size_meters);
+    // Props are not resolved to their tile here; only a selected tile biases streaming.
+    let edited_tile = match selected {
+        Some(SelectionTarget::Tile {
+            world_id: selected_world,
+            tile_id,
+            ..
+        }) if selected_world == world_id => Some(tile_id.coord),
+        _ => None,
+    };
+    streamer.set_edited_tile(edited_tile);
+}
+
+/// Moves the streaming focus with the camera, runs this frame's jobs and publishes every tracked
+/// tile's status to the overlay.
+pub fn update_tile_streaming(
+    controller: Res<ViewportCameraController>,
+    mode: Res<ViewportCameraMode>,
+    mut streaming: ResMut<EditorTileStreaming>,
+    mut overlay: ResMut<ViewportTileStreamingState>,
+) {
+    let streaming = &mut *streaming;
+    let (Some(streamer), Some(pool)) = (streaming.streamer.as_mut(), streaming.pool.as_ref())
+    else {
+        return;
+    };
+
+    let Some(focus) = camera_focus(&controller, *mode, streaming.source.as_ref()) else {
+        return;
+    };
+    streamer.update(focus);
+    streamer.pump(pool);
+    // No renderable is built from streamed tiles yet, so builds complete as they are handed out.
+    for job in streamer.take_build_jobs() {
+        streamer.mark_built(job.tile);
+    }
+    streamer.drain_transitions();
+
+    overlay.clear();
+    for (coord, tile) in streamer.tiles() {
+        overlay.set_tile_state(
+            coord,
+            tile_streaming_status(tile, streamer.is_pinned(coord)),
+        );
+    }
+}
+
+/// Streaming follows what the camera looks at, over the same area the overlays cover.
+fn camera_focus(
+    controller: &ViewportCameraController,
+    mode: ViewportCameraMode,
+    source: Option<&(PathBuf, WorldManifest)>,
+) -> Option<StreamingFocus> {
+    let tile_size = source?.1.world_spec.tile_size_meters;
+    if !tile_size.is_finite() || tile_size <= 0.0 {
+        return None;
+    }
+    let (center, reference_distance) = match mode {
+        ViewportCameraMode::Orbit => (controller.orbit_focus, controller.distance),
+        ViewportCameraMode::FreeFly => (controller.position, controller.position.y.abs().max(1.0)),
+    };
+    let forward = Vec2::new(-controller.yaw.sin(), -controller.yaw.cos());
+    Some(StreamingFocus {
+        center: [center.x, center.z],
+        load_radius: (reference_distance * 2.0).clamp(tile_size * 1.5, tile_size * 6.0),
+        view_direction: Some(forward.to_array()),
+    })
+}
+
+fn tile_streaming_status(tile: &StreamedTile, pinned: bool) -> TileStreamingStatus {
+    let phase = tile.state.phase();
+    TileStreamingStatus {
+        loaded: phase == TilePhase::Loaded,
+        built: phase == TilePhase::Built,
+        queued: phase == TilePhase::Loading && tile.is_queued(),
+        pending_load: phase == TilePhase::Loading && !tile.is_queued(),
+        error: tile.error.is_some(),
+        pinned,
+        dirty: false,
+    }
+}
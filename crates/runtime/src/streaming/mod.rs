@@ -0,0 +1,33 @@
+//! Streaming contracts (v0). Camera-centric tile loading with budgets.
+
+mod scheduler;
+mod tiles;
+mod workers;
+
+pub use tiles::{
+    decode_tile_data, read_tile_bytes, read_tile_data, StreamedTile, StreamingFocus, TileBuildJob,
+    TileData, TileDecodeJob, TileLoadRequest, TilePhase, TileState, TileStreamer, TileTransition,
+};
+pub use workers::{TileJobResult, TileLoadPool};
+
+#[derive(Debug, Clone, Copy)]
+pub struct StreamingBudgets {
+    /// Tiles loading, loaded or built at once; the least recently needed are evicted beyond it.
+    pub max_tiles_loaded: usize,
+    /// Build jobs handed out per frame. Builds are per tile for now, one job each.
+    pub max_chunk_mesh_builds_per_frame: usize,
+    pub max_io_requests_in_flight: usize,
+    /// Container decodes handed out per frame.
+    pub max_tile_decodes_per_frame: usize,
+}
+
+impl Default for StreamingBudgets {
+    fn default() -> Self {
+        Self {
+            max_tiles_loaded: 64,
+            max_chunk_mesh_builds_per_frame: 2,
+            max_io_requests_in_flight: 8,
+            max_tile_decodes_per_frame: 4,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Job ordering for the tile streamer. Lower priority values run first.
+
+use super::StreamingFocus;
+use foundation::ids::TileCoord;
+
+/// Extra distance, in tile sizes, a tile directly behind the view direction is treated as having.
+/// Tiles to the side get half of it; tiles ahead get none.
+const BEHIND_PENALTY_TILES: f32 = 2.0;
+
+/// Distance from `point` to the nearest point of the tile's footprint; 0 inside the tile.
+pub(super) fn tile_distance(tile: TileCoord, tile_size: f32, point: [f32; 2]) -> f32 {
+    let min_x = tile.x as f32 * tile_size;
+    let min_z = tile.y as f32 * tile_size;
+    let dx = (min_x - point[0])
+        .max(point[0] - (min_x + tile_size))
+        .max(0.0);
+    let dz = (min_z - point[1])
+        .max(point[1] - (min_z + tile_size))
+        .max(0.0);
+    (dx * dx + dz * dz).sqrt()
+}
+
+/// Orders a tile's jobs: its distance to the focus, plus a penalty for lying away from the view
+/// direction. The tile under the focus always comes first.
+pub(super) fn tile_priority(tile: TileCoord, tile_size: f32, focus: StreamingFocus) -> f32 {
+    let distance = tile_distance(tile, tile_size, focus.center);
+    let Some(forward) = focus.view_direction.and_then(normalize) else {
+        return distance;
+    };
+    if distance == 0.0 {
+        return 0.0;
+    }
+    let to_tile = [
+        (tile.x as f32 + 0.5) * tile_size - focus.center[0],
+        (tile.y as f32 + 0.5) * tile_size - focus.center[1],
+    ];
+    let Some(to_tile) = normalize(to_tile) else {
+        return distance;
+    };
+    let cos = forward[0] * to_tile[0] + forward[1] * to_tile[1];
+    distance + tile_size * BEHIND_PENALTY_TILES * (1.0 - cos) * 0.5
+}
+
+/// Pulls tiles near the tile being edited forward: a tile is never ordered after its distance to
+/// the edited tile's center, so the edited tile and its neighbours load first.
+pub(super) fn edit_biased_priority(
+    priority: f32,
+    tile: TileCoord,
+    tile_size: f32,
+    edited: Option<TileCoord>,
+) -> f32 {
+    let Some(edited) = edited else {
+        return priority;
+    };
+    let center = [
+        (edited.x as f32 + 0.5) * tile_size,
+        (edited.y as f32 + 0.5) * tile_size,
+    ];
+    priority.min(tile_distance(tile, tile_size, center))
+}
+
+/// Sorts `(priority, tile)` pairs so the most urgent comes first; ties go to the lower coordinate.
+pub(super) fn sort_by_priority<T>(jobs: &mut [(f32, TileCoord, T)]) {
+    jobs.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+}
+
+fn normalize(vector: [f32; 2]) -> Option<[f32; 2]> {
+    let length = (vector[0] * vector[0] + vector[1] * vector[1]).sqrt();
+    (length.is_finite() && length > f32::EPSILON).then(|| [vector[0] / length, vector[1] / length])
+}
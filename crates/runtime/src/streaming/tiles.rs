@@ -0,0 +1,833 @@
+//! Tile lifecycle: `Unloaded -> Loading -> Loaded(data) -> Built -> Unloading`.
+//!
+//! [`TileStreamer`] is UI-agnostic. The caller moves the focus point each frame, runs the jobs it
+//! hands out (reads, decodes, builds) and reports their results, and reacts to the reported
+//! transitions. Tiles are keyed by world tile coordinate; where region bounds overlap, the first
+//! region in the world manifest owns the tile.
+//!
+//! Jobs are handed out most urgent first (see [`TileStreamer::update`]) and capped by the
+//! [`StreamingBudgets`]: reads by the number in flight, decodes and builds per frame, and the
+//! number of resident tiles by evicting the least recently needed ones.
+//!
+//! Editors can keep tiles resident regardless of the focus: pinned tiles (or tiles with a pinned
+//! chunk) and tiles within the force-load radius are loaded wherever the focus is, even past
+//! `max_tiles_loaded`, and are never unloaded or evicted. Jobs near the tile being edited are
+//! handed out first.
+
+use super::scheduler::{edit_biased_priority, sort_by_priority, tile_distance, tile_priority};
+use super::StreamingBudgets;
+use anyhow::{bail, Context};
+use foundation::ids::{ChunkCoord, ChunkId, TileCoord, TileId};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use world::schema::{RegionManifest, WorldManifest};
+use world::storage::{list_tile_containers, tile_container_path, WorldLayout};
+use world::tile_container::{
+    decode_current_section, decode_hmap, decode_liqd, decode_prop, decode_wmap, HmapSection,
+    LiqdSection, PropSection, TileContainerReader, TileSectionTag, WmapSection,
+};
+
+/// Tiles are only unloaded this many tile sizes beyond the load radius, so a focus point moving
+/// back and forth across a tile edge does not reload the same tiles.
+const UNLOAD_MARGIN_TILES: f32 = 0.5;
+
+/// Source of [`TileStreamer::id`]s.
+static NEXT_STREAMER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Where streaming is centered, in world meters on the ground plane (x, z).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamingFocus {
+    pub center: [f32; 2],
+    /// Tiles whose footprint comes within this distance of `center` are loaded.
+    pub load_radius: f32,
+    /// Where the camera looks on the ground plane (x, z); need not be normalized. Tiles ahead
+    /// are loaded before tiles behind at the same distance.
+    pub view_direction: Option<[f32; 2]>,
+}
+
+/// Decoded sections of one tile. Sections the container lacks are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileData {
+    pub tile: TileCoord,
+    pub region_id: String,
+    pub hmap: Option<HmapSection>,
+    pub wmap: Option<WmapSection>,
+    pub liqd: Option<LiqdSection>,
+    pub prop: Option<PropSection>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TileState {
+    Unloaded,
+    /// Queued for, or in the middle of, reading and decoding its container.
+    Loading,
+    /// Data is available but nothing has been built from it yet.
+    Loaded(Arc<TileData>),
+    /// The consumer has built renderable state from the data.
+    Built(Arc<TileData>),
+    /// Out of range or evicted; the consumer should release what it built. The tile is dropped
+    /// on the next update (or once its in-flight job completes).
+    Unloading,
+}
+
+impl TileState {
+    pub fn phase(&self) -> TilePhase {
+        match self {
+            TileState::Unloaded => TilePhase::Unloaded,
+            TileState::Loading => TilePhase::Loading,
+            TileState::Loaded(_) => TilePhase::Loaded,
+            TileState::Built(_) => TilePhase::Built,
+            TileState::Unloading => TilePhase::Unloading,
+        }
+    }
+
+    pub fn data(&self) -> Option<&Arc<TileData>> {
+        match self {
+            TileState::Loaded(data) | TileState::Built(data) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+/// [`TileState`] without its data, for reporting transitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TilePhase {
+    Unloaded,
+    Loading,
+    Loaded,
+    Built,
+    Unloading,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileTransition {
+    pub tile: TileCoord,
+    pub from: TilePhase,
+    pub to: TilePhase,
+}
+
+/// A container read the caller should run and hand back through [`TileStreamer::complete_read`]
+/// (or, reading and decoding in one go, [`TileStreamer::complete_load`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileLoadRequest {
+    /// [`TileStreamer::id`] of the streamer that issued the request.
+    pub streamer: u64,
+    pub tile: TileCoord,
+    pub region_id: String,
+    pub path: PathBuf,
+}
+
+/// Container bytes the caller should decode and hand back through
+/// [`TileStreamer::complete_load`].
+#[derive(Debug, Clone)]
+pub struct TileDecodeJob {
+    /// [`TileStreamer::id`] of the streamer that issued the job.
+    pub streamer: u64,
+    pub tile: TileCoord,
+    pub region_id: String,
+    pub bytes: Arc<[u8]>,
+}
+
+/// A loaded tile the caller should build renderable state from, then report with
+/// [`TileStreamer::mark_built`].
+#[derive(Debug, Clone)]
+pub struct TileBuildJob {
+    pub tile: TileCoord,
+    pub data: Arc<TileData>,
+}
+
+/// Where a `Loading` tile is in the read/decode pipeline.
+#[derive(Debug, Clone)]
+enum LoadStage {
+    QueuedRead,
+    Reading,
+    QueuedDecode(Arc<[u8]>),
+    Decoding,
+}
+
+/// A tile the streamer is tracking.
+#[derive(Debug, Clone)]
+pub struct StreamedTile {
+    pub region_id: String,
+    pub path: PathBuf,
+    pub state: TileState,
+    /// Why the last load failed. Failed tiles stay `Unloaded` and are not read again until they
+    /// leave the range or [`TileStreamer::retry`] is called.
+    pub error: Option<String>,
+    /// `None` once no job of the tile is queued or in flight.
+    stage: Option<LoadStage>,
+    priority: f32,
+    /// Frame the tile was last within the load radius, pinned or force-loaded.
+    last_needed: u64,
+    /// Frame the tile was last handed out as a build job.
+    build_offered: u64,
+}
+
+impl StreamedTile {
+    /// Job order from the last update; lower runs first.
+    pub fn priority(&self) -> f32 {
+        self.priority
+    }
+
+    /// `Loading` and waiting for a read or decode slot, as opposed to being read or decoded.
+    pub fn is_queued(&self) -> bool {
+        matches!(
+            self.stage,
+            Some(LoadStage::QueuedRead | LoadStage::QueuedDecode(_))
+        )
+    }
+
+    fn job_in_flight(&self) -> bool {
+        matches!(self.stage, Some(LoadStage::Reading | LoadStage::Decoding))
+    }
+
+    fn is_resident(&self) -> bool {
+        matches!(
+            self.state.phase(),
+            TilePhase::Loading | TilePhase::Loaded | TilePhase::Built
+        )
+    }
+}
+
+pub struct TileStreamer {
+    id: u64,
+    layout: WorldLayout,
+    regions: Vec<RegionManifest>,
+    /// Tiles with a container file on disk, per region; see [`TileStreamer::refresh_tile_files`].
+    tile_files: BTreeMap<String, BTreeSet<TileCoord>>,
+    tile_size: f32,
+    budgets: StreamingBudgets,
+    tiles: BTreeMap<TileCoord, StreamedTile>,
+    transitions: Vec<TileTransition>,
+    pinned_tiles: BTreeSet<TileCoord>,
+    pinned_chunks: BTreeSet<ChunkId>,
+    force_load_radius: f32,
+    edited_tile: Option<TileCoord>,
+    frame: u64,
+    decodes_issued: usize,
+    builds_issued: usize,
+}
+
+impl TileStreamer {
+    pub fn new(layout: WorldLayout, manifest: &WorldManifest, budgets: StreamingBudgets) -> Self {
+        let mut streamer = Self {
+            id: NEXT_STREAMER_ID.fetch_add(1, Ordering::Relaxed),
+            layout,
+            regions: manifest.regions.clone(),
+            tile_files: BTreeMap::new(),
+            tile_size: manifest.world_spec.tile_size_meters,
+            budgets,
+            tiles: BTreeMap::new(),
+            transitions: Vec::new(),
+            pinned_tiles: BTreeSet::new(),
+            pinned_chunks: BTreeSet::new(),
+            force_load_radius: 0.0,
+            edited_tile: None,
+            frame: 0,
+            decodes_issued: 0,
+            builds_issued: 0,
+        };
+        streamer.refresh_tile_files();
+        streamer
+    }
+
+    /// Rescans every region's tiles dir. Only tiles found by the last scan are loaded, so call
+    /// this after tile files are created, restored or removed.
+    pub fn refresh_tile_files(&mut self) {
+        self.tile_files = self
+            .regions
+            .iter()
+            .map(|region| {
+                // An unreadable dir streams nothing; reads would fail on it anyway.
+                let tiles = list_tile_containers(&self.layout, &region.region_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|tile_id| tile_id.coord)
+                    .collect();
+                (region.region_id.clone(), tiles)
+            })
+            .collect();
+    }
+
+    /// Unique per streamer, so jobs issued by a streamer that was since replaced (say, for
+    /// another world) can be told apart from this one's.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn budgets(&self) -> StreamingBudgets {
+        self.budgets
+    }
+
+    /// Takes effect on the next update; lowering `max_tiles_loaded` evicts tiles then.
+    pub fn set_budgets(&mut self, budgets: StreamingBudgets) {
+        self.budgets = budgets;
+    }
+
+    /// Keeps `tile` resident wherever the focus is. Takes effect on the next update. Returns
+    /// `false` if it was already pinned.
+    pub fn pin_tile(&mut self, tile: TileCoord) -> bool {
+        self.pinned_tiles.insert(tile)
+    }
+
+    pub fn unpin_tile(&mut self, tile: TileCoord) -> bool {
+        self.pinned_tiles.remove(&tile)
+    }
+
+    /// Replaces the pinned tiles; chunk pins are kept.
+    pub fn set_pinned_tiles(&mut self, tiles: impl IntoIterator<Item = TileCoord>) {
+        self.pinned_tiles = tiles.into_iter().collect();
+    }
+
+    /// Tiles stream whole, so a pinned chunk keeps its tile resident until every pin on the tile
+    /// is removed.
+    pub fn pin_chunk(&mut self, chunk: ChunkId) -> bool {
+        self.pinned_chunks.insert(chunk)
+    }
+
+    pub fn unpin_chunk(&mut self, chunk: ChunkId) -> bool {
+        self.pinned_chunks.remove(&chunk)
+    }
+
+    pub fn clear_pins(&mut self) {
+        self.pinned_tiles.clear();
+        self.pinned_chunks.clear();
+    }
+
+    /// Whether the tile, or any of its chunks, is pinned.
+    pub fn is_pinned(&self, tile: TileCoord) -> bool {
+        is_pinned(&self.pinned_tiles, &self.pinned_chunks, tile)
+    }
+
+    pub fn pinned_tiles(&self) -> impl Iterator<Item = TileCoord> + '_ {
+        self.pinned_tiles.iter().copied()
+    }
+
+    pub fn pinned_chunks(&self) -> impl Iterator<Item = ChunkId> + '_ {
+        self.pinned_chunks.iter().copied()
+    }
+
+    pub fn force_load_radius(&self) -> f32 {
+        self.force_load_radius
+    }
+
+    /// Tiles within `meters` of the focus center are loaded whatever the focus's load radius,
+    /// and never evicted; `0` turns it off. Takes effect on the next update.
+    pub fn set_force_load_radius(&mut self, meters: f32) {
+        self.force_load_radius = if meters.is_finite() {
+            meters.max(0.0)
+        } else {
+            0.0
+        };
+    }
+
+    pub fn edited_tile(&self) -> Option<TileCoord> {
+        self.edited_tile
+    }
+
+    /// The tile being edited; it and its neighbours are read, decoded and built first. Takes
+    /// effect on the next update.
+    pub fn set_edited_tile(&mut self, tile: Option<TileCoord>) {
+        self.edited_tile = tile;
+    }
+
+    /// Starts a frame: re-prioritizes every tile for the new focus, unloads tiles that left the
+    /// range (cancelling their queued jobs), evicts the least recently needed tiles to stay
+    /// within `max_tiles_loaded` or make room for needed ones, and queues reads for the most
+    /// urgent missing tiles. Tiles without a `.tile` file are empty and never tracked.
+    pub fn update(&mut self, focus: StreamingFocus) {
+        if !self.tile_size.is_finite() || self.tile_size <= 0.0 {
+            return;
+        }
+        self.frame += 1;
+        self.decodes_issued = 0;
+        self.builds_issued = 0;
+        let focus = StreamingFocus {
+            load_radius: focus.load_radius.max(self.force_load_radius),
+            ..focus
+        };
+        let unload_radius = focus.load_radius + self.tile_size * UNLOAD_MARGIN_TILES;
+
+        let mut dropped = Vec::new();
+        for (coord, tile) in &mut self.tiles {
+            let distance = tile_distance(*coord, self.tile_size, focus.center);
+            let protected = is_pinned(&self.pinned_tiles, &self.pinned_chunks, *coord)
+                || is_forced(distance, self.force_load_radius);
+            tile.priority = edit_biased_priority(
+                tile_priority(*coord, self.tile_size, focus),
+                *coord,
+                self.tile_size,
+                self.edited_tile,
+            );
+            if distance <= focus.load_radius || protected {
+                tile.last_needed = self.frame;
+            }
+            let out_of_range = distance > unload_radius && !protected;
+            match tile.state.phase() {
+                TilePhase::Unloading if !tile.job_in_flight() => dropped.push(*coord),
+                // A failed tile out of range is forgotten, so it is read again on return.
+                TilePhase::Unloaded if out_of_range => dropped.push(*coord),
+                TilePhase::Loading | TilePhase::Loaded | TilePhase::Built if out_of_range => {
+                    begin_unload(*coord, tile, &mut self.transitions);
+                }
+                _ => {}
+            }
+        }
+        for coord in dropped {
+            if let Some(tile) = self.tiles.remove(&coord) {
+                if tile.state.phase() == TilePhase::Unloading {
+                    self.transitions.push(TileTransition {
+                        tile: coord,
+                        from: TilePhase::Unloading,
+                        to: TilePhase::Unloaded,
+                    });
+                }
+            }
+        }
+
+        let candidates: Vec<(f32, TileCoord, (String, PathBuf))> = self
+            .missing_tiles(focus)
+            .into_iter()
+            .filter(|(_, coord, (region_id, _))| {
+                self.tile_files
+                    .get(region_id)
+                    .is_some_and(|tiles| tiles.contains(coord))
+            })
+            .collect();
+        let resident = self.evict(candidates.len(), focus.center);
+
+        // Protected tiles are admitted past `max_tiles_loaded`; the rest fill what room is left.
+        let (mut admitted, optional): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|(_, coord, _)| self.is_protected(*coord, focus.center));
+        let room = self
+            .budgets
+            .max_tiles_loaded
+            .saturating_sub(resident + admitted.len());
+        admitted.extend(optional.into_iter().take(room));
+        sort_by_priority(&mut admitted);
+        for (priority, coord, (region_id, path)) in admitted {
+            self.tiles.insert(
+                coord,
+                StreamedTile {
+                    region_id,
+                    path,
+                    state: TileState::Loading,
+                    error: None,
+                    stage: Some(LoadStage::QueuedRead),
+                    priority,
+                    last_needed: self.frame,
+                    build_offered: 0,
+                },
+            );
+            self.transitions.push(TileTransition {
+                tile: coord,
+                from: TilePhase::Unloaded,
+                to: TilePhase::Loading,
+            });
+        }
+    }
+
+    /// Queued reads, most urgent first, up to `max_io_requests_in_flight` minus the reads still
+    /// outstanding.
+    pub fn take_load_requests(&mut self) -> Vec<TileLoadRequest> {
+        let in_flight = self
+            .tiles
+            .values()
+            .filter(|tile| matches!(tile.stage, Some(LoadStage::Reading)))
+            .count();
+        let slots = self
+            .budgets
+            .max_io_requests_in_flight
+            .saturating_sub(in_flight);
+        self.take_jobs(slots, |tile| {
+            matches!(tile.stage, Some(LoadStage::QueuedRead))
+        })
+        .into_iter()
+        .map(|coord| {
+            let tile = self.tiles.get_mut(&coord).expect("job tile is tracked");
+            tile.stage = Some(LoadStage::Reading);
+            TileLoadRequest {
+                streamer: self.id,
+                tile: coord,
+                region_id: tile.region_id.clone(),
+                path: tile.path.clone(),
+            }
+        })
+        .collect()
+    }
+
+    /// Records a finished read and queues the bytes for decoding. Results for tiles that left
+    /// the range meanwhile, or that are not being read, are discarded.
+    pub fn complete_read(&mut self, tile: TileCoord, result: anyhow::Result<Arc<[u8]>>) {
+        let Some(entry) = self.tiles.get_mut(&tile) else {
+            return;
+        };
+        if !matches!(entry.stage, Some(LoadStage::Reading)) {
+            return;
+        }
+        entry.stage = None;
+        if entry.state.phase() != TilePhase::Loading {
+            return;
+        }
+        match result {
+            Ok(bytes) => entry.stage = Some(LoadStage::QueuedDecode(bytes)),
+            Err(err) => fail_load(tile, entry, &err, &mut self.transitions),
+        }
+    }
+
+    /// Queued decodes, most urgent first, up to what is left of `max_tile_decodes_per_frame`.
+    pub fn take_decode_jobs(&mut self) -> Vec<TileDecodeJob> {
+        let slots = self
+            .budgets
+            .max_tile_decodes_per_frame
+            .saturating_sub(self.decodes_issued);
+        let jobs = self.take_jobs(slots, |tile| {
+            matches!(tile.stage, Some(LoadStage::QueuedDecode(_)))
+        });
+        self.decodes_issued += jobs.len();
+        jobs.into_iter()
+            .map(|coord| {
+                let tile = self.tiles.get_mut(&coord).expect("job tile is tracked");
+                let Some(LoadStage::QueuedDecode(bytes)) = tile.stage.replace(LoadStage::Decoding)
+                else {
+                    unreachable!("only queued decodes are selected");
+                };
+                TileDecodeJob {
+                    streamer: self.id,
+                    tile: coord,
+                    region_id: tile.region_id.clone(),
+                    bytes,
+                }
+            })
+            .collect()
+    }
+
+    /// Records a finished decode (or a combined read and decode). Results for tiles that left
+    /// the range meanwhile, or that are not being read or decoded, are discarded.
+    pub fn complete_load(&mut self, tile: TileCoord, result: anyhow::Result<TileData>) {
+        let Some(entry) = self.tiles.get_mut(&tile) else {
+            return;
+        };
+        if !entry.job_in_flight() {
+            return;
+        }
+        entry.stage = None;
+        if entry.state.phase() != TilePhase::Loading {
+            return;
+        }
+        match result {
+            Ok(data) => {
+                entry.state = TileState::Loaded(Arc::new(data));
+                entry.error = None;
+                self.transitions.push(TileTransition {
+                    tile,
+                    from: TilePhase::Loading,
+                    to: TilePhase::Loaded,
+                });
+            }
+            Err(err) => fail_load(tile, entry, &err, &mut self.transitions),
+        }
+    }
+
+    /// Loaded tiles to build, most urgent first, up to what is left of
+    /// `max_chunk_mesh_builds_per_frame` (one per tile). A tile handed out but not built is
+    /// offered again next frame.
+    pub fn take_build_jobs(&mut self) -> Vec<TileBuildJob> {
+        let slots = self
+            .budgets
+            .max_chunk_mesh_builds_per_frame
+            .saturating_sub(self.builds_issued);
+        let frame = self.frame;
+        let jobs = self.take_jobs(slots, |tile| {
+            tile.state.phase() == TilePhase::Loaded && tile.build_offered != frame
+        });
+        self.builds_issued += jobs.len();
+        jobs.into_iter()
+            .map(|coord| {
+                let tile = self.tiles.get_mut(&coord).expect("job tile is tracked");
+                tile.build_offered = frame;
+                TileBuildJob {
+                    tile: coord,
+                    data: tile.state.data().expect("loaded tiles have data").clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Runs the reads and decodes allowed this frame on the calling thread.
+    pub fn load_pending_blocking(&mut self) {
+        for request in self.take_load_requests() {
+            let result = read_tile_bytes(&request);
+            self.complete_read(request.tile, result);
+        }
+        for job in self.take_decode_jobs() {
+            let result = decode_tile_data(&job);
+            self.complete_load(job.tile, result);
+        }
+    }
+
+    /// Moves a `Loaded` tile to `Built`. Returns `false` if the tile is not `Loaded`.
+    pub fn mark_built(&mut self, tile: TileCoord) -> bool {
+        let Some(entry) = self.tiles.get_mut(&tile) else {
+            return false;
+        };
+        let TileState::Loaded(data) = &entry.state else {
+            return false;
+        };
+        entry.state = TileState::Built(data.clone());
+        self.transitions.push(TileTransition {
+            tile,
+            from: TilePhase::Loaded,
+            to: TilePhase::Built,
+        });
+        true
+    }
+
+    /// Forgets a failed load so the next update queues it again. Returns `false` if the tile
+    /// has no error.
+    pub fn retry(&mut self, tile: TileCoord) -> bool {
+        let failed = self.tiles.get(&tile).is_some_and(|entry| {
+            entry.error.is_some() && entry.state.phase() == TilePhase::Unloaded
+        });
+        if failed {
+            self.tiles.remove(&tile);
+        }
+        failed
+    }
+
+    /// Transitions since the last call, in the order they happened.
+    pub fn drain_transitions(&mut self) -> Vec<TileTransition> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    pub fn tile(&self, tile: TileCoord) -> Option<&StreamedTile> {
+        self.tiles.get(&tile)
+    }
+
+    /// Tracked tiles in coordinate order.
+    pub fn tiles(&self) -> impl Iterator<Item = (TileCoord, &StreamedTile)> {
+        self.tiles.iter().map(|(coord, tile)| (*coord, tile))
+    }
+
+    /// Up to `slots` tiles matching `filter`, most urgent first.
+    fn take_jobs(&self, slots: usize, filter: impl Fn(&StreamedTile) -> bool) -> Vec<TileCoord> {
+        if slots == 0 {
+            return Vec::new();
+        }
+        let mut jobs: Vec<(f32, TileCoord, ())> = self
+            .tiles
+            .iter()
+            .filter(|(_, tile)| filter(tile))
+            .map(|(coord, tile)| (tile.priority, *coord, ()))
+            .collect();
+        sort_by_priority(&mut jobs);
+        jobs.into_iter()
+            .take(slots)
+            .map(|(_, coord, ())| coord)
+            .collect()
+    }
+
+    /// Unloads resident tiles, least recently needed (then least urgent) first: as many as
+    /// exceed `max_tiles_loaded`, and as many tiles no longer needed as it takes to make room
+    /// for `wanted` new ones. Protected tiles count as resident but are never evicted. Returns
+    /// how many tiles stay resident.
+    fn evict(&mut self, wanted: usize, center: [f32; 2]) -> usize {
+        let resident = self
+            .tiles
+            .values()
+            .filter(|tile| tile.is_resident())
+            .count();
+        let mut evictable: Vec<(u64, f32, TileCoord)> = self
+            .tiles
+            .iter()
+            .filter(|(coord, tile)| tile.is_resident() && !self.is_protected(**coord, center))
+            .map(|(coord, tile)| (tile.last_needed, tile.priority, *coord))
+            .collect();
+        let max = self.budgets.max_tiles_loaded;
+        let overflow = resident.saturating_sub(max);
+        let room = (resident + wanted).saturating_sub(max);
+        let unneeded = evictable
+            .iter()
+            .filter(|(last_needed, _, _)| *last_needed < self.frame)
+            .count();
+        let evicted = overflow.max(room.min(unneeded)).min(evictable.len());
+
+        evictable.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.total_cmp(&a.1)).then(a.2.cmp(&b.2)));
+        for (_, _, coord) in evictable.iter().take(evicted) {
+            if let Some(tile) = self.tiles.get_mut(coord) {
+                begin_unload(*coord, tile, &mut self.transitions);
+            }
+        }
+        resident - evicted
+    }
+
+    /// Pinned, or within the force-load radius of `center`.
+    fn is_protected(&self, tile: TileCoord, center: [f32; 2]) -> bool {
+        self.is_pinned(tile)
+            || is_forced(
+                tile_distance(tile, self.tile_size, center),
+                self.force_load_radius,
+            )
+    }
+
+    /// Untracked tiles of any region within the load radius or pinned, most urgent first, with
+    /// their region and container path.
+    fn missing_tiles(&self, focus: StreamingFocus) -> Vec<(f32, TileCoord, (String, PathBuf))> {
+        let tile_size = self.tile_size;
+        let min_x = ((focus.center[0] - focus.load_radius) / tile_size).floor() as i32;
+        let max_x = ((focus.center[0] + focus.load_radius) / tile_size).floor() as i32;
+        let min_y = ((focus.center[1] - focus.load_radius) / tile_size).floor() as i32;
+        let max_y = ((focus.center[1] + focus.load_radius) / tile_size).floor() as i32;
+
+        let mut owners: BTreeMap<TileCoord, &str> = BTreeMap::new();
+        for region in &self.regions {
+            let bounds = region.bounds;
+            for y in min_y.max(bounds.min_y)..=max_y.min(bounds.max_y) {
+                for x in min_x.max(bounds.min_x)..=max_x.min(bounds.max_x) {
+                    owners
+                        .entry(TileCoord { x, y })
+                        .or_insert(&region.region_id);
+                }
+            }
+        }
+        owners
+            .retain(|coord, _| tile_distance(*coord, tile_size, focus.center) <= focus.load_radius);
+        let pinned = self
+            .pinned_tiles
+            .iter()
+            .copied()
+            .chain(self.pinned_chunks.iter().map(|chunk| chunk.tile.coord));
+        for coord in pinned {
+            let owner = self.regions.iter().find(|region| {
+                let bounds = region.bounds;
+                (bounds.min_x..=bounds.max_x).contains(&coord.x)
+                    && (bounds.min_y..=bounds.max_y).contains(&coord.y)
+            });
+            if let Some(region) = owner {
+                owners.entry(coord).or_insert(&region.region_id);
+            }
+        }
+
+        let mut missing: Vec<(f32, TileCoord, (String, PathBuf))> = owners
+            .into_iter()
+            .filter(|(coord, _)| !self.tiles.contains_key(coord))
+            .map(|(coord, region_id)| {
+                let path = tile_container_path(&self.layout, region_id, TileId { coord });
+                let priority = edit_biased_priority(
+                    tile_priority(coord, tile_size, focus),
+                    coord,
+                    tile_size,
+                    self.edited_tile,
+                );
+                (priority, coord, (region_id.to_string(), path))
+            })
+            .collect();
+        sort_by_priority(&mut missing);
+        missing
+    }
+}
+
+fn is_pinned(tiles: &BTreeSet<TileCoord>, chunks: &BTreeSet<ChunkId>, tile: TileCoord) -> bool {
+    let tile = TileId { coord: tile };
+    let first = ChunkId {
+        tile,
+        coord: ChunkCoord { x: 0, y: 0 },
+    };
+    let last = ChunkId {
+        tile,
+        coord: ChunkCoord {
+            x: u16::MAX,
+            y: u16::MAX,
+        },
+    };
+    tiles.contains(&tile.coord) || chunks.range(first..=last).next().is_some()
+}
+
+fn is_forced(distance: f32, force_load_radius: f32) -> bool {
+    force_load_radius > 0.0 && distance <= force_load_radius
+}
+
+/// Reads a tile container's bytes.
+pub fn read_tile_bytes(request: &TileLoadRequest) -> anyhow::Result<Arc<[u8]>> {
+    let bytes =
+        std::fs::read(&request.path).with_context(|| format!("read tile {:?}", request.path))?;
+    Ok(bytes.into())
+}
+
+/// Decodes the sections of a tile container read by [`read_tile_bytes`].
+pub fn decode_tile_data(job: &TileDecodeJob) -> anyhow::Result<TileData> {
+    let reader = TileContainerReader::from_bytes(job.bytes.clone())?;
+    let header_tile = TileCoord {
+        x: reader.header.tile_x,
+        y: reader.header.tile_y,
+    };
+    if header_tile != job.tile {
+        bail!(
+            "tile header says ({}, {}), expected ({}, {})",
+            header_tile.x,
+            header_tile.y,
+            job.tile.x,
+            job.tile.y
+        );
+    }
+    let present = |tag| reader.section(tag).is_some();
+    Ok(TileData {
+        tile: job.tile,
+        region_id: job.region_id.clone(),
+        hmap: present(TileSectionTag::HMAP)
+            .then(|| decode_hmap(&decode_current_section(&reader, TileSectionTag::HMAP)?))
+            .transpose()?,
+        wmap: present(TileSectionTag::WMAP)
+            .then(|| decode_wmap(&decode_current_section(&reader, TileSectionTag::WMAP)?))
+            .transpose()?,
+        liqd: present(TileSectionTag::LIQD)
+            .then(|| decode_liqd(&decode_current_section(&reader, TileSectionTag::LIQD)?))
+            .transpose()?,
+        prop: present(TileSectionTag::PROP)
+            .then(|| decode_prop(&decode_current_section(&reader, TileSectionTag::PROP)?))
+            .transpose()?,
+    })
+}
+
+/// Reads and decodes one tile container.
+pub fn read_tile_data(request: &TileLoadRequest) -> anyhow::Result<TileData> {
+    decode_tile_data(&TileDecodeJob {
+        streamer: request.streamer,
+        tile: request.tile,
+        region_id: request.region_id.clone(),
+        bytes: read_tile_bytes(request)?,
+    })
+}
+
+/// Moves a resident tile to `Unloading`. Queued jobs are cancelled; one in flight keeps the
+/// tile around until it completes.
+fn begin_unload(coord: TileCoord, tile: &mut StreamedTile, transitions: &mut Vec<TileTransition>) {
+    let from = tile.state.phase();
+    tile.state = TileState::Unloading;
+    if !tile.job_in_flight() {
+        tile.stage = None;
+    }
+    transitions.push(TileTransition {
+        tile: coord,
+        from,
+        to: TilePhase::Unloading,
+    });
+}
+
+fn fail_load(
+    coord: TileCoord,
+    tile: &mut StreamedTile,
+    err: &anyhow::Error,
+    transitions: &mut Vec<TileTransition>,
+) {
+    tile.state = TileState::Unloaded;
+    tile.error = Some(format!("{err:#}"));
+    transitions.push(TileTransition {
+        tile: coord,
+        from: TilePhase::Loading,
+        to: TilePhase::Unloaded,
+    });
+}
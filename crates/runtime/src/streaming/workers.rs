@@ -0,0 +1,185 @@
+//! Background threads for tile reads and decodes, so the frame loop never waits on disk IO.
+//!
+//! [`TileLoadPool`] runs the read and decode jobs the [`TileStreamer`] hands out on its own
+//! threads; [`TileStreamer::pump`] submits new jobs and applies finished ones once per frame.
+
+use super::tiles::{
+    decode_tile_data, read_tile_bytes, TileData, TileDecodeJob, TileLoadRequest, TileStreamer,
+};
+use foundation::ids::TileCoord;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::JoinHandle;
+
+enum Job {
+    Read(TileLoadRequest),
+    Decode(TileDecodeJob),
+}
+
+/// A finished job, tagged with the [`TileStreamer::id`] that issued it.
+pub enum TileJobResult {
+    Read {
+        streamer: u64,
+        tile: TileCoord,
+        result: anyhow::Result<Arc<[u8]>>,
+    },
+    Decoded {
+        streamer: u64,
+        tile: TileCoord,
+        result: anyhow::Result<TileData>,
+    },
+}
+
+impl TileJobResult {
+    pub fn tile(&self) -> TileCoord {
+        match self {
+            TileJobResult::Read { tile, .. } | TileJobResult::Decoded { tile, .. } => *tile,
+        }
+    }
+
+    pub fn streamer(&self) -> u64 {
+        match self {
+            TileJobResult::Read { streamer, .. } | TileJobResult::Decoded { streamer, .. } => {
+                *streamer
+            }
+        }
+    }
+}
+
+pub struct TileLoadPool {
+    jobs: Option<Sender<Job>>,
+    /// Behind a lock only so the pool is `Sync` and can live in shared state; `poll` is the sole
+    /// reader.
+    results: Mutex<Receiver<TileJobResult>>,
+    in_flight: Arc<AtomicUsize>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl TileLoadPool {
+    /// Starts `threads` workers; `0` uses the available parallelism.
+    pub fn new(threads: usize) -> anyhow::Result<Self> {
+        let threads = match threads {
+            0 => std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+            threads => threads,
+        };
+        let (job_sender, job_receiver) = channel::<Job>();
+        let (result_sender, results) = channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+
+        let mut workers = Vec::with_capacity(threads);
+        for index in 0..threads {
+            let jobs = Arc::clone(&job_receiver);
+            let results = result_sender.clone();
+            let in_flight = Arc::clone(&in_flight);
+            let worker = std::thread::Builder::new()
+                .name(format!("tile-load-{index}"))
+                .spawn(move || loop {
+                    // The lock is released as soon as a job is taken.
+                    let job = jobs
+                        .lock()
+                        .map_err(|_| ())
+                        .and_then(|jobs| jobs.recv().map_err(|_| ()));
+                    let Ok(job) = job else {
+                        break;
+                    };
+                    let result = match job {
+                        Job::Read(request) => TileJobResult::Read {
+                            streamer: request.streamer,
+                            tile: request.tile,
+                            result: read_tile_bytes(&request),
+                        },
+                        Job::Decode(job) => TileJobResult::Decoded {
+                            streamer: job.streamer,
+                            tile: job.tile,
+                            result: decode_tile_data(&job),
+                        },
+                    };
+                    // Sent before the count drops, so `in_flight() == 0` means `poll` sees it.
+                    let sent = results.send(result);
+                    in_flight.fetch_sub(1, Ordering::AcqRel);
+                    if sent.is_err() {
+                        break;
+                    }
+                })?;
+            workers.push(worker);
+        }
+
+        Ok(Self {
+            jobs: Some(job_sender),
+            results: Mutex::new(results),
+            in_flight,
+            workers,
+        })
+    }
+
+    pub fn submit_read(&self, request: TileLoadRequest) {
+        self.submit(Job::Read(request));
+    }
+
+    pub fn submit_decode(&self, job: TileDecodeJob) {
+        self.submit(Job::Decode(job));
+    }
+
+    /// Finished jobs, without waiting.
+    pub fn poll(&self) -> Vec<TileJobResult> {
+        let results = self.results.lock().unwrap_or_else(PoisonError::into_inner);
+        results.try_iter().collect()
+    }
+
+    /// Jobs submitted whose result has not been produced yet.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    fn submit(&self, job: Job) {
+        let Some(jobs) = &self.jobs else {
+            return;
+        };
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        if jobs.send(job).is_err() {
+            self.in_flight.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+impl Drop for TileLoadPool {
+    /// Lets the workers finish the submitted jobs and exit; their results are discarded.
+    fn drop(&mut self) {
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl TileStreamer {
+    /// Applies the pool's finished jobs, then submits the reads and decodes this frame's budgets
+    /// allow. Call once per frame after [`update`](Self::update). Returns how many results were
+    /// applied.
+    ///
+    /// A pool can outlive the streamer feeding it: results of jobs another streamer issued are
+    /// discarded, so a streamer replaced for another world leaves nothing behind. Two streamers
+    /// must not pump one pool at the same time.
+    pub fn pump(&mut self, pool: &TileLoadPool) -> usize {
+        let mut applied = 0;
+        for result in pool.poll() {
+            if result.streamer() != self.id() {
+                continue;
+            }
+            applied += 1;
+            match result {
+                TileJobResult::Read { tile, result, .. } => self.complete_read(tile, result),
+                TileJobResult::Decoded { tile, result, .. } => self.complete_load(tile, result),
+            }
+        }
+        for request in self.take_load_requests() {
+            pool.submit_read(request);
+        }
+        for job in self.take_decode_jobs() {
+            pool.submit_decode(job);
+        }
+        applied
+    }
+}
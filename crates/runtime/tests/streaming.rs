@@ -0,0 +1,627 @@
+use runtime::streaming::{
+    read_tile_data, StreamingBudgets, StreamingFocus, TileLoadPool, TilePhase, TileStreamer,
+    TileTransition,
+};
+use tempfile::tempdir;
+use world::schema::{ProjectManifest, RegionBounds, RegionManifest, WorldManifest, WorldSpec};
+use world::storage::{
+    create_project, create_world, save_tile_stub, tile_container_path, LiquidsMask, LiquidsMeta,
+    PropsInstances, TerrainHeight, TileMeta, TileStub,
+};
+use world::tile_container::world_spec_hash::DEFAULT_WORLD_SPEC;
+use world::{ChunkCoord, ChunkId, TileCoord, TileId};
+
+fn tile(x: i32) -> TileCoord {
+    TileCoord { x, y: 0 }
+}
+
+fn transition(x: i32, from: TilePhase, to: TilePhase) -> TileTransition {
+    TileTransition {
+        tile: tile(x),
+        from,
+        to,
+    }
+}
+
+#[test]
+fn tiles_stream_in_and_out_around_the_focus() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            tile_size_meters: 64.0,
+            heightfield_samples: 2,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 3, 0),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let save_tile = |x: i32| {
+        let tile_id = TileId { coord: tile(x) };
+        let stub = TileStub {
+            meta: TileMeta::new(tile_id),
+            terrain: TerrainHeight::new(2, vec![x as f32; 4]),
+            liquids_mask: LiquidsMask::new(2, vec![0; 4]),
+            liquids_meta: LiquidsMeta::new(Vec::new()),
+            props: PropsInstances::new(Vec::new()),
+        };
+        save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+            .expect("save tile stub");
+    };
+    save_tile(0);
+    save_tile(1);
+    // Tile 2 has no file (an empty tile); tile 3 is corrupt.
+    let corrupt = tile_container_path(&world_layout, "region_0", TileId { coord: tile(3) });
+    std::fs::write(&corrupt, b"not a tile").expect("write corrupt tile");
+
+    let mut streamer = TileStreamer::new(
+        world_layout.clone(),
+        &world_manifest,
+        StreamingBudgets::default(),
+    );
+    let near_start = StreamingFocus {
+        center: [32.0, 32.0],
+        load_radius: 70.0,
+        view_direction: None,
+    };
+    streamer.update(near_start);
+    let requests = streamer.take_load_requests();
+    let requested: Vec<TileCoord> = requests.iter().map(|request| request.tile).collect();
+    for request in &requests {
+        streamer.complete_load(request.tile, read_tile_data(request));
+    }
+    assert_eq!(requested, vec![tile(0), tile(1)]);
+    assert_eq!(
+        streamer.drain_transitions(),
+        vec![
+            transition(0, TilePhase::Unloaded, TilePhase::Loading),
+            transition(1, TilePhase::Unloaded, TilePhase::Loading),
+            transition(0, TilePhase::Loading, TilePhase::Loaded),
+            transition(1, TilePhase::Loading, TilePhase::Loaded),
+        ]
+    );
+    let data = streamer.tile(tile(1)).and_then(|t| t.state.data()).unwrap();
+    assert_eq!(data.hmap.as_ref().unwrap().samples, vec![1.0; 4]);
+    assert!(streamer.mark_built(tile(0)));
+    assert!(!streamer.mark_built(tile(0)));
+    assert_eq!(
+        streamer.tile(tile(0)).unwrap().state.phase(),
+        TilePhase::Built
+    );
+    streamer.drain_transitions();
+
+    // Tile 1 is past the load radius but within the unload margin, so it stays.
+    let near_end = StreamingFocus {
+        center: [224.0, 32.0],
+        load_radius: 70.0,
+        view_direction: None,
+    };
+    streamer.update(near_end);
+    streamer.load_pending_blocking();
+    assert_eq!(
+        streamer.drain_transitions(),
+        vec![
+            transition(0, TilePhase::Built, TilePhase::Unloading),
+            transition(3, TilePhase::Unloaded, TilePhase::Loading),
+            transition(3, TilePhase::Loading, TilePhase::Unloaded),
+        ]
+    );
+    assert_eq!(
+        streamer.tile(tile(1)).unwrap().state.phase(),
+        TilePhase::Loaded
+    );
+    assert!(streamer.tile(tile(2)).is_none());
+    assert!(streamer.tile(tile(3)).unwrap().error.is_some());
+
+    // Unloading tiles are dropped on the next update; failed tiles are not read again.
+    streamer.update(near_end);
+    assert!(streamer.take_load_requests().is_empty());
+    assert_eq!(
+        streamer.drain_transitions(),
+        vec![transition(0, TilePhase::Unloading, TilePhase::Unloaded)]
+    );
+    assert!(streamer.tile(tile(0)).is_none());
+
+    assert!(streamer.retry(tile(3)));
+    assert!(!streamer.retry(tile(1)));
+    streamer.update(near_end);
+    assert_eq!(streamer.take_load_requests().len(), 1);
+
+    // Tile files are scanned once; a file written since is only streamed after a refresh.
+    save_tile(2);
+    streamer.update(near_end);
+    assert!(streamer.tile(tile(2)).is_none());
+    streamer.refresh_tile_files();
+    streamer.update(near_end);
+    let requested: Vec<TileCoord> = streamer
+        .take_load_requests()
+        .iter()
+        .map(|request| request.tile)
+        .collect();
+    assert_eq!(requested, vec![tile(2)]);
+}
+
+#[test]
+fn reads_respect_budgets_and_out_of_range_results_are_discarded() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            tile_size_meters: 64.0,
+            heightfield_samples: 2,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 3, 0),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    for x in 0..4 {
+        let tile_id = TileId { coord: tile(x) };
+        let stub = TileStub {
+            meta: TileMeta::new(tile_id),
+            terrain: TerrainHeight::new(2, vec![0.0; 4]),
+            liquids_mask: LiquidsMask::new(2, vec![0; 4]),
+            liquids_meta: LiquidsMeta::new(Vec::new()),
+            props: PropsInstances::new(Vec::new()),
+        };
+        save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+            .expect("save tile stub");
+    }
+
+    let budgets = StreamingBudgets {
+        max_tiles_loaded: 3,
+        max_io_requests_in_flight: 1,
+        ..StreamingBudgets::default()
+    };
+    let mut streamer = TileStreamer::new(world_layout, &world_manifest, budgets);
+    let everything = StreamingFocus {
+        center: [0.0, 32.0],
+        load_radius: 1000.0,
+        view_direction: None,
+    };
+    for expected in 0..3 {
+        streamer.update(everything);
+        assert!(streamer.tile(tile(expected)).unwrap().is_queued());
+        let requests = streamer.take_load_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].tile, tile(expected));
+        assert!(!streamer.tile(tile(expected)).unwrap().is_queued());
+        if expected == 0 {
+            // Results for tiles whose job was never handed out are ignored, so they neither
+            // free a read slot nor skip the queue.
+            streamer.complete_read(tile(1), Ok(Vec::new().into()));
+            streamer.complete_load(tile(1), Err(anyhow::anyhow!("stray result")));
+            let queued = streamer.tile(tile(1)).unwrap();
+            assert!(queued.is_queued());
+            assert!(queued.error.is_none());
+            assert!(streamer.take_load_requests().is_empty());
+        }
+        streamer.update(everything);
+        assert!(streamer.take_load_requests().is_empty());
+        streamer.complete_load(requests[0].tile, read_tile_data(&requests[0]));
+    }
+    // Three tiles are resident; the fourth waits for room.
+    streamer.update(everything);
+    assert!(streamer.take_load_requests().is_empty());
+
+    let far = StreamingFocus {
+        center: [-1000.0, 32.0],
+        load_radius: 10.0,
+        view_direction: None,
+    };
+    streamer.update(far);
+    streamer.update(far);
+    assert_eq!(streamer.tiles().count(), 0);
+    streamer.drain_transitions();
+
+    // A read that completes after its tile left the range is discarded.
+    streamer.update(everything);
+    let requests = streamer.take_load_requests();
+    assert_eq!(requests[0].tile, tile(0));
+    streamer.update(far);
+    streamer.update(far);
+    assert_eq!(
+        streamer.tile(tile(0)).unwrap().state.phase(),
+        TilePhase::Unloading
+    );
+    streamer.complete_load(tile(0), read_tile_data(&requests[0]));
+    streamer.update(far);
+    assert_eq!(streamer.tiles().count(), 0);
+    let tile_0: Vec<TileTransition> = streamer
+        .drain_transitions()
+        .into_iter()
+        .filter(|transition| transition.tile == tile(0))
+        .collect();
+    assert_eq!(
+        tile_0,
+        vec![
+            transition(0, TilePhase::Unloaded, TilePhase::Loading),
+            transition(0, TilePhase::Loading, TilePhase::Unloading),
+            transition(0, TilePhase::Unloading, TilePhase::Unloaded),
+        ]
+    );
+}
+
+#[test]
+fn jobs_follow_the_view_direction_and_evict_least_recently_needed_tiles() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            tile_size_meters: 64.0,
+            heightfield_samples: 2,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 3, 0),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    for x in 0..4 {
+        let tile_id = TileId { coord: tile(x) };
+        let stub = TileStub {
+            meta: TileMeta::new(tile_id),
+            terrain: TerrainHeight::new(2, vec![0.0; 4]),
+            liquids_mask: LiquidsMask::new(2, vec![0; 4]),
+            liquids_meta: LiquidsMeta::new(Vec::new()),
+            props: PropsInstances::new(Vec::new()),
+        };
+        save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+            .expect("save tile stub");
+    }
+
+    let budgets = StreamingBudgets {
+        max_tiles_loaded: 2,
+        max_chunk_mesh_builds_per_frame: 1,
+        ..StreamingBudgets::default()
+    };
+    let mut streamer = TileStreamer::new(world_layout, &world_manifest, budgets);
+    // Tiles 0 and 2 are equally close, but the camera looks towards +x.
+    let looking_east = |x: f32| StreamingFocus {
+        center: [x, 32.0],
+        load_radius: 40.0,
+        view_direction: Some([1.0, 0.0]),
+    };
+    streamer.update(looking_east(96.0));
+    let requests = streamer.take_load_requests();
+    let requested: Vec<TileCoord> = requests.iter().map(|request| request.tile).collect();
+    assert_eq!(requested, vec![tile(1), tile(2)]);
+    assert!(streamer.tile(tile(0)).is_none());
+    for request in &requests {
+        streamer.complete_load(request.tile, read_tile_data(request));
+    }
+
+    // One build per frame, most urgent first; a tile handed out is not offered twice.
+    let builds = streamer.take_build_jobs();
+    assert_eq!(builds.len(), 1);
+    assert_eq!(builds[0].tile, tile(1));
+    assert!(streamer.take_build_jobs().is_empty());
+    assert!(streamer.mark_built(tile(1)));
+    streamer.update(looking_east(96.0));
+    let builds = streamer.take_build_jobs();
+    assert_eq!(builds.len(), 1);
+    assert_eq!(builds[0].tile, tile(2));
+    streamer.drain_transitions();
+
+    // Tile 1 is no longer needed but within the unload margin; it makes room for tile 3.
+    streamer.update(looking_east(170.0));
+    assert_eq!(
+        streamer.drain_transitions(),
+        vec![
+            transition(1, TilePhase::Built, TilePhase::Unloading),
+            transition(3, TilePhase::Unloaded, TilePhase::Loading),
+        ]
+    );
+
+    // Shrinking the budget evicts the least urgent of the needed tiles.
+    streamer.set_budgets(StreamingBudgets {
+        max_tiles_loaded: 1,
+        ..budgets
+    });
+    streamer.update(looking_east(170.0));
+    let transitions = streamer.drain_transitions();
+    assert!(
+        transitions.contains(&transition(3, TilePhase::Loading, TilePhase::Unloading)),
+        "{transitions:?}"
+    );
+    assert_eq!(
+        streamer.tile(tile(2)).unwrap().state.phase(),
+        TilePhase::Loaded
+    );
+}
+
+#[test]
+fn worker_pool_reads_and_decodes_off_the_calling_thread() {
+    // The editor keeps the streamer and its pool in shared app state.
+    fn shareable<T: Send + Sync>() {}
+    shareable::<TileStreamer>();
+    shareable::<TileLoadPool>();
+
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            tile_size_meters: 64.0,
+            heightfield_samples: 2,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 3, 0),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    for x in 0..3 {
+        let tile_id = TileId { coord: tile(x) };
+        let stub = TileStub {
+            meta: TileMeta::new(tile_id),
+            terrain: TerrainHeight::new(2, vec![x as f32; 4]),
+            liquids_mask: LiquidsMask::new(2, vec![0; 4]),
+            liquids_meta: LiquidsMeta::new(Vec::new()),
+            props: PropsInstances::new(Vec::new()),
+        };
+        save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+            .expect("save tile stub");
+    }
+    let corrupt = tile_container_path(&world_layout, "region_0", TileId { coord: tile(3) });
+    std::fs::write(&corrupt, b"not a tile").expect("write corrupt tile");
+
+    let budgets = StreamingBudgets {
+        max_io_requests_in_flight: 2,
+        max_tile_decodes_per_frame: 1,
+        ..StreamingBudgets::default()
+    };
+    let mut streamer = TileStreamer::new(world_layout, &world_manifest, budgets);
+    let pool = TileLoadPool::new(2).expect("start pool");
+    let focus = StreamingFocus {
+        center: [0.0, 32.0],
+        load_radius: 1000.0,
+        view_direction: None,
+    };
+    let settled = |streamer: &TileStreamer| {
+        streamer
+            .tiles()
+            .all(|(_, tile)| tile.state.phase() != TilePhase::Loading)
+    };
+    for _ in 0..1000 {
+        streamer.update(focus);
+        streamer.pump(&pool);
+        if streamer.tiles().count() == 4 && settled(&streamer) && pool.in_flight() == 0 {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    for x in 0..3 {
+        let data = streamer.tile(tile(x)).and_then(|t| t.state.data()).unwrap();
+        assert_eq!(data.hmap.as_ref().unwrap().samples, vec![x as f32; 4]);
+        assert!(data.prop.as_ref().unwrap().instances.is_empty());
+    }
+    let failed = streamer.tile(tile(3)).unwrap();
+    assert_eq!(failed.state.phase(), TilePhase::Unloaded);
+    assert!(failed.error.is_some());
+}
+
+#[test]
+fn pinned_and_force_loaded_tiles_stay_resident_and_edits_are_loaded_first() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            tile_size_meters: 64.0,
+            heightfield_samples: 2,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 7, 0),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    for x in 0..8 {
+        let tile_id = TileId { coord: tile(x) };
+        let stub = TileStub {
+            meta: TileMeta::new(tile_id),
+            terrain: TerrainHeight::new(2, vec![0.0; 4]),
+            liquids_mask: LiquidsMask::new(2, vec![0; 4]),
+            liquids_meta: LiquidsMeta::new(Vec::new()),
+            props: PropsInstances::new(Vec::new()),
+        };
+        save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+            .expect("save tile stub");
+    }
+
+    let budgets = StreamingBudgets {
+        max_tiles_loaded: 2,
+        ..StreamingBudgets::default()
+    };
+    let mut streamer = TileStreamer::new(world_layout.clone(), &world_manifest, budgets);
+    let chunk = ChunkId {
+        tile: TileId { coord: tile(4) },
+        coord: ChunkCoord { x: 1, y: 1 },
+    };
+    assert!(streamer.pin_tile(tile(6)));
+    assert!(!streamer.pin_tile(tile(6)));
+    assert!(streamer.pin_chunk(chunk));
+    streamer.set_force_load_radius(64.0);
+    assert!(streamer.is_pinned(tile(4)));
+    assert!(!streamer.is_pinned(tile(1)));
+
+    // Only tile 0 is in the load radius, but the force-load radius reaches tile 1 and the pins
+    // are loaded far away and past `max_tiles_loaded`.
+    let near = StreamingFocus {
+        center: [32.0, 32.0],
+        load_radius: 10.0,
+        view_direction: None,
+    };
+    streamer.update(near);
+    let requests = streamer.take_load_requests();
+    let requested: Vec<TileCoord> = requests.iter().map(|request| request.tile).collect();
+    assert_eq!(requested, vec![tile(0), tile(1), tile(4), tile(6)]);
+    for request in &requests {
+        streamer.complete_load(request.tile, read_tile_data(request));
+    }
+
+    // Force-loading follows the focus; pins do not.
+    let far = StreamingFocus {
+        center: [-1000.0, 32.0],
+        load_radius: 10.0,
+        view_direction: None,
+    };
+    streamer.update(far);
+    let phases: Vec<(TileCoord, TilePhase)> = streamer
+        .tiles()
+        .map(|(coord, tile)| (coord, tile.state.phase()))
+        .collect();
+    assert_eq!(
+        phases,
+        vec![
+            (tile(0), TilePhase::Unloading),
+            (tile(1), TilePhase::Unloading),
+            (tile(4), TilePhase::Loaded),
+            (tile(6), TilePhase::Loaded),
+        ]
+    );
+    assert!(streamer.unpin_chunk(chunk));
+    streamer.update(far);
+    assert_eq!(
+        streamer.tile(tile(4)).unwrap().state.phase(),
+        TilePhase::Unloading
+    );
+    assert_eq!(
+        streamer.tile(tile(6)).unwrap().state.phase(),
+        TilePhase::Loaded
+    );
+    streamer.clear_pins();
+    streamer.update(far);
+    assert_eq!(
+        streamer.tile(tile(6)).unwrap().state.phase(),
+        TilePhase::Unloading
+    );
+
+    // The edited tile and its neighbours are read before tiles nearer the focus.
+    let budgets = StreamingBudgets {
+        max_io_requests_in_flight: 3,
+        ..StreamingBudgets::default()
+    };
+    let mut streamer = TileStreamer::new(world_layout, &world_manifest, budgets);
+    streamer.set_edited_tile(Some(tile(6)));
+    streamer.update(StreamingFocus {
+        center: [-100.0, 32.0],
+        load_radius: 1000.0,
+        view_direction: None,
+    });
+    let requested: Vec<TileCoord> = streamer
+        .take_load_requests()
+        .into_iter()
+        .map(|request| request.tile)
+        .collect();
+    assert_eq!(requested, vec![tile(6), tile(5), tile(7)]);
+}
+
+#[test]
+fn a_pool_shared_across_worlds_only_applies_results_to_the_streamer_that_issued_them() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let mut streamers = Vec::new();
+    for (world_id, height) in [("world_a", 1.0), ("world_b", 2.0)] {
+        let world_manifest = WorldManifest {
+            world_id: world_id.to_string(),
+            world_spec: WorldSpec {
+                tile_size_meters: 64.0,
+                heightfield_samples: 2,
+                ..DEFAULT_WORLD_SPEC
+            },
+            regions: vec![RegionManifest {
+                region_id: "region_0".to_string(),
+                name: "Region 0".to_string(),
+                bounds: RegionBounds::new(0, 0, 0, 0),
+            }],
+            ..WorldManifest::default()
+        };
+        let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+        let tile_id = TileId { coord: tile(0) };
+        let stub = TileStub {
+            meta: TileMeta::new(tile_id),
+            terrain: TerrainHeight::new(2, vec![height; 4]),
+            liquids_mask: LiquidsMask::new(2, vec![0; 4]),
+            liquids_meta: LiquidsMeta::new(Vec::new()),
+            props: PropsInstances::new(Vec::new()),
+        };
+        save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+            .expect("save tile stub");
+        streamers.push(TileStreamer::new(
+            world_layout,
+            &world_manifest,
+            StreamingBudgets::default(),
+        ));
+    }
+    let mut world_b = streamers.pop().unwrap();
+    let mut world_a = streamers.pop().unwrap();
+    assert_ne!(world_a.id(), world_b.id());
+
+    let pool = TileLoadPool::new(1).expect("start pool");
+    let focus = StreamingFocus {
+        center: [32.0, 32.0],
+        load_radius: 10.0,
+        view_direction: None,
+    };
+    let wait_idle = |pool: &TileLoadPool| {
+        for _ in 0..1000 {
+            if pool.in_flight() == 0 {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        panic!("pool did not finish");
+    };
+
+    // World A's read finishes after the editor switched to world B, whose read of the same tile
+    // is already in flight.
+    world_a.update(focus);
+    world_a.pump(&pool);
+    wait_idle(&pool);
+    drop(world_a);
+    world_b.update(focus);
+    for request in world_b.take_load_requests() {
+        pool.submit_read(request);
+    }
+    for _ in 0..1000 {
+        world_b.update(focus);
+        world_b.pump(&pool);
+        if world_b.tile(tile(0)).unwrap().state.phase() == TilePhase::Loaded {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    let data = world_b.tile(tile(0)).and_then(|t| t.state.data()).unwrap();
+    assert_eq!(data.hmap.as_ref().unwrap().samples, vec![2.0; 4]);
+}
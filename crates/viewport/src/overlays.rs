@@ -193,7 +193,7 @@ impl ViewportOverlayScope {
     }
 }
 
-/// Streaming state per tile (fed by streaming systems later).
+/// Streaming state per tile, rewritten each frame by the editor's tile streaming system.
 #[derive(Resource, Debug, Default, Clone)]
 pub struct ViewportTileStreamingState {
     tiles: HashMap<TileCoord, TileStreamingStatus>,
@@ -221,16 +221,24 @@ impl ViewportTileStreamingState {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TileStreamingVisual {
     Loaded,
+    Built,
+    Queued,
     PendingLoad,
     Dirty,
     Pinned,
     Error,
 }
 
-/// Multi-flag tile status; visual precedence is Error > Pending > Dirty > Pinned > Loaded.
+/// Multi-flag tile status; visual precedence is
+/// Error > Pending > Queued > Dirty > Pinned > Built > Loaded.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct TileStreamingStatus {
     pub loaded: bool,
+    /// Loaded and built into renderable form.
+    pub built: bool,
+    /// Waiting for a read or decode slot.
+    pub queued: bool,
+    /// Being read or decoded.
     pub pending_load: bool,
     pub dirty: bool,
     pub pinned: bool,
@@ -239,7 +247,13 @@ pub struct TileStreamingStatus {
 
 impl TileStreamingStatus {
     pub fn is_empty(self) -> bool {
-        !self.loaded && !self.pending_load && !self.dirty && !self.pinned && !self.error
+        !self.loaded
+            && !self.built
+            && !self.queued
+            && !self.pending_load
+            && !self.dirty
+            && !self.pinned
+            && !self.error
     }
 
     pub fn visual_state(self) -> Option<TileStreamingVisual> {
@@ -247,10 +261,14 @@ impl TileStreamingStatus {
             Some(TileStreamingVisual::Error)
         } else if self.pending_load {
             Some(TileStreamingVisual::PendingLoad)
+        } else if self.queued {
+            Some(TileStreamingVisual::Queued)
         } else if self.dirty {
             Some(TileStreamingVisual::Dirty)
         } else if self.pinned {
             Some(TileStreamingVisual::Pinned)
+        } else if self.built {
+            Some(TileStreamingVisual::Built)
         } else if self.loaded {
             Some(TileStreamingVisual::Loaded)
         } else {
@@ -302,5 +320,18 @@ mod tests {
             ..Default::default()
         };
         assert_eq!(status.visual_state(), Some(TileStreamingVisual::Error));
+        let status = TileStreamingStatus {
+            loaded: true,
+            built: true,
+            pinned: true,
+            ..Default::default()
+        };
+        assert_eq!(status.visual_state(), Some(TileStreamingVisual::Pinned));
+        let status = TileStreamingStatus {
+            queued: true,
+            pinned: true,
+            ..Default::default()
+        };
+        assert_eq!(status.visual_state(), Some(TileStreamingVisual::Queued));
     }
 }
@@ -536,6 +536,8 @@ fn tile_coord_from_world(position: Vec2, tile_size_meters: f32) -> Option<(i32,
 fn streaming_color(state: TileStreamingVisual) -> Color {
     match state {
         TileStreamingVisual::Loaded => Color::srgba(0.2, 0.7, 0.25, 0.6),
+        TileStreamingVisual::Built => Color::srgba(0.35, 0.9, 0.4, 0.75),
+        TileStreamingVisual::Queued => Color::srgba(0.7, 0.65, 0.35, 0.45),
         TileStreamingVisual::PendingLoad => Color::srgba(0.95, 0.8, 0.2, 0.7),
         TileStreamingVisual::Dirty => Color::srgba(0.95, 0.5, 0.2, 0.75),
         TileStreamingVisual::Pinned => Color::srgba(0.3, 0.7, 0.95, 0.7),
@@ -0,0 +1,96 @@
+//! Catalog of the assets available in a project's `assets_dir`.
+//!
+//! An asset `namespace/name` exists when a file `<assets_dir>/<namespace>/<name>.<ext>` exists;
+//! the extension is ignored, so one asset may have several files (model, material, metadata).
+//! `name` may contain `/` for nested folders: `core/trees/oak.glb` is asset `core`, `trees/oak`.
+//! Files and folders starting with `.` are skipped.
+
+use anyhow::Context;
+use foundation::ids::AssetId;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetCatalog {
+    assets: BTreeSet<AssetId>,
+}
+
+impl AssetCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, asset: AssetId) {
+        self.assets.insert(asset);
+    }
+
+    pub fn contains(&self, asset: &AssetId) -> bool {
+        self.assets.contains(asset)
+    }
+
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Assets ordered by namespace, then name.
+    pub fn iter(&self) -> impl Iterator<Item = &AssetId> {
+        self.assets.iter()
+    }
+}
+
+/// Scans `assets_dir` for assets. A missing directory yields an empty catalog.
+pub fn scan_asset_catalog(assets_dir: &Path) -> anyhow::Result<AssetCatalog> {
+    let mut catalog = AssetCatalog::new();
+    if !assets_dir.exists() {
+        return Ok(catalog);
+    }
+    for entry in
+        fs::read_dir(assets_dir).with_context(|| format!("read assets dir {:?}", assets_dir))?
+    {
+        let path = entry?.path();
+        let Some(namespace) = visible_name(&path) else {
+            continue;
+        };
+        if path.is_dir() {
+            scan_namespace_dir(&path, namespace, "", &mut catalog)?;
+        }
+    }
+    Ok(catalog)
+}
+
+fn scan_namespace_dir(
+    dir: &Path,
+    namespace: &str,
+    prefix: &str,
+    catalog: &mut AssetCatalog,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("read assets dir {:?}", dir))? {
+        let path = entry?.path();
+        let Some(file_name) = visible_name(&path) else {
+            continue;
+        };
+        if path.is_dir() {
+            let prefix = format!("{prefix}{file_name}/");
+            scan_namespace_dir(&path, namespace, &prefix, catalog)?;
+            continue;
+        }
+        let stem = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(file_name);
+        catalog.insert(AssetId::new(namespace, format!("{prefix}{stem}")));
+    }
+    Ok(())
+}
+
+/// The entry's UTF-8 file name, or `None` for hidden or non-UTF-8 entries.
+fn visible_name(path: &Path) -> Option<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.starts_with('.'))
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use world::migrations::{
+    migrate_project_manifest, migrate_world_manifest, migrate_world_sections,
+    plan_project_migration, ManifestPlan, MigrationPlan,
+};
+use world::storage::{
+    find_legacy_tiles, migrate_legacy_tiles, project_layout, read_project_manifest,
+    read_world_manifest, world_layout, write_project_manifest, write_world_manifest,
+    LegacyDisposition, LegacyMigrationReport, LegacyTile, ProjectLayout,
+};
+
+const USAGE: &str = "usage: migrate_project [--json] [--dry-run] \
+[--legacy-tiles[=quarantine|delete]] [project_root]";
+
+fn main() -> anyhow::Result<()> {
+    let mut json = false;
+    let mut dry_run = false;
+    let mut legacy: Option<LegacyDisposition> = None;
+    let mut path: Option<PathBuf> = None;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--dry-run" => dry_run = true,
+            "--legacy-tiles" | "--legacy-tiles=quarantine" => {
+                legacy = Some(LegacyDisposition::Quarantine)
+            }
+            "--legacy-tiles=delete" => legacy = Some(LegacyDisposition::Delete),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            value if value.starts_with("--") => {
+                return Err(anyhow::anyhow!("unknown flag: {}\n{}", value, USAGE));
+            }
+            value => {
+                if path.is_some() {
+                    return Err(anyhow::anyhow!("unexpected argument: {}", value));
+                }
+                path = Some(PathBuf::from(value));
+            }
+        }
+    }
+
+    let project_root = path.unwrap_or_else(|| PathBuf::from("."));
+    let plan = plan_project_migration(&project_root)?;
+    // Legacy folders are listed per world; worlds whose manifest cannot be read yet are skipped.
+    let legacy_tiles = match legacy {
+        Some(_) if dry_run => Some(list_legacy_tiles(&project_root, &plan)),
+        _ => None,
+    };
+    if json {
+        match &legacy_tiles {
+            Some(tiles) => println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "plan": plan,
+                    "legacy_tiles": tiles,
+                }))?
+            ),
+            None => println!("{}", serde_json::to_string_pretty(&plan)?),
+        }
+    } else {
+        print_plan(&plan);
+        if let Some(tiles) = &legacy_tiles {
+            print_legacy_tiles(tiles);
+        }
+    }
+
+    if plan.has_errors() {
+        if !dry_run {
+            eprintln!("not migrating: resolve the errors above first");
+        }
+        std::process::exit(1);
+    }
+    if dry_run || (plan.is_noop() && legacy.is_none()) {
+        return Ok(());
+    }
+
+    if plan.project.needs_rewrite() {
+        let mut manifest = read_project_manifest(&project_root)?;
+        migrate_project_manifest(&mut manifest)?;
+        write_project_manifest(&project_root, &manifest)?;
+    }
+    let layout = project_layout(&project_root, &read_project_manifest(&project_root)?);
+    let mut failed = false;
+    for world_plan in &plan.worlds {
+        let world = world_layout(&layout, &world_plan.world_id);
+        let mut manifest = read_world_manifest(&world.world_root)?;
+        if world_plan.manifest.needs_rewrite() {
+            migrate_world_manifest(&mut manifest)?;
+            write_world_manifest(&world.world_root, &manifest)?;
+        }
+        if world_plan.sections.is_empty() {
+            continue;
+        }
+        let report = migrate_world_sections(&world, &manifest)?;
+        for (path, reason) in &report.failed {
+            eprintln!("failed {}: {}", path.display(), reason);
+            failed = true;
+        }
+    }
+
+    if let Some(disposition) = legacy {
+        for world_plan in &plan.worlds {
+            let world = world_layout(&layout, &world_plan.world_id);
+            let manifest = read_world_manifest(&world.world_root)?;
+            let report = migrate_legacy_tiles(&world, &manifest, disposition)?;
+            print_legacy_report(&world_plan.world_id, &report, json);
+            failed |= !report.is_complete();
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn list_legacy_tiles(
+    project_root: &Path,
+    plan: &MigrationPlan,
+) -> BTreeMap<String, Vec<LegacyTile>> {
+    let Ok(layout) =
+        read_project_manifest(project_root).map(|manifest| project_layout(project_root, &manifest))
+    else {
+        return BTreeMap::new();
+    };
+    plan.worlds
+        .iter()
+        .filter_map(|world_plan| {
+            let tiles = world_legacy_tiles(&layout, &world_plan.world_id)?;
+            Some((world_plan.world_id.clone(), tiles))
+        })
+        .collect()
+}
+
+fn world_legacy_tiles(layout: &ProjectLayout, world_id: &str) -> Option<Vec<LegacyTile>> {
+    let world = world_layout(layout, world_id);
+    let manifest = read_world_manifest(&world.world_root).ok()?;
+    find_legacy_tiles(&world, &manifest).ok()
+}
+
+fn print_plan(plan: &MigrationPlan) {
+    print_manifest("project", &plan.project);
+    for world in &plan.worlds {
+        print_manifest(&format!("world {}", world.world_id), &world.manifest);
+        println!(
+            "  {} tiles scanned, {} sections to migrate",
+            world.tiles_scanned,
+            world.sections.len()
+        );
+        for section in &world.sections {
+            println!(
+                "    {}: {} {} -> {}",
+                section.path.display(),
+                section.tag,
+                section.from,
+                section.to
+            );
+            for note in &section.notes {
+                println!("      lossy {}", note);
+            }
+        }
+        for (path, reason) in &world.failed {
+            println!("    error {}: {}", path.display(), reason);
+        }
+    }
+    if plan.is_noop() {
+        println!("nothing to migrate");
+    }
+}
+
+fn print_legacy_tiles(tiles: &BTreeMap<String, Vec<LegacyTile>>) {
+    for (world_id, tiles) in tiles {
+        println!("world {}: {} legacy tile folders", world_id, tiles.len());
+        for tile in tiles {
+            println!("    {}", legacy_tile_name(tile));
+        }
+    }
+}
+
+fn print_legacy_report(world_id: &str, report: &LegacyMigrationReport, json: bool) {
+    let mut lines = Vec::new();
+    for tile in &report.migrated {
+        lines.push(format!("    migrated {}", legacy_tile_name(tile)));
+    }
+    for tile in &report.already_migrated {
+        lines.push(format!("    already migrated {}", legacy_tile_name(tile)));
+    }
+    for (tile, reason) in &report.failed {
+        lines.push(format!("    failed {}: {}", legacy_tile_name(tile), reason));
+    }
+    if lines.is_empty() {
+        return;
+    }
+    // Keep stdout a single JSON document in --json mode.
+    let print = |line: &str| {
+        if json {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    };
+    print(&format!("world {}: legacy tiles", world_id));
+    for line in &lines {
+        print(line);
+    }
+}
+
+fn legacy_tile_name(tile: &LegacyTile) -> String {
+    format!(
+        "{}/{}_{}",
+        tile.region_id, tile.tile_id.coord.x, tile.tile_id.coord.y
+    )
+}
+
+fn print_manifest(label: &str, manifest: &ManifestPlan) {
+    if let Some(error) = &manifest.error {
+        println!("{} ({}): error: {}", label, manifest.path.display(), error);
+        return;
+    }
+    if manifest.from == manifest.to {
+        println!("{}: format v{}", label, manifest.from);
+    } else {
+        println!("{}: format v{} -> v{}", label, manifest.from, manifest.to);
+    }
+    for key in &manifest.defaulted {
+        println!("  defaulted {}", key);
+    }
+    for key in &manifest.dropped {
+        println!("  dropped {}", key);
+    }
+}
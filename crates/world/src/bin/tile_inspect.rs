@@ -0,0 +1,195 @@
+use std::path::PathBuf;
+
+use world::tile_container::inspect::{
+    diff_tiles, inspect_tile, SectionDiffStatus, SectionSummary, TileDiff, TileInspection,
+};
+
+const USAGE: &str =
+    "usage: tile-inspect [--json] <tile> | tile-inspect [--json] --diff <left> <right>";
+
+fn main() -> anyhow::Result<()> {
+    let mut json = false;
+    let mut diff = false;
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--diff" => diff = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            value if value.starts_with("--") => {
+                return Err(anyhow::anyhow!("unknown flag: {}\n{}", value, USAGE));
+            }
+            value => paths.push(PathBuf::from(value)),
+        }
+    }
+
+    if diff {
+        let [left, right] = paths.as_slice() else {
+            return Err(anyhow::anyhow!("--diff expects two tiles\n{}", USAGE));
+        };
+        let result = diff_tiles(left, right)?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        } else {
+            print_diff(&result);
+        }
+        if !result.is_identical() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let [path] = paths.as_slice() else {
+        return Err(anyhow::anyhow!("expected one tile\n{}", USAGE));
+    };
+    let inspection = inspect_tile(path)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&inspection)?);
+    } else {
+        print_inspection(&inspection);
+    }
+    Ok(())
+}
+
+fn print_inspection(inspection: &TileInspection) {
+    let header = &inspection.header;
+    println!("{}", inspection.path.display());
+    println!(
+        "  container v{}  tile ({}, {})  {} bytes ({} reclaimable)",
+        header.container_version,
+        header.tile_x,
+        header.tile_y,
+        inspection.file_len,
+        inspection.reclaimable_bytes
+    );
+    println!(
+        "  region_hash {:016x}  world_spec_hash {:016x}  created {}",
+        header.region_hash, header.world_spec_hash, header.created_timestamp
+    );
+    println!(
+        "  {} sections, directory at {}",
+        header.section_count, header.section_dir_offset
+    );
+    println!();
+    println!(
+        "  {:<4} {:>3} {:<5} {:>5} {:>10} {:>10} {:>10} {:>8}",
+        "tag", "ver", "codec", "flags", "offset", "stored", "decoded", "crc32"
+    );
+    for section in &inspection.sections {
+        println!(
+            "  {:<4} {:>3} {:<5} {:>5} {:>10} {:>10} {:>10} {:08x}{}",
+            section.tag,
+            section.section_version,
+            section.codec,
+            section.flags,
+            section.offset,
+            section.stored_len,
+            section.decoded_len,
+            section.crc32,
+            if section.crc_ok { "" } else { " CRC MISMATCH" }
+        );
+        if let Some(summary) = &section.summary {
+            println!("       {}", describe_summary(summary));
+        }
+        if let Some(error) = &section.error {
+            println!("       error: {}", error);
+        }
+    }
+}
+
+fn describe_summary(summary: &SectionSummary) -> String {
+    match summary {
+        SectionSummary::Meta {
+            format_version,
+            tile_x,
+            tile_y,
+            ..
+        } => format!("format v{} tile ({}, {})", format_version, tile_x, tile_y),
+        SectionSummary::Hmap {
+            width,
+            height,
+            encoding,
+            min,
+            max,
+            mean,
+        } => format!(
+            "{}x{} {} min {} max {} mean {}",
+            width, height, encoding, min, max, mean
+        ),
+        SectionSummary::Wmap {
+            width,
+            height,
+            layers,
+            coverage,
+        } => {
+            let coverage: Vec<String> = coverage
+                .iter()
+                .enumerate()
+                .map(|(layer, value)| format!("{}:{:.1}%", layer, value * 100.0))
+                .collect();
+            format!(
+                "{}x{} {} layers coverage {}",
+                width,
+                height,
+                layers,
+                coverage.join(" ")
+            )
+        }
+        SectionSummary::Liqd {
+            width,
+            height,
+            wet_cells,
+            bodies,
+        } => {
+            let bodies: Vec<String> = bodies
+                .iter()
+                .map(|body| {
+                    format!(
+                        "#{} {} @{} ({} cells)",
+                        body.id, body.kind, body.height, body.cells
+                    )
+                })
+                .collect();
+            format!(
+                "{}x{} {} wet cells, bodies [{}]",
+                width,
+                height,
+                wet_cells,
+                bodies.join(", ")
+            )
+        }
+        SectionSummary::Prop { count } => format!("{} instances", count),
+        SectionSummary::Spln { count, points } => format!("{} splines, {} points", count, points),
+        SectionSummary::Addx { keys } => format!("keys [{}]", keys.join(", ")),
+    }
+}
+
+fn print_diff(diff: &TileDiff) {
+    println!("--- {}", diff.left.display());
+    println!("+++ {}", diff.right.display());
+    for change in &diff.header {
+        println!(
+            "header {}: {} -> {}",
+            change.field, change.left, change.right
+        );
+    }
+    for section in &diff.sections {
+        let marker = match section.status {
+            SectionDiffStatus::Unchanged => "=",
+            SectionDiffStatus::Changed => "~",
+            SectionDiffStatus::Added => "+",
+            SectionDiffStatus::Removed => "-",
+        };
+        println!("{} {}", marker, section.tag);
+        for change in &section.changes {
+            println!("    {}: {} -> {}", change.field, change.left, change.right);
+        }
+    }
+    if diff.is_identical() {
+        println!("tiles are identical");
+    }
+}
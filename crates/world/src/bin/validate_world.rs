@@ -1,17 +1,72 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use world::validator::{validate_project, validate_project_and_quarantine};
+use world::storage::{
+    list_world_ids, project_layout, read_project_manifest, read_world_manifest, world_layout,
+};
+use world::tile_container::extensions::AddxRegistry;
+use world::validator::{
+    fix_project, junit_report, list_project_tiles, reassign_duplicate_instances,
+    repair_world_seams, sarif_report, validate_project_with_options, SeamAxis, Severity,
+    ValidationIssue, ValidationOptions, DEFAULT_SEAM_TOLERANCE,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Sarif,
+    Junit,
+}
 
 fn main() -> anyhow::Result<()> {
-    let mut json = false;
+    let mut format = Format::Text;
     let mut quarantine = false;
+    let mut fail_on = Severity::Info;
+    let mut min_severity = Severity::Info;
+    let mut seam_tolerance = DEFAULT_SEAM_TOLERANCE;
+    let mut repair_seams = false;
+    let mut reassign_instances = false;
+    let mut fix = false;
+    let mut cache = true;
+    let mut threads = 0;
     let mut path: Option<PathBuf> = None;
 
-    let args = std::env::args().skip(1);
-    for arg in args {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
         match arg.as_str() {
-            "--json" => json = true,
+            "--json" => format = Format::Json,
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("sarif") => Format::Sarif,
+                    Some("junit") => Format::Junit,
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "--format expects text, json, sarif or junit"
+                        ))
+                    }
+                };
+            }
             "--quarantine" => quarantine = true,
+            "--fail-on" => fail_on = severity_arg(&arg, args.next())?,
+            "--min-severity" => min_severity = severity_arg(&arg, args.next())?,
+            "--seam-tolerance" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--seam-tolerance expects meters"))?;
+                seam_tolerance = value.parse()?;
+            }
+            "--repair-seams" => repair_seams = true,
+            "--reassign-instances" => reassign_instances = true,
+            "--fix" => fix = true,
+            "--no-cache" => cache = false,
+            "--threads" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--threads expects a count"))?;
+                threads = value.parse()?;
+            }
             value => {
                 if path.is_some() {
                     return Err(anyhow::anyhow!("unexpected argument: {}", value));
@@ -22,27 +77,149 @@ fn main() -> anyhow::Result<()> {
     }
 
     let project_root = path.unwrap_or_else(|| PathBuf::from("."));
-    let issues = if quarantine {
-        validate_project_and_quarantine(&project_root)
+    // Absolute, so SARIF/JUnit paths can be made relative to it.
+    let project_root = std::fs::canonicalize(&project_root).unwrap_or(project_root);
+    if fix {
+        fix_tiles(&project_root)?;
+    }
+    if repair_seams {
+        repair_project_seams(&project_root, seam_tolerance)?;
+    }
+    if reassign_instances {
+        reassign_project_instances(&project_root)?;
+    }
+    let options = ValidationOptions {
+        quarantine,
+        seam_tolerance,
+        cache,
+        threads,
+    };
+    // Listed before validating: quarantine may move tiles away, but they still get a test case.
+    let tiles = if format == Format::Junit {
+        list_project_tiles(&project_root).unwrap_or_default()
     } else {
-        validate_project(&project_root)
+        Vec::new()
     };
+    let mut issues =
+        validate_project_with_options(&project_root, &AddxRegistry::default(), options);
+    let failed = issues.iter().any(|issue| issue.severity >= fail_on);
+    issues.retain(|issue| issue.severity >= min_severity);
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&issues)?);
-    } else {
-        for issue in &issues {
-            if let Some(path) = &issue.path {
-                println!("{}: {}", path.display(), issue.message);
-            } else {
-                println!("{}", issue.message);
-            }
-        }
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&issues)?),
+        Format::Sarif => println!(
+            "{}",
+            serde_json::to_string_pretty(&sarif_report(&issues, &project_root))?
+        ),
+        Format::Junit => print!("{}", junit_report(&issues, &tiles, &project_root, fail_on)),
+        Format::Text => print_text(&issues),
     }
 
-    if issues.is_empty() {
-        Ok(())
-    } else {
+    if failed {
         std::process::exit(1);
     }
+    Ok(())
+}
+
+fn print_text(issues: &[ValidationIssue]) {
+    for issue in issues {
+        let mut location = String::new();
+        if let Some(path) = &issue.path {
+            location.push_str(&format!("{}: ", path.display()));
+        }
+        if let Some(tile) = issue.tile {
+            location.push_str(&format!("({}, {}) ", tile.x, tile.y));
+        }
+        if let Some(section) = &issue.section {
+            location.push_str(&format!("{} ", section));
+        }
+        println!(
+            "{}[{}] {}{}",
+            issue.severity, issue.code, location, issue.message
+        );
+    }
+}
+
+fn severity_arg(flag: &str, value: Option<String>) -> anyhow::Result<Severity> {
+    let value = value.ok_or_else(|| anyhow::anyhow!("{} expects error, warning or info", flag))?;
+    value.parse()
+}
+
+/// Applies safe repairs before validating, so fixable tiles are not quarantined. Every change is
+/// logged to stderr.
+fn fix_tiles(project_root: &Path) -> anyhow::Result<()> {
+    let report = fix_project(project_root)?;
+    for repair in &report.repairs {
+        let section = repair.section.as_deref().unwrap_or("header");
+        eprintln!(
+            "fixed [{}] {}: ({}, {}) {} {}",
+            repair.code,
+            repair.path.display(),
+            repair.tile.x,
+            repair.tile.y,
+            section,
+            repair.description
+        );
+    }
+    for (path, reason) in &report.failed {
+        eprintln!("fix failed {}: {}", path.display(), reason);
+    }
+    Ok(())
+}
+
+/// Averages mismatched HMAP seams in every world before validating. Progress goes to stderr so
+/// `--json` output stays parseable.
+fn repair_project_seams(project_root: &Path, tolerance: f32) -> anyhow::Result<()> {
+    let layout = project_layout(project_root, &read_project_manifest(project_root)?);
+    for world_id in list_world_ids(&layout)? {
+        let world = world_layout(&layout, &world_id);
+        let manifest = read_world_manifest(&world.world_root)?;
+        let report = repair_world_seams(&world, &manifest, tolerance)?;
+        for seam in &report.repaired {
+            let axis = match seam.axis {
+                SeamAxis::X => "x",
+                SeamAxis::Y => "y",
+            };
+            eprintln!(
+                "repaired {} {}: ({}, {}) | ({}, {}) along {}, max delta {:.3} m",
+                world_id,
+                seam.region_id,
+                seam.tile.x,
+                seam.tile.y,
+                seam.neighbor.x,
+                seam.neighbor.y,
+                axis,
+                seam.max_delta
+            );
+        }
+        for path in &report.tiles_rewritten {
+            eprintln!("rewrote {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Gives instances shared between tiles fresh ids before validating. Each change goes to stderr.
+fn reassign_project_instances(project_root: &Path) -> anyhow::Result<()> {
+    let layout = project_layout(project_root, &read_project_manifest(project_root)?);
+    for world_id in list_world_ids(&layout)? {
+        let world = world_layout(&layout, &world_id);
+        let manifest = read_world_manifest(&world.world_root)?;
+        let report = reassign_duplicate_instances(&world, &manifest)?;
+        for change in &report.reassigned {
+            eprintln!(
+                "reassigned {} {}: ({}, {}) instance {} -> {}",
+                world_id,
+                change.location.region_id,
+                change.location.tile.x,
+                change.location.tile.y,
+                change.old_id.0,
+                change.new_id.0
+            );
+        }
+        for path in &report.tiles_rewritten {
+            eprintln!("rewrote {}", path.display());
+        }
+    }
+    Ok(())
 }
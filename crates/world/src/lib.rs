@@ -1,5 +1,6 @@
 //! Authoritative world schema and serialization contracts.
 
+pub mod assets;
 pub mod migrations;
 pub mod schema;
 pub mod storage;
@@ -1,5 +1,16 @@
 use crate::schema::{ProjectManifest, WorldManifest, PROJECT_FORMAT_VERSION, WORLD_FORMAT_VERSION};
-use anyhow::{anyhow, bail};
+use crate::storage::{
+    list_world_ids, project_layout, region_tiles_dir, world_layout, WorldLayout,
+    PROJECT_MANIFEST_FILE, WORLD_MANIFEST_FILE,
+};
+use crate::tile_container::{
+    compact_tile, current_section_version, payload_version, section_chain, TileContainerPatch,
+    TileContainerReader, TileSectionPayload,
+};
+use anyhow::{anyhow, bail, Context};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 
 pub struct ProjectMigration {
     pub from: u32,
@@ -13,6 +24,8 @@ pub struct WorldMigration {
     pub apply: fn(&mut WorldManifest) -> anyhow::Result<()>,
 }
 
+pub use crate::tile_container::{migrate_section_payload, section_migrations, SectionMigration};
+
 pub const MIN_PROJECT_FORMAT_VERSION: u32 = 1;
 pub const MIN_WORLD_FORMAT_VERSION: u32 = 1;
 
@@ -78,3 +91,329 @@ pub fn migrate_world_manifest(manifest: &mut WorldManifest) -> anyhow::Result<()
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigratedSection {
+    pub path: PathBuf,
+    pub tag: String,
+    pub from: u16,
+    pub to: u16,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SectionMigrationReport {
+    pub tiles_scanned: usize,
+    pub tiles_rewritten: usize,
+    pub sections: Vec<MigratedSection>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Rewrites every tile of the world whose sections are older than the current encoders.
+///
+/// Migrated sections are patched in with their original codec and flags, then the tile is
+/// compacted. Sections that are already current keep their bytes. Failures are collected per
+/// tile and leave that tile unchanged.
+pub fn migrate_world_sections(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+) -> anyhow::Result<SectionMigrationReport> {
+    let mut report = SectionMigrationReport::default();
+    for path in world_tile_paths(layout, manifest)? {
+        report.tiles_scanned += 1;
+        match migrate_tile_sections(&path, manifest.world_spec.chunks_per_tile) {
+            Ok(sections) if sections.is_empty() => {}
+            Ok(sections) => {
+                report.tiles_rewritten += 1;
+                report.sections.extend(sections);
+            }
+            Err(err) => report.failed.push((path, format!("{:#}", err))),
+        }
+    }
+    Ok(report)
+}
+
+/// `.tile` files of every region in the world, sorted per region.
+fn world_tile_paths(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for region in &manifest.regions {
+        let dir = region_tiles_dir(layout, &region.region_id);
+        if !dir.exists() {
+            continue;
+        }
+        let mut region_paths = Vec::new();
+        for entry in std::fs::read_dir(&dir).with_context(|| format!("read tiles dir {:?}", dir))? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some("tile") {
+                region_paths.push(path);
+            }
+        }
+        region_paths.sort();
+        paths.extend(region_paths);
+    }
+    Ok(paths)
+}
+
+fn migrate_tile_sections(
+    path: &Path,
+    chunks_per_tile: u16,
+) -> anyhow::Result<Vec<MigratedSection>> {
+    let reader = TileContainerReader::open_buffered(path)?;
+    let mut patch = TileContainerPatch::new().chunks_per_tile(chunks_per_tile);
+    let mut migrated = Vec::new();
+    for entry in &reader.directory {
+        let decoded = reader.decode_section_cow(entry.tag)?;
+        let Some(payload) = migrate_section_payload(entry.tag, &decoded)? else {
+            continue;
+        };
+        let from = payload_version(&decoded)?;
+        let to = payload_version(&payload)?;
+        patch.replace_section(TileSectionPayload {
+            tag: entry.tag,
+            section_version: to,
+            codec: entry.codec,
+            flags: entry.flags,
+            decoded: payload,
+        });
+        migrated.push(MigratedSection {
+            path: path.to_path_buf(),
+            tag: entry.tag.as_string(),
+            from,
+            to,
+        });
+    }
+    drop(reader);
+
+    if !migrated.is_empty() {
+        patch.apply(path)?;
+        compact_tile(path)?;
+    }
+    Ok(migrated)
+}
+
+/// Dry-run view of a project upgrade; nothing is written while it is computed.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationPlan {
+    pub project: ManifestPlan,
+    pub worlds: Vec<WorldMigrationPlan>,
+}
+
+impl MigrationPlan {
+    /// True when applying the plan would not change anything on disk.
+    pub fn is_noop(&self) -> bool {
+        !self.project.needs_rewrite()
+            && self
+                .worlds
+                .iter()
+                .all(|world| !world.manifest.needs_rewrite() && world.sections.is_empty())
+    }
+
+    /// True when some manifest or tile cannot be migrated.
+    pub fn has_errors(&self) -> bool {
+        self.project.error.is_some()
+            || self
+                .worlds
+                .iter()
+                .any(|world| world.manifest.error.is_some() || !world.failed.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestPlan {
+    pub path: PathBuf,
+    pub from: u32,
+    pub to: u32,
+    /// Keys missing from the file that a rewrite fills with their default value.
+    pub defaulted: Vec<String>,
+    /// Keys the current schema does not know; a rewrite drops them.
+    pub dropped: Vec<String>,
+    /// Why the manifest cannot be read or migrated.
+    pub error: Option<String>,
+}
+
+impl ManifestPlan {
+    pub fn needs_rewrite(&self) -> bool {
+        self.error.is_none()
+            && (self.from != self.to || !self.defaulted.is_empty() || !self.dropped.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorldMigrationPlan {
+    pub world_id: String,
+    pub manifest: ManifestPlan,
+    pub tiles_scanned: usize,
+    pub sections: Vec<PlannedSection>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedSection {
+    pub path: PathBuf,
+    pub tag: String,
+    pub from: u16,
+    pub to: u16,
+    /// Notes of the migration steps that lose or default data.
+    pub notes: Vec<String>,
+}
+
+/// Walks the project and reports which manifests and tile sections a migration would touch.
+pub fn plan_project_migration(project_root: &Path) -> anyhow::Result<MigrationPlan> {
+    let (project, project_manifest) = plan_manifest(
+        &project_root.join(PROJECT_MANIFEST_FILE),
+        migrate_project_manifest,
+        |manifest: &ProjectManifest| manifest.format_version,
+    );
+    let layout = project_layout(project_root, &project_manifest.unwrap_or_default());
+
+    let mut worlds = Vec::new();
+    for world_id in list_world_ids(&layout)? {
+        let world = world_layout(&layout, &world_id);
+        let (manifest_plan, manifest) = plan_manifest(
+            &world.world_root.join(WORLD_MANIFEST_FILE),
+            migrate_world_manifest,
+            |manifest: &WorldManifest| manifest.format_version,
+        );
+        let mut plan = WorldMigrationPlan {
+            world_id,
+            manifest: manifest_plan,
+            tiles_scanned: 0,
+            sections: Vec::new(),
+            failed: Vec::new(),
+        };
+        if let Some(manifest) = manifest {
+            for path in world_tile_paths(&world, &manifest)? {
+                plan.tiles_scanned += 1;
+                match plan_tile_sections(&path) {
+                    Ok(sections) => plan.sections.extend(sections),
+                    Err(err) => plan.failed.push((path, format!("{:#}", err))),
+                }
+            }
+        }
+        worlds.push(plan);
+    }
+    Ok(MigrationPlan { project, worlds })
+}
+
+/// Plans one manifest file. Returns the parsed (unmigrated) manifest when it could be read.
+fn plan_manifest<T>(
+    path: &Path,
+    migrate: fn(&mut T) -> anyhow::Result<()>,
+    version: fn(&T) -> u32,
+) -> (ManifestPlan, Option<T>)
+where
+    T: Clone + Serialize + DeserializeOwned,
+{
+    let mut plan = ManifestPlan {
+        path: path.to_path_buf(),
+        from: 0,
+        to: 0,
+        defaulted: Vec::new(),
+        dropped: Vec::new(),
+        error: None,
+    };
+    let parsed = std::fs::read_to_string(path)
+        .with_context(|| format!("read manifest {:?}", path))
+        .and_then(|text| {
+            let raw: toml::Value = toml::from_str(&text)?;
+            let manifest: T = toml::from_str(&text)?;
+            Ok((raw, manifest))
+        });
+    let (raw, manifest) = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            plan.error = Some(format!("{:#}", err));
+            return (plan, None);
+        }
+    };
+
+    plan.from = version(&manifest);
+    let mut migrated = manifest.clone();
+    if let Err(err) = migrate(&mut migrated) {
+        plan.to = plan.from;
+        plan.error = Some(format!("{:#}", err));
+        return (plan, Some(manifest));
+    }
+    plan.to = version(&migrated);
+    match toml::Value::try_from(&migrated) {
+        Ok(written) => diff_keys(&raw, &written, "", &mut plan.defaulted, &mut plan.dropped),
+        Err(err) => plan.error = Some(format!("serialize manifest: {err}")),
+    }
+    (plan, Some(manifest))
+}
+
+/// Collects dotted key paths present only in `written` (defaulted) or only in `raw` (dropped).
+fn diff_keys(
+    raw: &toml::Value,
+    written: &toml::Value,
+    prefix: &str,
+    defaulted: &mut Vec<String>,
+    dropped: &mut Vec<String>,
+) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+    match (raw, written) {
+        (toml::Value::Table(raw), toml::Value::Table(written)) => {
+            for (key, value) in written {
+                match raw.get(key) {
+                    Some(raw_value) => diff_keys(raw_value, value, &join(key), defaulted, dropped),
+                    None => defaulted.push(join(key)),
+                }
+            }
+            for key in raw.keys() {
+                if !written.contains_key(key) {
+                    dropped.push(join(key));
+                }
+            }
+        }
+        (toml::Value::Array(raw), toml::Value::Array(written)) => {
+            for (index, (raw_value, value)) in raw.iter().zip(written).enumerate() {
+                diff_keys(
+                    raw_value,
+                    value,
+                    &format!("{prefix}[{index}]"),
+                    defaulted,
+                    dropped,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn plan_tile_sections(path: &Path) -> anyhow::Result<Vec<PlannedSection>> {
+    let reader = TileContainerReader::open_buffered(path)?;
+    let mut planned = Vec::new();
+    for entry in &reader.directory {
+        let Some(current) = current_section_version(entry.tag) else {
+            continue;
+        };
+        let decoded = reader.decode_section_cow(entry.tag)?;
+        let from = payload_version(&decoded)?;
+        if from == current {
+            continue;
+        }
+        let chain = section_chain(entry.tag, from, current)?;
+        planned.push(PlannedSection {
+            path: path.to_path_buf(),
+            tag: entry.tag.as_string(),
+            from,
+            to: current,
+            notes: chain
+                .iter()
+                .filter(|migration| !migration.note.is_empty())
+                .map(|migration| {
+                    format!("{} -> {}: {}", migration.from, migration.to, migration.note)
+                })
+                .collect(),
+        });
+    }
+    Ok(planned)
+}
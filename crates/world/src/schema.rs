@@ -42,6 +42,8 @@ pub struct ProjectManifest {
     pub assets_dir: String,
     pub exports_dir: String,
     pub cache_dir: String,
+    /// Custom liquid kind names allowed in LIQD sections (`LiqdKind::Custom`).
+    pub liquid_kinds: Vec<String>,
 }
 
 impl Default for ProjectManifest {
@@ -55,6 +57,7 @@ impl Default for ProjectManifest {
             assets_dir: "assets".to_string(),
             exports_dir: "exports".to_string(),
             cache_dir: "cache".to_string(),
+            liquid_kinds: Vec::new(),
         }
     }
 }
@@ -0,0 +1,206 @@
+//! One-shot migration of legacy per-file tile folders (`<x>_<y>/terrain.height.bin`, ...) into
+//! `.tile` containers.
+//!
+//! Each tile is migrated independently: the container is written, reloaded and compared with
+//! the legacy data before the folder is quarantined or deleted. Re-running the migration after
+//! an interruption picks up the remaining folders; a folder whose container already holds the
+//! same data is only disposed of.
+
+use crate::schema::WorldManifest;
+use crate::storage::liquids::LIQUIDS_META_FILE;
+use crate::storage::props::PROPS_INSTANCES_FILE;
+use crate::storage::{
+    load_tile_stub, quarantine_tile_dir, read_liquids_mask, read_liquids_meta,
+    read_props_instances, read_terrain_height, read_tile_meta, region_tiles_dir, save_tile_stub,
+    tile_container_path, tile_dir, LiquidsMeta, PropsInstances, TileStub, WorldLayout,
+};
+use anyhow::{bail, Context};
+use foundation::ids::{TileCoord, TileId};
+use serde::Serialize;
+use std::fs;
+
+/// What happens to a legacy folder once its container has been verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyDisposition {
+    Quarantine,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LegacyTile {
+    pub region_id: String,
+    pub tile_id: TileId,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LegacyMigrationReport {
+    /// Tiles converted into a new container.
+    pub migrated: Vec<LegacyTile>,
+    /// Tiles whose container already matched the legacy data (e.g. an interrupted earlier run).
+    pub already_migrated: Vec<LegacyTile>,
+    /// Tiles left untouched, with the reason.
+    pub failed: Vec<(LegacyTile, String)>,
+}
+
+impl LegacyMigrationReport {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Lists legacy tile folders in every region of the world.
+pub fn find_legacy_tiles(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+) -> anyhow::Result<Vec<LegacyTile>> {
+    let mut tiles = Vec::new();
+    for region in &manifest.regions {
+        let dir = region_tiles_dir(layout, &region.region_id);
+        if !dir.exists() {
+            continue;
+        }
+        for entry in fs::read_dir(&dir).with_context(|| format!("read tiles dir {:?}", dir))? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(tile_id) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(parse_legacy_dir_name)
+            else {
+                continue;
+            };
+            tiles.push(LegacyTile {
+                region_id: region.region_id.clone(),
+                tile_id,
+            });
+        }
+    }
+    tiles.sort_by_key(|tile| {
+        (
+            tile.region_id.clone(),
+            tile.tile_id.coord.x,
+            tile.tile_id.coord.y,
+        )
+    });
+    Ok(tiles)
+}
+
+/// Converts every legacy tile folder of the world into a container. Failures are collected per
+/// tile and leave that tile's legacy folder in place.
+pub fn migrate_legacy_tiles(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+    disposition: LegacyDisposition,
+) -> anyhow::Result<LegacyMigrationReport> {
+    let mut report = LegacyMigrationReport::default();
+    for tile in find_legacy_tiles(layout, manifest)? {
+        match migrate_legacy_tile(layout, manifest, &tile, disposition) {
+            Ok(true) => report.migrated.push(tile),
+            Ok(false) => report.already_migrated.push(tile),
+            Err(err) => {
+                let reason = format!("{:#}", err);
+                report.failed.push((tile, reason));
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Returns `true` when a container was written, `false` when a matching one already existed.
+fn migrate_legacy_tile(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+    tile: &LegacyTile,
+    disposition: LegacyDisposition,
+) -> anyhow::Result<bool> {
+    let region = tile.region_id.as_str();
+    let stub = read_legacy_stub(layout, region, tile.tile_id)?;
+    let container = tile_container_path(layout, region, tile.tile_id);
+
+    let written = if container.exists() {
+        let existing = load_tile_stub(layout, region, tile.tile_id)
+            .context("existing container is unreadable")?;
+        if !same_tile_data(&existing, &stub) {
+            bail!("existing container differs from legacy data");
+        }
+        false
+    } else {
+        save_tile_stub(layout, manifest, region, tile.tile_id, &stub)?;
+        let reloaded = load_tile_stub(layout, region, tile.tile_id);
+        if !reloaded.is_ok_and(|reloaded| same_tile_data(&reloaded, &stub)) {
+            fs::remove_file(&container)
+                .with_context(|| format!("remove unverified container {:?}", container))?;
+            bail!("container round-trip does not match legacy data");
+        }
+        true
+    };
+
+    match disposition {
+        LegacyDisposition::Quarantine => {
+            quarantine_tile_dir(layout, region, tile.tile_id, "migrated to .tile container")?;
+        }
+        LegacyDisposition::Delete => {
+            let dir = tile_dir(layout, region, tile.tile_id);
+            fs::remove_dir_all(&dir).with_context(|| format!("remove legacy tile {:?}", dir))?;
+        }
+    }
+    Ok(written)
+}
+
+/// Reads a legacy folder. Liquid bodies and props are optional; older folders may lack them.
+fn read_legacy_stub(
+    layout: &WorldLayout,
+    region: &str,
+    tile_id: TileId,
+) -> anyhow::Result<TileStub> {
+    let dir = tile_dir(layout, region, tile_id);
+    let meta = read_tile_meta(layout, region, tile_id)?;
+    if meta.tile_id != tile_id {
+        bail!(
+            "tile meta id ({}, {}) does not match folder",
+            meta.tile_id.coord.x,
+            meta.tile_id.coord.y
+        );
+    }
+    let liquids_meta = if dir.join(LIQUIDS_META_FILE).exists() {
+        read_liquids_meta(layout, region, tile_id)?
+    } else {
+        LiquidsMeta::new(Vec::new())
+    };
+    let props = if dir.join(PROPS_INSTANCES_FILE).exists() {
+        read_props_instances(layout, region, tile_id)?
+    } else {
+        PropsInstances::new(Vec::new())
+    };
+    Ok(TileStub {
+        meta,
+        terrain: read_terrain_height(layout, region, tile_id)?,
+        liquids_mask: read_liquids_mask(layout, region, tile_id)?,
+        liquids_meta,
+        props,
+    })
+}
+
+/// Compares tile content, ignoring the per-file `format_version` stamps that containers do not
+/// carry.
+fn same_tile_data(a: &TileStub, b: &TileStub) -> bool {
+    a.meta.tile_id == b.meta.tile_id
+        && a.terrain.size == b.terrain.size
+        && a.terrain.samples == b.terrain.samples
+        && a.liquids_mask.size == b.liquids_mask.size
+        && a.liquids_mask.mask == b.liquids_mask.mask
+        && a.liquids_meta.bodies == b.liquids_meta.bodies
+        && a.props.instances == b.props.instances
+}
+
+fn parse_legacy_dir_name(name: &str) -> Option<TileId> {
+    let (x, y) = name.split_once('_')?;
+    Some(TileId {
+        coord: TileCoord {
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+        },
+    })
+}
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 
 const LIQUIDS_MASK_FILE: &str = "liquids.mask.bin";
-const LIQUIDS_META_FILE: &str = "liquids.meta.json";
+pub(crate) const LIQUIDS_META_FILE: &str = "liquids.meta.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LiquidsMask {
@@ -2,7 +2,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::Context;
-use foundation::ids::TileId;
+use foundation::ids::{TileCoord, TileId};
 
 use crate::schema::{ProjectManifest, RegionManifest, WorldManifest};
 use crate::tile_container::{
@@ -10,6 +10,7 @@ use crate::tile_container::{
     TileSectionPayload, TileSectionTag, DEFAULT_ALIGNMENT,
 };
 
+pub mod legacy;
 pub mod liquids;
 pub mod manifest;
 pub mod props;
@@ -17,6 +18,9 @@ pub mod quarantine;
 pub mod terrain;
 pub mod tile_meta;
 
+pub use legacy::{
+    find_legacy_tiles, migrate_legacy_tiles, LegacyDisposition, LegacyMigrationReport, LegacyTile,
+};
 pub use liquids::{
     read_liquids_mask, read_liquids_meta, write_liquids_mask, write_liquids_meta, LiquidBody,
     LiquidKind, LiquidsMask, LiquidsMeta,
@@ -26,7 +30,11 @@ pub use manifest::{
     PROJECT_MANIFEST_FILE, WORLD_MANIFEST_FILE,
 };
 pub use props::{read_props_instances, write_props_instances, PropInstance, PropsInstances};
-pub use quarantine::{quarantine_tile_dir, quarantine_tile_file};
+pub use quarantine::{
+    list_quarantined, purge_quarantined, quarantine_tile_dir, quarantine_tile_file,
+    quarantine_tile_file_with_details, restore_quarantined, QuarantineEntry, QuarantineKind,
+    RestoreOutcome, QUARANTINE_MANIFEST_FILE,
+};
 pub use terrain::{read_terrain_height, write_terrain_height, TerrainHeight};
 pub use tile_meta::{read_tile_meta, write_tile_meta, TileMeta};
 
@@ -83,6 +91,27 @@ pub fn world_layout(project_layout: &ProjectLayout, world_id: &str) -> WorldLayo
     }
 }
 
+/// World directory names under the project's worlds dir, sorted. Missing dir yields none.
+pub fn list_world_ids(layout: &ProjectLayout) -> anyhow::Result<Vec<String>> {
+    if !layout.worlds_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut world_ids = Vec::new();
+    for entry in fs::read_dir(&layout.worlds_dir)
+        .with_context(|| format!("read worlds dir {:?}", layout.worlds_dir))?
+    {
+        let entry = entry?;
+        if !entry.path().is_dir() {
+            continue;
+        }
+        if let Some(name) = entry.file_name().to_str() {
+            world_ids.push(name.to_string());
+        }
+    }
+    world_ids.sort();
+    Ok(world_ids)
+}
+
 pub fn create_project(
     project_root: &Path,
     manifest: &ProjectManifest,
@@ -140,6 +169,46 @@ pub fn tile_container_path(layout: &WorldLayout, region: &str, tile_id: TileId)
     region_tiles_dir(layout, region).join(format!("x{}_y{}.tile", tile_id.coord.x, tile_id.coord.y))
 }
 
+/// Inverse of the file name [`tile_container_path`] gives a tile, e.g. `x3_y-2.tile`.
+pub fn parse_tile_container_filename(name: &str) -> Option<TileId> {
+    let stem = name.strip_suffix(".tile")?;
+    let mut parts = stem.split('_');
+    let x_part = parts.next()?;
+    let y_part = parts.next()?;
+    if !x_part.starts_with('x') || !y_part.starts_with('y') {
+        return None;
+    }
+    let x = x_part[1..].parse::<i32>().ok()?;
+    let y = y_part[1..].parse::<i32>().ok()?;
+    Some(TileId {
+        coord: TileCoord { x, y },
+    })
+}
+
+/// Tiles with a container file in the region's tiles dir, sorted. Missing dir yields none.
+pub fn list_tile_containers(layout: &WorldLayout, region_id: &str) -> anyhow::Result<Vec<TileId>> {
+    let dir = region_tiles_dir(layout, region_id);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut tiles = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("read tiles dir {:?}", dir))? {
+        let entry = entry?;
+        if !entry.path().is_file() {
+            continue;
+        }
+        if let Some(tile_id) = entry
+            .file_name()
+            .to_str()
+            .and_then(parse_tile_container_filename)
+        {
+            tiles.push(tile_id);
+        }
+    }
+    tiles.sort_by_key(|tile_id| (tile_id.coord.y, tile_id.coord.x));
+    Ok(tiles)
+}
+
 pub(crate) fn tile_dir(layout: &WorldLayout, region: &str, tile_id: TileId) -> PathBuf {
     region_tiles_dir(layout, region).join(format!("{}_{}", tile_id.coord.x, tile_id.coord.y))
 }
@@ -227,10 +296,10 @@ pub fn save_tile_stub(
     });
     writer.add_section(TileSectionPayload {
         tag: TileSectionTag::LIQD,
-        section_version: 1,
+        section_version: 2,
         codec: 0,
         flags: 0,
-        decoded: encode_liqd(&liqd),
+        decoded: encode_liqd(&liqd)?,
     });
     writer.add_section(TileSectionPayload {
         tag: TileSectionTag::PROP,
@@ -255,11 +324,12 @@ pub fn load_tile_stub(
     tile_id: TileId,
 ) -> anyhow::Result<TileStub> {
     let path = tile_container_path(layout, region, tile_id);
-    let reader = crate::tile_container::TileContainerReader::open(&path)?;
-    let meta = crate::tile_container::decode_meta(&reader.decode_section(TileSectionTag::META)?)?;
-    let hmap = crate::tile_container::decode_hmap(&reader.decode_section(TileSectionTag::HMAP)?)?;
-    let liqd = crate::tile_container::decode_liqd(&reader.decode_section(TileSectionTag::LIQD)?)?;
-    let prop = crate::tile_container::decode_prop(&reader.decode_section(TileSectionTag::PROP)?)?;
+    let reader = crate::tile_container::TileContainerReader::open_buffered(&path)?;
+    let section = |tag| crate::tile_container::decode_current_section(&reader, tag);
+    let meta = crate::tile_container::decode_meta(&section(TileSectionTag::META)?)?;
+    let hmap = crate::tile_container::decode_hmap(&section(TileSectionTag::HMAP)?)?;
+    let liqd = crate::tile_container::decode_liqd(&section(TileSectionTag::LIQD)?)?;
+    let prop = crate::tile_container::decode_prop(&section(TileSectionTag::PROP)?)?;
 
     Ok(TileStub {
         meta: TileMeta {
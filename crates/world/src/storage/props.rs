@@ -5,7 +5,7 @@ use foundation::ids::{AssetId, InstanceId, TileId};
 use serde::{Deserialize, Serialize};
 use std::fs;
 
-const PROPS_INSTANCES_FILE: &str = "props.instances.bin";
+pub(crate) const PROPS_INSTANCES_FILE: &str = "props.instances.bin";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PropsInstances {
@@ -1,13 +1,75 @@
+//! Moves broken tiles out of a world into `regions/_quarantine/<timestamp>-<seq>/<region>/`, with
+//! a `quarantine.toml` beside them recording why, and lists, restores or purges them later.
+
 use crate::storage::{tile_container_path, tile_dir, WorldLayout};
 use anyhow::Context;
-use foundation::ids::TileId;
+use foundation::ids::{TileCoord, TileId};
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+pub const QUARANTINE_MANIFEST_FILE: &str = "quarantine.toml";
+
 static QUARANTINE_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// What was moved into quarantine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuarantineKind {
+    /// A `.tile` container.
+    TileFile,
+    /// A legacy per-tile directory.
+    TileDir,
+}
+
+impl QuarantineKind {
+    fn suffix(self) -> &'static str {
+        match self {
+            QuarantineKind::TileFile => "tile",
+            QuarantineKind::TileDir => "dir",
+        }
+    }
+}
+
+/// Contents of `quarantine.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct QuarantineManifest {
+    region_id: String,
+    tile: TileCoord,
+    kind: QuarantineKind,
+    reason: String,
+    quarantined_at_ms: u64,
+    #[serde(default)]
+    details: Vec<String>,
+}
+
+/// One quarantined tile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantineEntry {
+    /// Name of the entry's folder (`<timestamp>-<seq>`) under the world's quarantine dir.
+    pub id: String,
+    pub region_id: String,
+    pub tile: TileCoord,
+    pub kind: QuarantineKind,
+    /// The quarantined file or directory.
+    pub path: PathBuf,
+    /// `None` for entries quarantined before reasons were recorded.
+    pub reason: Option<String>,
+    /// Issue summaries recorded when the tile was quarantined.
+    pub details: Vec<String>,
+    pub quarantined_at_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestoreOutcome {
+    /// The tile is back at this path and the entry is gone.
+    Restored(PathBuf),
+    /// A tile was written at the original location since; nothing was moved.
+    Conflict { existing: PathBuf },
+}
+
 pub fn quarantine_tile_dir(
     layout: &WorldLayout,
     region: &str,
@@ -15,18 +77,15 @@ pub fn quarantine_tile_dir(
     reason: &str,
 ) -> anyhow::Result<PathBuf> {
     let source = tile_dir(layout, region, tile_id);
-    let target = quarantine_path(layout, region, tile_id, "dir")?;
-    fs::create_dir_all(
-        target
-            .parent()
-            .ok_or_else(|| anyhow::anyhow!("invalid quarantine path"))?,
+    quarantine_item(
+        layout,
+        region,
+        tile_id,
+        QuarantineKind::TileDir,
+        &source,
+        reason,
+        &[],
     )
-    .with_context(|| format!("create quarantine dir {:?}", target))?;
-    if source.exists() {
-        fs::rename(&source, &target)
-            .with_context(|| format!("quarantine tile {:?}: {}", source, reason))?;
-    }
-    Ok(target)
 }
 
 pub fn quarantine_tile_file(
@@ -34,40 +93,238 @@ pub fn quarantine_tile_file(
     region: &str,
     tile_id: TileId,
     reason: &str,
+) -> anyhow::Result<PathBuf> {
+    quarantine_tile_file_with_details(layout, region, tile_id, reason, &[])
+}
+
+/// Like [`quarantine_tile_file`], also recording `details` (e.g. one line per validation issue)
+/// in the entry's manifest.
+pub fn quarantine_tile_file_with_details(
+    layout: &WorldLayout,
+    region: &str,
+    tile_id: TileId,
+    reason: &str,
+    details: &[String],
 ) -> anyhow::Result<PathBuf> {
     let source = tile_container_path(layout, region, tile_id);
-    let target = quarantine_path(layout, region, tile_id, "tile")?;
-    fs::create_dir_all(
-        target
-            .parent()
-            .ok_or_else(|| anyhow::anyhow!("invalid quarantine path"))?,
+    quarantine_item(
+        layout,
+        region,
+        tile_id,
+        QuarantineKind::TileFile,
+        &source,
+        reason,
+        details,
     )
-    .with_context(|| format!("create quarantine dir {:?}", target))?;
-    if source.exists() {
-        fs::rename(&source, &target)
-            .with_context(|| format!("quarantine tile {:?}: {}", source, reason))?;
+}
+
+/// Lists the world's quarantined tiles, oldest first. Entries without a usable manifest
+/// (quarantined by older versions, damaged, or naming a region that is not a plain folder name)
+/// are inferred from their layout and have no reason.
+pub fn list_quarantined(layout: &WorldLayout) -> anyhow::Result<Vec<QuarantineEntry>> {
+    if !layout.quarantine_dir.exists() {
+        return Ok(Vec::new());
     }
-    Ok(target)
+    let mut entries = Vec::new();
+    for dir_entry in fs::read_dir(&layout.quarantine_dir)
+        .with_context(|| format!("read quarantine dir {:?}", layout.quarantine_dir))?
+    {
+        let entry_dir = dir_entry?.path();
+        let Some(id) = entry_dir.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !entry_dir.is_dir() {
+            continue;
+        }
+        if let Some(manifest) = read_manifest(&entry_dir.join(QUARANTINE_MANIFEST_FILE))
+            .filter(|manifest| is_plain_name(&manifest.region_id))
+        {
+            let path = entry_dir
+                .join(&manifest.region_id)
+                .join(item_name(manifest.tile, manifest.kind));
+            if path.exists() {
+                entries.push(QuarantineEntry {
+                    id: id.to_string(),
+                    region_id: manifest.region_id,
+                    tile: manifest.tile,
+                    kind: manifest.kind,
+                    path,
+                    reason: Some(manifest.reason),
+                    details: manifest.details,
+                    quarantined_at_ms: manifest.quarantined_at_ms,
+                });
+            }
+            continue;
+        }
+
+        let quarantined_at_ms = id
+            .split('-')
+            .next()
+            .and_then(|timestamp| timestamp.parse().ok())
+            .unwrap_or(0);
+        let Ok(region_entries) = fs::read_dir(&entry_dir) else {
+            continue;
+        };
+        for region_entry in region_entries.flatten() {
+            let region_dir = region_entry.path();
+            let Some(region_id) = region_dir.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Ok(items) = fs::read_dir(&region_dir) else {
+                continue;
+            };
+            for item in items.flatten() {
+                let path = item.path();
+                let Some((tile, kind)) = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(parse_item_name)
+                else {
+                    continue;
+                };
+                entries.push(QuarantineEntry {
+                    id: id.to_string(),
+                    region_id: region_id.to_string(),
+                    tile,
+                    kind,
+                    path,
+                    reason: None,
+                    details: Vec::new(),
+                    quarantined_at_ms,
+                });
+            }
+        }
+    }
+    entries.sort_by(|a, b| {
+        (a.quarantined_at_ms, &a.id, &a.path).cmp(&(b.quarantined_at_ms, &b.id, &b.path))
+    });
+    Ok(entries)
+}
+
+/// `None` when the manifest is missing, unreadable or malformed.
+fn read_manifest(path: &Path) -> Option<QuarantineManifest> {
+    let text = fs::read_to_string(path).ok()?;
+    toml::from_str(&text).ok()
+}
+
+/// Moves a quarantined tile back to its original location and removes the entry. If a tile has
+/// been written there since, nothing is moved and `Conflict` is returned, unless `overwrite` is
+/// set: then the newer tile is quarantined in turn (never deleted) before the restore.
+pub fn restore_quarantined(
+    layout: &WorldLayout,
+    entry: &QuarantineEntry,
+    overwrite: bool,
+) -> anyhow::Result<RestoreOutcome> {
+    let entry_dir = entry_dir(layout, entry)?;
+    if !is_plain_name(&entry.region_id) {
+        anyhow::bail!("invalid quarantine region id {:?}", entry.region_id);
+    }
+    let tile_id = TileId { coord: entry.tile };
+    let target = match entry.kind {
+        QuarantineKind::TileFile => tile_container_path(layout, &entry.region_id, tile_id),
+        QuarantineKind::TileDir => tile_dir(layout, &entry.region_id, tile_id),
+    };
+    if target.exists() {
+        if !overwrite {
+            return Ok(RestoreOutcome::Conflict { existing: target });
+        }
+        quarantine_item(
+            layout,
+            &entry.region_id,
+            tile_id,
+            entry.kind,
+            &target,
+            &format!("replaced by restoring quarantine entry {}", entry.id),
+            &[],
+        )?;
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("create tiles dir {:?}", parent))?;
+    }
+    fs::rename(&entry.path, &target)
+        .with_context(|| format!("restore quarantined tile {:?}", entry.path))?;
+    fs::remove_dir_all(&entry_dir)
+        .with_context(|| format!("remove quarantine entry {:?}", entry_dir))?;
+    Ok(RestoreOutcome::Restored(target))
 }
 
-fn quarantine_path(
+/// Deletes a quarantine entry, including the quarantined tile.
+pub fn purge_quarantined(layout: &WorldLayout, entry: &QuarantineEntry) -> anyhow::Result<()> {
+    let entry_dir = entry_dir(layout, entry)?;
+    fs::remove_dir_all(&entry_dir)
+        .with_context(|| format!("purge quarantine entry {:?}", entry_dir))
+}
+
+fn quarantine_item(
     layout: &WorldLayout,
     region: &str,
     tile_id: TileId,
-    suffix: &str,
+    kind: QuarantineKind,
+    source: &Path,
+    reason: &str,
+    details: &[String],
 ) -> anyhow::Result<PathBuf> {
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
-        .as_millis();
+        .as_millis() as u64;
     let seq = QUARANTINE_COUNTER.fetch_add(1, Ordering::Relaxed);
-    let target = layout
-        .quarantine_dir
-        .join(format!("{timestamp}-{seq}"))
-        .join(region)
-        .join(format!(
-            "x{}_y{}.{}",
-            tile_id.coord.x, tile_id.coord.y, suffix
-        ));
+    let entry_dir = layout.quarantine_dir.join(format!("{timestamp}-{seq}"));
+    let target = entry_dir.join(region).join(item_name(tile_id.coord, kind));
+    fs::create_dir_all(
+        target
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("invalid quarantine path"))?,
+    )
+    .with_context(|| format!("create quarantine dir {:?}", target))?;
+    if source.exists() {
+        fs::rename(source, &target)
+            .with_context(|| format!("quarantine tile {:?}: {}", source, reason))?;
+        let manifest = QuarantineManifest {
+            region_id: region.to_string(),
+            tile: tile_id.coord,
+            kind,
+            reason: reason.to_string(),
+            quarantined_at_ms: timestamp,
+            details: details.to_vec(),
+        };
+        let manifest_path = entry_dir.join(QUARANTINE_MANIFEST_FILE);
+        fs::write(&manifest_path, toml::to_string_pretty(&manifest)?)
+            .with_context(|| format!("write quarantine manifest {:?}", manifest_path))?;
+    }
     Ok(target)
 }
+
+/// The entry's folder, refusing ids that would point outside the quarantine dir.
+fn entry_dir(layout: &WorldLayout, entry: &QuarantineEntry) -> anyhow::Result<PathBuf> {
+    if !is_plain_name(&entry.id) {
+        anyhow::bail!("invalid quarantine entry id {:?}", entry.id);
+    }
+    Ok(layout.quarantine_dir.join(&entry.id))
+}
+
+/// True for a single path component such as a folder name; rejects `..`, separators and roots.
+fn is_plain_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+fn item_name(tile: TileCoord, kind: QuarantineKind) -> String {
+    format!("x{}_y{}.{}", tile.x, tile.y, kind.suffix())
+}
+
+fn parse_item_name(name: &str) -> Option<(TileCoord, QuarantineKind)> {
+    let (stem, kind) = if let Some(stem) = name.strip_suffix(".tile") {
+        (stem, QuarantineKind::TileFile)
+    } else {
+        (name.strip_suffix(".dir")?, QuarantineKind::TileDir)
+    };
+    let (x, y) = stem.split_once('_')?;
+    let x = x.strip_prefix('x')?.parse().ok()?;
+    let y = y.strip_prefix('y')?.parse().ok()?;
+    Some((TileCoord { x, y }, kind))
+}
@@ -0,0 +1,380 @@
+//! Chunk-addressable layout for grid sections (HMAP/WMAP/LIQD), container v2.
+//!
+//! A chunked section stores the section's decoded bytes split into one "base" block (the section
+//! header plus any non-grid trailer, e.g. LIQD bodies) and one block per chunk of the grid. Each
+//! block carries its own length and CRC and is compressed independently with the section codec,
+//! so a single chunk can be read and verified without touching the rest of the section.
+
+use crate::tile_container::{decode_payload, encode_payload, TileSectionTag};
+use anyhow::{anyhow, bail};
+use crc32fast::Hasher;
+use foundation::ids::ChunkCoord;
+
+pub const CHUNKED_LAYOUT_VERSION: u16 = 1;
+pub const CHUNKED_HEADER_SIZE: usize = 24;
+pub const CHUNK_TABLE_ENTRY_SIZE: usize = 16;
+
+const CHUNKED_FLAG_SHARED_EDGES: u16 = 1;
+
+/// Decoded grid bytes of one chunk, row-major over the chunk's rectangle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileChunk {
+    pub coord: ChunkCoord,
+    pub width: u16,
+    pub height: u16,
+    pub elem_size: u16,
+    pub data: Vec<u8>,
+}
+
+impl TileChunk {
+    /// Interprets the chunk as f32 grid samples (HMAP).
+    pub fn samples_f32(&self) -> anyhow::Result<Vec<f32>> {
+        if self.elem_size != 4 {
+            bail!("chunk element size {} is not f32", self.elem_size);
+        }
+        Ok(self
+            .data
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChunkedHeader {
+    pub chunks_x: u16,
+    pub chunks_y: u16,
+    pub grid_width: u16,
+    pub grid_height: u16,
+    pub elem_size: u16,
+    pub shared_edges: bool,
+    pub prefix_len: u32,
+    pub table_crc: u32,
+}
+
+impl ChunkedHeader {
+    pub fn block_count(&self) -> usize {
+        1 + self.chunks_x as usize * self.chunks_y as usize
+    }
+
+    pub fn table_len(&self) -> usize {
+        self.block_count() * CHUNK_TABLE_ENTRY_SIZE
+    }
+
+    fn to_bytes(self) -> [u8; CHUNKED_HEADER_SIZE] {
+        let mut bytes = [0u8; CHUNKED_HEADER_SIZE];
+        let flags = if self.shared_edges {
+            CHUNKED_FLAG_SHARED_EDGES
+        } else {
+            0
+        };
+        bytes[0..2].copy_from_slice(&CHUNKED_LAYOUT_VERSION.to_le_bytes());
+        bytes[2..4].copy_from_slice(&flags.to_le_bytes());
+        bytes[4..6].copy_from_slice(&self.chunks_x.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.chunks_y.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.grid_width.to_le_bytes());
+        bytes[10..12].copy_from_slice(&self.grid_height.to_le_bytes());
+        bytes[12..14].copy_from_slice(&self.elem_size.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.prefix_len.to_le_bytes());
+        bytes[20..24].copy_from_slice(&self.table_crc.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() < CHUNKED_HEADER_SIZE {
+            bail!("chunked section header truncated");
+        }
+        let version = u16::from_le_bytes(bytes[0..2].try_into()?);
+        if version != CHUNKED_LAYOUT_VERSION {
+            bail!("unsupported chunked layout version {}", version);
+        }
+        let flags = u16::from_le_bytes(bytes[2..4].try_into()?);
+        let header = Self {
+            chunks_x: u16::from_le_bytes(bytes[4..6].try_into()?),
+            chunks_y: u16::from_le_bytes(bytes[6..8].try_into()?),
+            grid_width: u16::from_le_bytes(bytes[8..10].try_into()?),
+            grid_height: u16::from_le_bytes(bytes[10..12].try_into()?),
+            elem_size: u16::from_le_bytes(bytes[12..14].try_into()?),
+            shared_edges: flags & CHUNKED_FLAG_SHARED_EDGES != 0,
+            prefix_len: u32::from_le_bytes(bytes[16..20].try_into()?),
+            table_crc: u32::from_le_bytes(bytes[20..24].try_into()?),
+        };
+        if header.chunks_x == 0 || header.chunks_y == 0 || header.elem_size == 0 {
+            bail!("chunked section header has empty dimensions");
+        }
+        Ok(header)
+    }
+
+    /// Grid rectangle `(x0, y0, width, height)` covered by a chunk.
+    pub fn chunk_rect(&self, coord: ChunkCoord) -> anyhow::Result<(usize, usize, usize, usize)> {
+        if coord.x >= self.chunks_x || coord.y >= self.chunks_y {
+            bail!(
+                "chunk ({}, {}) outside {}x{} chunk grid",
+                coord.x,
+                coord.y,
+                self.chunks_x,
+                self.chunks_y
+            );
+        }
+        let (step_x, extra) = chunk_step(self.grid_width, self.chunks_x, self.shared_edges)?;
+        let (step_y, _) = chunk_step(self.grid_height, self.chunks_y, self.shared_edges)?;
+        Ok((
+            coord.x as usize * step_x,
+            coord.y as usize * step_y,
+            step_x + extra,
+            step_y + extra,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChunkTableEntry {
+    pub offset: u32,
+    pub stored_len: u32,
+    pub decoded_len: u32,
+    pub crc32: u32,
+}
+
+impl ChunkTableEntry {
+    fn to_bytes(self) -> [u8; CHUNK_TABLE_ENTRY_SIZE] {
+        let mut bytes = [0u8; CHUNK_TABLE_ENTRY_SIZE];
+        bytes[0..4].copy_from_slice(&self.offset.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.stored_len.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.decoded_len.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.crc32.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self {
+            offset: u32::from_le_bytes(bytes[0..4].try_into()?),
+            stored_len: u32::from_le_bytes(bytes[4..8].try_into()?),
+            decoded_len: u32::from_le_bytes(bytes[8..12].try_into()?),
+            crc32: u32::from_le_bytes(bytes[12..16].try_into()?),
+        })
+    }
+}
+
+/// Grid geometry of a decoded section payload.
+struct GridLayout {
+    prefix_len: usize,
+    width: u16,
+    height: u16,
+    elem_size: u16,
+    shared_edges: bool,
+}
+
+impl GridLayout {
+    fn grid_len(&self) -> usize {
+        self.width as usize * self.height as usize * self.elem_size as usize
+    }
+}
+
+fn grid_layout(tag: TileSectionTag, decoded: &[u8]) -> anyhow::Result<GridLayout> {
+    if decoded.len() < 12 {
+        bail!("section {} too small to chunk", tag);
+    }
+    let width = u16::from_le_bytes(decoded[4..6].try_into()?);
+    let height = u16::from_le_bytes(decoded[6..8].try_into()?);
+    let field = u16::from_le_bytes(decoded[8..10].try_into()?);
+    let layout = if tag == TileSectionTag::HMAP {
+        if field != 0 {
+            bail!("only f32 HMAP encoding can be chunked");
+        }
+        GridLayout {
+            prefix_len: 12,
+            width,
+            height,
+            elem_size: 4,
+            shared_edges: true,
+        }
+    } else if tag == TileSectionTag::WMAP {
+        GridLayout {
+            prefix_len: 12,
+            width,
+            height,
+            elem_size: field,
+            shared_edges: false,
+        }
+    } else if tag == TileSectionTag::LIQD {
+        GridLayout {
+            prefix_len: 12,
+            width,
+            height,
+            elem_size: 1,
+            shared_edges: false,
+        }
+    } else {
+        bail!("section {} is not a grid section", tag);
+    };
+    if layout.elem_size == 0 {
+        bail!("section {} has empty grid elements", tag);
+    }
+    if decoded.len() < layout.prefix_len + layout.grid_len() {
+        bail!("section {} grid truncated", tag);
+    }
+    Ok(layout)
+}
+
+/// Returns `(step, extra)` where chunk `i` covers `[i * step, i * step + step + extra)`.
+fn chunk_step(size: u16, chunks: u16, shared_edges: bool) -> anyhow::Result<(usize, usize)> {
+    let size = size as usize;
+    let chunks = chunks as usize;
+    let cells = if shared_edges {
+        size.checked_sub(1)
+            .ok_or_else(|| anyhow!("grid too small to chunk"))?
+    } else {
+        size
+    };
+    if chunks == 0 || cells == 0 || cells % chunks != 0 {
+        bail!("grid size {} is not divisible into {} chunks", size, chunks);
+    }
+    Ok((cells / chunks, usize::from(shared_edges)))
+}
+
+pub(crate) fn encode_chunked(
+    tag: TileSectionTag,
+    decoded: &[u8],
+    codec: u16,
+    chunks_per_tile: u16,
+) -> anyhow::Result<Vec<u8>> {
+    let layout = grid_layout(tag, decoded)?;
+    let mut header = ChunkedHeader {
+        chunks_x: chunks_per_tile,
+        chunks_y: chunks_per_tile,
+        grid_width: layout.width,
+        grid_height: layout.height,
+        elem_size: layout.elem_size,
+        shared_edges: layout.shared_edges,
+        prefix_len: layout.prefix_len as u32,
+        table_crc: 0,
+    };
+
+    let grid_start = layout.prefix_len;
+    let grid_end = grid_start + layout.grid_len();
+    let mut base = decoded[..grid_start].to_vec();
+    base.extend_from_slice(&decoded[grid_end..]);
+
+    let mut blocks = Vec::with_capacity(header.block_count());
+    blocks.push(base);
+    let row_len = layout.width as usize * layout.elem_size as usize;
+    let elem = layout.elem_size as usize;
+    for cy in 0..header.chunks_y {
+        for cx in 0..header.chunks_x {
+            let (x0, y0, w, h) = header.chunk_rect(ChunkCoord { x: cx, y: cy })?;
+            let mut block = Vec::with_capacity(w * h * elem);
+            for y in y0..y0 + h {
+                let start = grid_start + y * row_len + x0 * elem;
+                block.extend_from_slice(&decoded[start..start + w * elem]);
+            }
+            blocks.push(block);
+        }
+    }
+
+    let mut table = Vec::with_capacity(header.table_len());
+    let mut payloads = Vec::with_capacity(blocks.len());
+    let mut offset = CHUNKED_HEADER_SIZE + header.table_len();
+    for block in &blocks {
+        let stored = encode_payload(codec, block)?;
+        let entry = ChunkTableEntry {
+            offset: u32::try_from(offset).map_err(|_| anyhow!("chunked section too large"))?,
+            stored_len: stored.len() as u32,
+            decoded_len: block.len() as u32,
+            crc32: crc32(&stored),
+        };
+        table.extend_from_slice(&entry.to_bytes());
+        offset += stored.len();
+        payloads.push(stored);
+    }
+    header.table_crc = crc32(&table);
+
+    let mut out = Vec::with_capacity(offset);
+    out.extend_from_slice(&header.to_bytes());
+    out.extend_from_slice(&table);
+    for payload in payloads {
+        out.extend_from_slice(&payload);
+    }
+    Ok(out)
+}
+
+/// Parses and verifies the chunk table that follows a chunked section header.
+pub(crate) fn parse_chunk_table(
+    header: &ChunkedHeader,
+    table: &[u8],
+) -> anyhow::Result<Vec<ChunkTableEntry>> {
+    if table.len() < header.table_len() {
+        bail!("chunk table truncated");
+    }
+    let table = &table[..header.table_len()];
+    if crc32(table) != header.table_crc {
+        bail!("chunk table crc mismatch");
+    }
+    table
+        .chunks_exact(CHUNK_TABLE_ENTRY_SIZE)
+        .map(ChunkTableEntry::from_bytes)
+        .collect()
+}
+
+/// Verifies and decodes one block given its stored bytes.
+pub(crate) fn decode_block(
+    entry: &ChunkTableEntry,
+    stored: &[u8],
+    codec: u16,
+) -> anyhow::Result<Vec<u8>> {
+    if stored.len() != entry.stored_len as usize {
+        bail!("chunk block length mismatch");
+    }
+    if crc32(stored) != entry.crc32 {
+        bail!("chunk block crc mismatch");
+    }
+    decode_payload(codec, stored, u64::from(entry.decoded_len))
+}
+
+pub(crate) fn decode_chunked(stored: &[u8], codec: u16) -> anyhow::Result<Vec<u8>> {
+    let header = ChunkedHeader::from_bytes(stored)?;
+    let entries = parse_chunk_table(&header, &stored[CHUNKED_HEADER_SIZE..])?;
+    let block = |entry: &ChunkTableEntry| -> anyhow::Result<Vec<u8>> {
+        let start = entry.offset as usize;
+        let end = start
+            .checked_add(entry.stored_len as usize)
+            .filter(|end| *end <= stored.len())
+            .ok_or_else(|| anyhow!("chunk block out of bounds"))?;
+        decode_block(entry, &stored[start..end], codec)
+    };
+
+    let base = block(&entries[0])?;
+    let prefix_len = header.prefix_len as usize;
+    if base.len() < prefix_len {
+        bail!("chunked base block truncated");
+    }
+    let elem = header.elem_size as usize;
+    let row_len = header.grid_width as usize * elem;
+    let mut grid = vec![0u8; row_len * header.grid_height as usize];
+    for cy in 0..header.chunks_y {
+        for cx in 0..header.chunks_x {
+            let coord = ChunkCoord { x: cx, y: cy };
+            let (x0, y0, w, h) = header.chunk_rect(coord)?;
+            let index = 1 + cy as usize * header.chunks_x as usize + cx as usize;
+            let data = block(&entries[index])?;
+            if data.len() != w * h * elem {
+                bail!("chunk ({}, {}) has wrong size", cx, cy);
+            }
+            for (row, src) in data.chunks_exact(w * elem).enumerate() {
+                let start = (y0 + row) * row_len + x0 * elem;
+                grid[start..start + w * elem].copy_from_slice(src);
+            }
+        }
+    }
+
+    let mut decoded = Vec::with_capacity(base.len() + grid.len());
+    decoded.extend_from_slice(&base[..prefix_len]);
+    decoded.extend_from_slice(&grid);
+    decoded.extend_from_slice(&base[prefix_len..]);
+    Ok(decoded)
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut hasher = Hasher::new();
+    hasher.update(bytes);
+    hasher.finalize()
+}
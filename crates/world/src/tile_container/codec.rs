@@ -0,0 +1,49 @@
+use crate::tile_container::{CODEC_LZ4, CODEC_RAW, CODEC_ZSTD};
+use anyhow::{anyhow, bail};
+
+/// zstd level used for archival sections; favours ratio over encode speed.
+const ZSTD_LEVEL: i32 = 9;
+
+pub fn is_supported_codec(codec: u16) -> bool {
+    matches!(codec, CODEC_RAW | CODEC_LZ4 | CODEC_ZSTD)
+}
+
+pub fn codec_name(codec: u16) -> &'static str {
+    match codec {
+        CODEC_RAW => "raw",
+        CODEC_LZ4 => "lz4",
+        CODEC_ZSTD => "zstd",
+        _ => "unknown",
+    }
+}
+
+pub fn encode_payload(codec: u16, decoded: &[u8]) -> anyhow::Result<Vec<u8>> {
+    match codec {
+        CODEC_RAW => Ok(decoded.to_vec()),
+        CODEC_LZ4 => Ok(lz4_flex::block::compress(decoded)),
+        CODEC_ZSTD => zstd::bulk::compress(decoded, ZSTD_LEVEL)
+            .map_err(|err| anyhow!("zstd compress failed: {err}")),
+        other => bail!("unsupported codec {}", other),
+    }
+}
+
+pub fn decode_payload(codec: u16, stored: &[u8], decoded_len: u64) -> anyhow::Result<Vec<u8>> {
+    let expected =
+        usize::try_from(decoded_len).map_err(|_| anyhow!("decoded_len {decoded_len} too large"))?;
+    let decoded = match codec {
+        CODEC_RAW => stored.to_vec(),
+        CODEC_LZ4 => lz4_flex::block::decompress(stored, expected)
+            .map_err(|err| anyhow!("lz4 decompress failed: {err}"))?,
+        CODEC_ZSTD => zstd::bulk::decompress(stored, expected)
+            .map_err(|err| anyhow!("zstd decompress failed: {err}"))?,
+        other => bail!("unsupported codec {}", other),
+    };
+    if decoded.len() != expected {
+        bail!(
+            "decoded length {} does not match directory decoded_len {}",
+            decoded.len(),
+            expected
+        );
+    }
+    Ok(decoded)
+}
@@ -0,0 +1,93 @@
+//! Registry of known ADDX extension keys.
+//!
+//! Third-party tools register the keys they own so the validator can check their payloads.
+//! Keys that are not registered are carried through untouched and never fail validation.
+
+use crate::tile_container::{is_valid_addx_key, AddxEntry};
+use anyhow::bail;
+
+pub struct AddxExtension {
+    pub key: String,
+    pub min_version: u16,
+    pub max_version: u16,
+    pub validate: fn(u16, &[u8]) -> anyhow::Result<()>,
+}
+
+impl AddxExtension {
+    pub fn new(key: impl Into<String>, min_version: u16, max_version: u16) -> Self {
+        Self {
+            key: key.into(),
+            min_version,
+            max_version,
+            validate: |_, _| Ok(()),
+        }
+    }
+
+    pub fn with_validator(mut self, validate: fn(u16, &[u8]) -> anyhow::Result<()>) -> Self {
+        self.validate = validate;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AddxEntryStatus {
+    Valid,
+    Unknown,
+    Invalid(String),
+}
+
+#[derive(Default)]
+pub struct AddxRegistry {
+    extensions: Vec<AddxExtension>,
+}
+
+impl AddxRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, extension: AddxExtension) -> anyhow::Result<()> {
+        if !is_valid_addx_key(&extension.key) {
+            bail!("invalid ADDX key {:?}", extension.key);
+        }
+        if extension.min_version > extension.max_version {
+            bail!(
+                "ADDX extension {} has an empty version range",
+                extension.key
+            );
+        }
+        if self.get(&extension.key).is_some() {
+            bail!("ADDX extension {} already registered", extension.key);
+        }
+        self.extensions.push(extension);
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&AddxExtension> {
+        self.extensions
+            .iter()
+            .find(|extension| extension.key == key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.extensions
+            .iter()
+            .map(|extension| extension.key.as_str())
+    }
+
+    pub fn check_entry(&self, entry: &AddxEntry) -> AddxEntryStatus {
+        let Some(extension) = self.get(&entry.key) else {
+            return AddxEntryStatus::Unknown;
+        };
+        if entry.version < extension.min_version || entry.version > extension.max_version {
+            return AddxEntryStatus::Invalid(format!(
+                "version {} outside supported {}..={}",
+                entry.version, extension.min_version, extension.max_version
+            ));
+        }
+        match (extension.validate)(entry.version, &entry.data) {
+            Ok(()) => AddxEntryStatus::Valid,
+            Err(err) => AddxEntryStatus::Invalid(err.to_string()),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::{anyhow, bail};
 use std::fmt;
 
 pub const TILE_MAGIC: [u8; 4] = *b"TILE";
-pub const CONTAINER_VERSION: u16 = 1;
+pub const CONTAINER_VERSION: u16 = 2;
 pub const MIN_CONTAINER_VERSION: u16 = 1;
 pub const ENDIAN_LITTLE: u16 = 1;
 pub const HEADER_SIZE: usize = 128;
@@ -10,6 +10,15 @@ pub const DIR_ENTRY_SIZE: usize = 64;
 pub const MAX_SECTION_COUNT: u32 = 256;
 pub const DEFAULT_ALIGNMENT: u64 = 64;
 
+pub const CODEC_RAW: u16 = 0;
+pub const CODEC_LZ4: u16 = 1;
+pub const CODEC_ZSTD: u16 = 2;
+
+/// Directory entry flag: payload uses the chunk-addressable layout (container v2+).
+pub const SECTION_FLAG_CHUNKED: u32 = 1;
+/// First container version that may contain chunked sections.
+pub const CHUNKED_CONTAINER_VERSION: u16 = 2;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct TileSectionTag(pub u32);
 
@@ -0,0 +1,497 @@
+//! Structured dumps and section-by-section diffs of `.tile` containers.
+//!
+//! Used by the `tile-inspect` binary; everything here is serializable so the same data backs both
+//! the text and JSON outputs.
+
+use crate::tile_container::{
+    codec_name, decode_addx, decode_hmap, decode_liqd, decode_meta, decode_prop, decode_spln,
+    decode_wmap, hmap_encoding, reclaimable_bytes, LiqdKind, TileContainerReader,
+    TileSectionDirEntry, TileSectionTag, SECTION_FLAG_CHUNKED,
+};
+use crc32fast::Hasher;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TileInspection {
+    pub path: PathBuf,
+    pub file_len: u64,
+    pub reclaimable_bytes: u64,
+    pub header: HeaderInspection,
+    pub sections: Vec<SectionInspection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeaderInspection {
+    pub container_version: u16,
+    pub flags: u32,
+    pub tile_x: i32,
+    pub tile_y: i32,
+    pub region_hash: u64,
+    pub world_spec_hash: u64,
+    pub section_count: u32,
+    pub section_dir_offset: u64,
+    pub created_timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionInspection {
+    pub tag: String,
+    pub section_version: u16,
+    pub codec: String,
+    pub flags: u32,
+    pub chunked: bool,
+    pub offset: u64,
+    pub stored_len: u64,
+    pub decoded_len: u64,
+    pub crc32: u32,
+    pub crc_ok: bool,
+    pub summary: Option<SectionSummary>,
+    /// Read or decode failure; `summary` is `None` when set.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SectionSummary {
+    Meta {
+        format_version: u32,
+        tile_x: i32,
+        tile_y: i32,
+        region_hash: u64,
+        created_timestamp: u64,
+    },
+    Hmap {
+        width: u16,
+        height: u16,
+        encoding: String,
+        min: f32,
+        max: f32,
+        mean: f32,
+    },
+    Wmap {
+        width: u16,
+        height: u16,
+        layers: u16,
+        /// Fraction of cells with a non-zero weight, per layer.
+        coverage: Vec<f32>,
+    },
+    Liqd {
+        width: u16,
+        height: u16,
+        /// Cells whose mask value indexes an existing body.
+        wet_cells: usize,
+        bodies: Vec<LiqdBodySummary>,
+    },
+    Prop {
+        count: usize,
+    },
+    Spln {
+        count: usize,
+        points: usize,
+    },
+    Addx {
+        keys: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LiqdBodySummary {
+    pub id: u32,
+    pub height: f32,
+    pub kind: String,
+    /// Mask cells that point at this body.
+    pub cells: usize,
+}
+
+pub fn inspect_tile(path: impl AsRef<Path>) -> anyhow::Result<TileInspection> {
+    let reader = TileContainerReader::open_buffered(path)?;
+    Ok(inspect_reader(&reader))
+}
+
+pub fn inspect_reader(reader: &TileContainerReader) -> TileInspection {
+    let header = &reader.header;
+    TileInspection {
+        path: reader.path.clone(),
+        file_len: reader.file_len,
+        reclaimable_bytes: reclaimable_bytes(reader),
+        header: HeaderInspection {
+            container_version: header.container_version,
+            flags: header.flags,
+            tile_x: header.tile_x,
+            tile_y: header.tile_y,
+            region_hash: header.region_hash,
+            world_spec_hash: header.world_spec_hash,
+            section_count: header.section_count,
+            section_dir_offset: header.section_dir_offset,
+            created_timestamp: header.created_timestamp,
+        },
+        sections: reader
+            .directory
+            .iter()
+            .map(|entry| inspect_section(reader, entry))
+            .collect(),
+    }
+}
+
+fn inspect_section(reader: &TileContainerReader, entry: &TileSectionDirEntry) -> SectionInspection {
+    let crc_ok = reader.read_section(entry.tag).is_ok_and(|stored| {
+        let mut hasher = Hasher::new();
+        hasher.update(&stored);
+        hasher.finalize() == entry.crc32
+    });
+    let (summary, error) = match reader
+        .decode_section_cow(entry.tag)
+        .and_then(|decoded| summarize_section(entry.tag, &decoded))
+    {
+        Ok(summary) => (summary, None),
+        Err(err) => (None, Some(format!("{:#}", err))),
+    };
+    SectionInspection {
+        tag: entry.tag.as_string(),
+        section_version: entry.section_version,
+        codec: codec_name(entry.codec).to_string(),
+        flags: entry.flags,
+        chunked: entry.flags & SECTION_FLAG_CHUNKED != 0,
+        offset: entry.offset,
+        stored_len: entry.stored_len,
+        decoded_len: entry.decoded_len,
+        crc32: entry.crc32,
+        crc_ok,
+        summary,
+        error,
+    }
+}
+
+/// Summarizes a decoded payload. Unknown tags have no summary.
+pub fn summarize_section(
+    tag: TileSectionTag,
+    decoded: &[u8],
+) -> anyhow::Result<Option<SectionSummary>> {
+    let summary = if tag == TileSectionTag::META {
+        let meta = decode_meta(decoded)?;
+        SectionSummary::Meta {
+            format_version: meta.format_version,
+            tile_x: meta.tile_id.coord.x,
+            tile_y: meta.tile_id.coord.y,
+            region_hash: meta.region_hash,
+            created_timestamp: meta.created_timestamp,
+        }
+    } else if tag == TileSectionTag::HMAP {
+        let encoding = hmap_encoding(decoded)?;
+        let hmap = decode_hmap(decoded)?;
+        let (mut min, mut max, mut sum) = (f32::INFINITY, f32::NEG_INFINITY, 0.0f64);
+        for sample in &hmap.samples {
+            min = min.min(*sample);
+            max = max.max(*sample);
+            sum += f64::from(*sample);
+        }
+        let mean = if hmap.samples.is_empty() {
+            (min, max) = (0.0, 0.0);
+            0.0
+        } else {
+            (sum / hmap.samples.len() as f64) as f32
+        };
+        SectionSummary::Hmap {
+            width: hmap.width,
+            height: hmap.height,
+            encoding: format!("{:?}", encoding),
+            min,
+            max,
+            mean,
+        }
+    } else if tag == TileSectionTag::WMAP {
+        let wmap = decode_wmap(decoded)?;
+        let layers = wmap.layers as usize;
+        let cells = wmap.width as usize * wmap.height as usize;
+        let mut covered = vec![0usize; layers];
+        if layers > 0 {
+            for cell in wmap.weights.chunks_exact(layers) {
+                for (layer, weight) in cell.iter().enumerate() {
+                    if *weight > 0 {
+                        covered[layer] += 1;
+                    }
+                }
+            }
+        }
+        SectionSummary::Wmap {
+            width: wmap.width,
+            height: wmap.height,
+            layers: wmap.layers,
+            coverage: covered
+                .into_iter()
+                .map(|count| {
+                    if cells == 0 {
+                        0.0
+                    } else {
+                        count as f32 / cells as f32
+                    }
+                })
+                .collect(),
+        }
+    } else if tag == TileSectionTag::LIQD {
+        let liqd = decode_liqd(decoded)?;
+        // Mask values are body indices; values past the body list are invalid, not wet.
+        let mut cells = vec![0usize; liqd.bodies.len()];
+        for value in &liqd.mask {
+            if let Some(count) = cells.get_mut(*value as usize) {
+                *count += 1;
+            }
+        }
+        SectionSummary::Liqd {
+            width: liqd.width,
+            height: liqd.height,
+            wet_cells: cells.iter().sum(),
+            bodies: liqd
+                .bodies
+                .iter()
+                .zip(cells)
+                .map(|(body, cells)| LiqdBodySummary {
+                    id: body.id,
+                    height: body.height,
+                    kind: match &body.kind {
+                        LiqdKind::Water => "water".to_string(),
+                        LiqdKind::Lava => "lava".to_string(),
+                        LiqdKind::Slime => "slime".to_string(),
+                        LiqdKind::Custom(name) => name.clone(),
+                    },
+                    cells,
+                })
+                .collect(),
+        }
+    } else if tag == TileSectionTag::PROP {
+        SectionSummary::Prop {
+            count: decode_prop(decoded)?.instances.len(),
+        }
+    } else if tag == TileSectionTag::SPLN {
+        let spln = decode_spln(decoded)?;
+        SectionSummary::Spln {
+            count: spln.splines.len(),
+            points: spln.splines.iter().map(|spline| spline.points.len()).sum(),
+        }
+    } else if tag == TileSectionTag::ADDX {
+        SectionSummary::Addx {
+            keys: decode_addx(decoded)?
+                .entries
+                .into_iter()
+                .map(|entry| entry.key)
+                .collect(),
+        }
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(summary))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TileDiff {
+    pub left: PathBuf,
+    pub right: PathBuf,
+    pub header: Vec<FieldChange>,
+    pub sections: Vec<SectionDiff>,
+}
+
+impl TileDiff {
+    /// True when nothing but the header's `created_timestamp` differs; the timestamp is still
+    /// listed in `header` but every save refreshes it, so it says nothing about the content.
+    pub fn is_identical(&self) -> bool {
+        self.header
+            .iter()
+            .all(|change| change.field == "created_timestamp")
+            && self
+                .sections
+                .iter()
+                .all(|section| section.status == SectionDiffStatus::Unchanged)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub left: String,
+    pub right: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionDiffStatus {
+    Unchanged,
+    Changed,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionDiff {
+    pub tag: String,
+    pub status: SectionDiffStatus,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Compares two tiles section by section. Offsets and `section_dir_offset` are layout details
+/// and ignored; a section counts as changed when its metadata, stored bytes or decoded bytes
+/// differ.
+pub fn diff_tiles(left: impl AsRef<Path>, right: impl AsRef<Path>) -> anyhow::Result<TileDiff> {
+    let left = TileContainerReader::open_buffered(left)?;
+    let right = TileContainerReader::open_buffered(right)?;
+    Ok(diff_readers(&left, &right))
+}
+
+pub fn diff_readers(left: &TileContainerReader, right: &TileContainerReader) -> TileDiff {
+    let mut header = Vec::new();
+    let (l, r) = (&left.header, &right.header);
+    push_change(
+        &mut header,
+        "container_version",
+        l.container_version,
+        r.container_version,
+    );
+    push_change(&mut header, "flags", l.flags, r.flags);
+    push_change(&mut header, "tile_x", l.tile_x, r.tile_x);
+    push_change(&mut header, "tile_y", l.tile_y, r.tile_y);
+    push_change(&mut header, "region_hash", l.region_hash, r.region_hash);
+    push_change(
+        &mut header,
+        "world_spec_hash",
+        l.world_spec_hash,
+        r.world_spec_hash,
+    );
+    push_change(
+        &mut header,
+        "section_count",
+        l.section_count,
+        r.section_count,
+    );
+    push_change(
+        &mut header,
+        "created_timestamp",
+        l.created_timestamp,
+        r.created_timestamp,
+    );
+
+    let mut tags: Vec<TileSectionTag> = left.directory.iter().map(|entry| entry.tag).collect();
+    for entry in &right.directory {
+        if !tags.contains(&entry.tag) {
+            tags.push(entry.tag);
+        }
+    }
+
+    let sections = tags
+        .into_iter()
+        .map(|tag| match (left.section(tag), right.section(tag)) {
+            (Some(a), Some(b)) => {
+                let changes = diff_section(left, a, right, b);
+                SectionDiff {
+                    tag: tag.as_string(),
+                    status: if changes.is_empty() {
+                        SectionDiffStatus::Unchanged
+                    } else {
+                        SectionDiffStatus::Changed
+                    },
+                    changes,
+                }
+            }
+            (Some(_), None) => SectionDiff {
+                tag: tag.as_string(),
+                status: SectionDiffStatus::Removed,
+                changes: Vec::new(),
+            },
+            _ => SectionDiff {
+                tag: tag.as_string(),
+                status: SectionDiffStatus::Added,
+                changes: Vec::new(),
+            },
+        })
+        .collect();
+
+    TileDiff {
+        left: left.path.clone(),
+        right: right.path.clone(),
+        header,
+        sections,
+    }
+}
+
+fn diff_section(
+    left: &TileContainerReader,
+    a: &TileSectionDirEntry,
+    right: &TileContainerReader,
+    b: &TileSectionDirEntry,
+) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    push_change(
+        &mut changes,
+        "section_version",
+        a.section_version,
+        b.section_version,
+    );
+    push_change(
+        &mut changes,
+        "codec",
+        codec_name(a.codec),
+        codec_name(b.codec),
+    );
+    push_change(&mut changes, "flags", a.flags, b.flags);
+    push_change(&mut changes, "stored_len", a.stored_len, b.stored_len);
+    push_change(&mut changes, "decoded_len", a.decoded_len, b.decoded_len);
+    push_change(
+        &mut changes,
+        "crc32",
+        format!("{:08x}", a.crc32),
+        format!("{:08x}", b.crc32),
+    );
+
+    match (
+        left.decode_section_cow(a.tag),
+        right.decode_section_cow(b.tag),
+    ) {
+        (Ok(da), Ok(db)) => {
+            if da != db {
+                changes.push(FieldChange {
+                    field: "decoded".to_string(),
+                    left: format!("{} bytes", da.len()),
+                    right: format!("{} bytes", db.len()),
+                });
+                let sa = summarize_section(a.tag, &da).ok().flatten();
+                let sb = summarize_section(b.tag, &db).ok().flatten();
+                if sa != sb {
+                    changes.push(FieldChange {
+                        field: "summary".to_string(),
+                        left: summary_string(sa.as_ref()),
+                        right: summary_string(sb.as_ref()),
+                    });
+                }
+            }
+        }
+        (da, db) => {
+            let describe = |result: anyhow::Result<_>| match result {
+                Ok(_) => "ok".to_string(),
+                Err(err) => format!("error: {:#}", err),
+            };
+            let (da, db) = (describe(da.map(|_| ())), describe(db.map(|_| ())));
+            push_change(&mut changes, "decoded", da, db);
+        }
+    }
+    changes
+}
+
+fn summary_string(summary: Option<&SectionSummary>) -> String {
+    summary
+        .and_then(|summary| serde_json::to_string(summary).ok())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn push_change<T: PartialEq + ToString>(
+    changes: &mut Vec<FieldChange>,
+    field: &str,
+    left: T,
+    right: T,
+) {
+    if left != right {
+        changes.push(FieldChange {
+            field: field.to_string(),
+            left: left.to_string(),
+            right: right.to_string(),
+        });
+    }
+}
@@ -1,18 +1,32 @@
+mod chunked;
+mod codec;
+pub mod extensions;
 mod format;
+pub mod inspect;
+mod patch;
 mod reader;
 mod sections;
 pub mod world_spec_hash;
 mod writer;
 
+pub use chunked::TileChunk;
+pub use codec::{codec_name, decode_payload, encode_payload, is_supported_codec};
 pub use format::{
-    alignment_padding, TileContainerHeader, TileSectionDirEntry, TileSectionTag, CONTAINER_VERSION,
+    alignment_padding, TileContainerHeader, TileSectionDirEntry, TileSectionTag,
+    CHUNKED_CONTAINER_VERSION, CODEC_LZ4, CODEC_RAW, CODEC_ZSTD, CONTAINER_VERSION,
     DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE, HEADER_SIZE, MAX_SECTION_COUNT, MIN_CONTAINER_VERSION,
-    TILE_MAGIC,
+    SECTION_FLAG_CHUNKED, TILE_MAGIC,
 };
+pub use patch::{compact_tile, reclaimable_bytes, TileContainerPatch};
 pub use reader::TileContainerReader;
+pub(crate) use sections::section_chain;
 pub use sections::{
-    decode_hmap, decode_liqd, decode_meta, decode_prop, decode_wmap, encode_hmap, encode_liqd,
-    encode_meta, encode_prop, encode_wmap, HmapSection, LiqdBody, LiqdKind, LiqdSection,
-    MetaSection, PropRecord, PropSection, WmapSection,
+    current_section_version, decode_addx, decode_current_section, decode_hmap, decode_liqd,
+    decode_meta, decode_prop, decode_spln, decode_wmap, encode_addx, encode_hmap, encode_hmap_with,
+    encode_liqd, encode_meta, encode_prop, encode_spln, encode_wmap, hmap_encoding,
+    hmap_quantization_error, is_valid_addx_key, migrate_section_payload, payload_version,
+    section_migrations, AddxEntry, AddxSection, HmapEncoding, HmapSection, LiqdBody, LiqdKind,
+    LiqdSection, MetaSection, PropRecord, PropSection, SectionMigration, SplinePoint, SplineRecord,
+    SplnSection, WmapSection,
 };
 pub use writer::{TileContainerWriter, TileSectionPayload};
@@ -0,0 +1,184 @@
+//! In-place section patching and compaction for `.tile` containers.
+//!
+//! A patch appends the new payloads and a fresh directory to the end of the file, then rewrites
+//! the fixed-size header to point at the new directory. Untouched sections keep their bytes and
+//! offsets, and a crash before the header write leaves the previous directory in effect.
+//! Superseded payloads and directories stay behind as dead space until `compact_tile` rewrites
+//! the file.
+
+use crate::tile_container::writer::{compare_sections, encode_section, replace_with_backup};
+use crate::tile_container::{
+    alignment_padding, TileContainerHeader, TileContainerReader, TileSectionDirEntry,
+    TileSectionPayload, TileSectionTag, CONTAINER_VERSION, DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE,
+    HEADER_SIZE, MAX_SECTION_COUNT,
+};
+use anyhow::{bail, Context};
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::Path;
+
+#[derive(Debug, Default)]
+pub struct TileContainerPatch {
+    replace: Vec<TileSectionPayload>,
+    remove: Vec<TileSectionTag>,
+    chunks_per_tile: u16,
+}
+
+impl TileContainerPatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Chunk grid used for replacement sections flagged with `SECTION_FLAG_CHUNKED`.
+    pub fn chunks_per_tile(mut self, chunks_per_tile: u16) -> Self {
+        self.chunks_per_tile = chunks_per_tile;
+        self
+    }
+
+    /// Replaces the section with the same tag, or adds it if the tile does not have one.
+    pub fn replace_section(&mut self, payload: TileSectionPayload) {
+        self.remove.retain(|tag| *tag != payload.tag);
+        self.replace.retain(|existing| existing.tag != payload.tag);
+        self.replace.push(payload);
+    }
+
+    /// Drops a section from the directory. Removing a missing section is a no-op.
+    pub fn remove_section(&mut self, tag: TileSectionTag) {
+        self.replace.retain(|existing| existing.tag != tag);
+        if !self.remove.contains(&tag) {
+            self.remove.push(tag);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replace.is_empty() && self.remove.is_empty()
+    }
+
+    /// Appends the replacement payloads and a new directory to the tile at `path`, then points
+    /// the header at the new directory. Returns the header now on disk.
+    pub fn apply(&self, path: impl AsRef<Path>) -> anyhow::Result<TileContainerHeader> {
+        let path = path.as_ref();
+        let (mut header, mut directory, file_len) = {
+            let reader = TileContainerReader::open(path)?;
+            (reader.header, reader.directory, reader.file_len)
+        };
+        if self.is_empty() {
+            return Ok(header);
+        }
+
+        directory.retain(|entry| !self.remove.contains(&entry.tag));
+        let mut encoded = Vec::with_capacity(self.replace.len());
+        for payload in &self.replace {
+            let (stored, crc32) = encode_section(payload, self.chunks_per_tile)?;
+            encoded.push((payload, stored, crc32));
+        }
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .with_context(|| format!("open tile {:?} for patching", path))?;
+        let mut offset = file_len;
+        for (payload, stored, crc32) in encoded {
+            offset += alignment_padding(offset, DEFAULT_ALIGNMENT);
+            file.seek(SeekFrom::Start(offset))?;
+            file.write_all(&stored)?;
+            let entry = TileSectionDirEntry {
+                tag: payload.tag,
+                section_version: payload.section_version,
+                codec: payload.codec,
+                flags: payload.flags,
+                offset,
+                stored_len: stored.len() as u64,
+                decoded_len: payload.decoded.len() as u64,
+                crc32,
+            };
+            offset += entry.stored_len;
+            match directory
+                .iter_mut()
+                .find(|existing| existing.tag == payload.tag)
+            {
+                Some(existing) => *existing = entry,
+                None => directory.push(entry),
+            }
+        }
+        if directory.len() as u32 > MAX_SECTION_COUNT {
+            bail!("section_count {} exceeds cap", directory.len());
+        }
+        directory.sort_by(|a, b| compare_sections(a.tag, b.tag));
+
+        let dir_offset = offset + alignment_padding(offset, DEFAULT_ALIGNMENT);
+        file.seek(SeekFrom::Start(dir_offset))?;
+        for entry in &directory {
+            file.write_all(&entry.to_bytes())?;
+        }
+        file.sync_data()?;
+
+        // The header is the commit point: until it lands, readers still see the old directory.
+        header.container_version = CONTAINER_VERSION;
+        header.section_count = directory.len() as u32;
+        header.section_dir_offset = dir_offset;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header.to_bytes())?;
+        file.sync_data()?;
+        Ok(header)
+    }
+}
+
+/// Bytes a `compact_tile` pass would reclaim: superseded payloads, old directories and padding
+/// left behind by patches.
+pub fn reclaimable_bytes(reader: &TileContainerReader) -> u64 {
+    let (_, compacted_len) = compacted_layout(&reader.directory);
+    reader.file_len.saturating_sub(compacted_len)
+}
+
+/// Rewrites the tile at `path` with only its live sections, copying their stored bytes verbatim
+/// (no re-encoding). Uses the same tmp + `.tile.bak` rotation as `TileContainerWriter::write`.
+/// Returns the number of bytes reclaimed.
+pub fn compact_tile(path: impl AsRef<Path>) -> anyhow::Result<u64> {
+    let path = path.as_ref();
+    let reader = TileContainerReader::open_buffered(path)?;
+    let mut directory = reader.directory.clone();
+    directory.sort_by(|a, b| compare_sections(a.tag, b.tag));
+    let (offsets, compacted_len) = compacted_layout(&directory);
+    if compacted_len >= reader.file_len {
+        return Ok(0);
+    }
+
+    let mut header = reader.header;
+    header.section_count = directory.len() as u32;
+    header.section_dir_offset = HEADER_SIZE as u64;
+
+    let mut payloads = Vec::with_capacity(directory.len());
+    for (entry, offset) in directory.iter_mut().zip(&offsets) {
+        payloads.push(reader.section_bytes(entry.tag)?);
+        entry.offset = *offset;
+    }
+
+    let tmp_path = path.with_extension("tile.tmp");
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(&header.to_bytes())?;
+    for entry in &directory {
+        file.write_all(&entry.to_bytes())?;
+    }
+    for (entry, payload) in directory.iter().zip(payloads) {
+        file.seek(SeekFrom::Start(entry.offset))?;
+        file.write_all(payload)?;
+    }
+    file.sync_all()?;
+    drop(file);
+
+    replace_with_backup(&tmp_path, path)?;
+    Ok(reader.file_len - compacted_len)
+}
+
+/// Offsets a freshly written container would use for `directory`, plus its total length.
+fn compacted_layout(directory: &[TileSectionDirEntry]) -> (Vec<u64>, u64) {
+    let mut offset = (HEADER_SIZE + directory.len() * DIR_ENTRY_SIZE) as u64;
+    let mut offsets = Vec::with_capacity(directory.len());
+    for entry in directory {
+        offset += alignment_padding(offset, DEFAULT_ALIGNMENT);
+        offsets.push(offset);
+        offset += entry.stored_len;
+    }
+    (offsets, offset)
+}
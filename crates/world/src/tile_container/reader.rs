@@ -1,12 +1,19 @@
+use crate::tile_container::chunked::{
+    decode_block, decode_chunked, parse_chunk_table, ChunkedHeader, CHUNKED_HEADER_SIZE,
+};
 use crate::tile_container::{
-    TileContainerHeader, TileSectionDirEntry, TileSectionTag, CONTAINER_VERSION, DIR_ENTRY_SIZE,
-    HEADER_SIZE, MAX_SECTION_COUNT, MIN_CONTAINER_VERSION,
+    decode_payload, is_supported_codec, TileChunk, TileContainerHeader, TileSectionDirEntry,
+    TileSectionTag, CHUNKED_CONTAINER_VERSION, CODEC_RAW, CONTAINER_VERSION, DIR_ENTRY_SIZE,
+    HEADER_SIZE, MAX_SECTION_COUNT, MIN_CONTAINER_VERSION, SECTION_FLAG_CHUNKED,
 };
 use anyhow::{anyhow, bail, Context};
 use crc32fast::Hasher;
+use foundation::ids::ChunkCoord;
+use std::borrow::Cow;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone)]
 pub struct TileContainerReader {
@@ -14,9 +21,21 @@ pub struct TileContainerReader {
     pub header: TileContainerHeader,
     pub directory: Vec<TileSectionDirEntry>,
     pub file_len: u64,
+    source: TileSource,
+}
+
+/// Backing storage for section reads.
+///
+/// `File` keeps one handle open for the reader's lifetime; `Bytes` holds the whole container in
+/// memory so sections can be borrowed without copying.
+#[derive(Debug, Clone)]
+enum TileSource {
+    File(Arc<Mutex<File>>),
+    Bytes(Arc<[u8]>),
 }
 
 impl TileContainerReader {
+    /// Opens a container and reads sections on demand through a single shared file handle.
     pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
         let path = path.as_ref().to_path_buf();
         let mut file = File::open(&path).with_context(|| format!("open tile {:?}", path))?;
@@ -27,84 +46,222 @@ impl TileContainerReader {
 
         let mut header_bytes = [0u8; HEADER_SIZE];
         file.read_exact(&mut header_bytes)?;
-        let header = TileContainerHeader::from_bytes(&header_bytes)?;
-
-        if header.container_version < MIN_CONTAINER_VERSION {
-            bail!(
-                "container version {} below minimum {}",
-                header.container_version,
-                MIN_CONTAINER_VERSION
-            );
-        }
-        if header.container_version > CONTAINER_VERSION {
-            bail!(
-                "container version {} exceeds supported {}",
-                header.container_version,
-                CONTAINER_VERSION
-            );
-        }
-
-        if header.section_count > MAX_SECTION_COUNT {
-            bail!("section_count {} exceeds cap", header.section_count);
-        }
+        let header = parse_header(&header_bytes, file_len)?;
 
         let dir_bytes_len = header.section_count as u64 * DIR_ENTRY_SIZE as u64;
-        let dir_end = header.section_dir_offset + dir_bytes_len;
-        if dir_end > file_len {
-            return Err(anyhow!("section directory exceeds file bounds"));
-        }
-
         file.seek(SeekFrom::Start(header.section_dir_offset))?;
         let mut dir_bytes = vec![0u8; dir_bytes_len as usize];
         file.read_exact(&mut dir_bytes)?;
+        let directory = parse_directory(&dir_bytes)?;
+
+        Ok(Self {
+            path,
+            header,
+            directory,
+            file_len,
+            source: TileSource::File(Arc::new(Mutex::new(file))),
+        })
+    }
 
-        let mut directory = Vec::with_capacity(header.section_count as usize);
-        for chunk in dir_bytes.chunks_exact(DIR_ENTRY_SIZE) {
-            directory.push(TileSectionDirEntry::from_bytes(chunk)?);
+    /// Reads the whole container into memory once; sections are then served as borrowed slices.
+    pub fn open_buffered(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let bytes = std::fs::read(&path).with_context(|| format!("open tile {:?}", path))?;
+        let mut reader = Self::from_bytes(bytes)?;
+        reader.path = path;
+        Ok(reader)
+    }
+
+    /// Parses a container held in memory. `path` is left empty.
+    pub fn from_bytes(bytes: impl Into<Arc<[u8]>>) -> anyhow::Result<Self> {
+        let bytes: Arc<[u8]> = bytes.into();
+        let file_len = bytes.len() as u64;
+        if file_len < HEADER_SIZE as u64 {
+            bail!("tile too small");
         }
+        let header = parse_header(&bytes[..HEADER_SIZE], file_len)?;
+        let dir_start = header.section_dir_offset as usize;
+        let dir_end = dir_start + header.section_count as usize * DIR_ENTRY_SIZE;
+        let directory = parse_directory(&bytes[dir_start..dir_end])?;
 
         Ok(Self {
-            path,
+            path: PathBuf::new(),
             header,
             directory,
             file_len,
+            source: TileSource::Bytes(bytes),
         })
     }
 
+    pub fn is_buffered(&self) -> bool {
+        matches!(self.source, TileSource::Bytes(_))
+    }
+
     pub fn section(&self, tag: TileSectionTag) -> Option<&TileSectionDirEntry> {
         self.directory.iter().find(|entry| entry.tag == tag)
     }
 
     pub fn read_section(&self, tag: TileSectionTag) -> anyhow::Result<Vec<u8>> {
+        let entry = self.checked_entry(tag)?;
+        match &self.source {
+            TileSource::Bytes(bytes) => Ok(slice_entry(bytes, entry).to_vec()),
+            TileSource::File(file) => {
+                let len = usize::try_from(entry.stored_len)
+                    .map_err(|_| anyhow!("section {} too large", tag))?;
+                let mut file = file
+                    .lock()
+                    .map_err(|_| anyhow!("tile file handle poisoned"))?;
+                file.seek(SeekFrom::Start(entry.offset))?;
+                let mut buffer = vec![0u8; len];
+                file.read_exact(&mut buffer)?;
+                Ok(buffer)
+            }
+        }
+    }
+
+    /// Borrows the stored bytes of a section. Only available for buffered readers.
+    pub fn section_bytes(&self, tag: TileSectionTag) -> anyhow::Result<&[u8]> {
+        let entry = self.checked_entry(tag)?;
+        match &self.source {
+            TileSource::Bytes(bytes) => Ok(slice_entry(bytes, entry)),
+            TileSource::File(_) => bail!("section {} cannot be borrowed from a file reader", tag),
+        }
+    }
+
+    pub fn read_section_checked(&self, tag: TileSectionTag) -> anyhow::Result<Vec<u8>> {
+        Ok(self.stored_checked(tag)?.into_owned())
+    }
+
+    pub fn decode_section(&self, tag: TileSectionTag) -> anyhow::Result<Vec<u8>> {
+        Ok(self.decode_section_cow(tag)?.into_owned())
+    }
+
+    /// Decodes a section, borrowing from the buffer when the reader is buffered and the section
+    /// is stored raw. The payload is returned as stored; see `decode_current_section` for one
+    /// migrated to the current payload version.
+    pub fn decode_section_cow(&self, tag: TileSectionTag) -> anyhow::Result<Cow<'_, [u8]>> {
         let entry = self
             .section(tag)
             .ok_or_else(|| anyhow!("section {} not found", tag))?;
-        let dir_end = self.header.section_dir_offset
-            + self.header.section_count as u64 * DIR_ENTRY_SIZE as u64;
-        if entry.offset < dir_end {
-            bail!("section {} overlaps directory", tag);
+        if !is_supported_codec(entry.codec) {
+            bail!("unsupported codec {} for section {}", entry.codec, tag);
         }
-        if entry.stored_len == 0 {
-            bail!("section {} has zero length", tag);
+        let stored = self.stored_checked(tag)?;
+        if entry.flags & SECTION_FLAG_CHUNKED != 0 {
+            self.ensure_chunked_allowed(tag)?;
+            let decoded = decode_chunked(&stored, entry.codec)
+                .with_context(|| format!("decode chunked section {}", tag))?;
+            if decoded.len() as u64 != entry.decoded_len {
+                bail!(
+                    "decoded length {} does not match directory decoded_len {}",
+                    decoded.len(),
+                    entry.decoded_len
+                );
+            }
+            return Ok(Cow::Owned(decoded));
         }
-        let end = entry.offset.saturating_add(entry.stored_len);
-        if end > self.file_len {
-            bail!("section {} out of bounds", tag);
+        if entry.codec == CODEC_RAW && stored.len() as u64 == entry.decoded_len {
+            return Ok(stored);
         }
-        let len =
-            usize::try_from(entry.stored_len).map_err(|_| anyhow!("section {} too large", tag))?;
-        let mut file = File::open(&self.path)?;
-        file.seek(SeekFrom::Start(entry.offset))?;
-        let mut buffer = vec![0u8; len];
-        file.read_exact(&mut buffer)?;
-        Ok(buffer)
+        decode_payload(entry.codec, &stored, entry.decoded_len)
+            .map(Cow::Owned)
+            .with_context(|| format!("decode section {}", tag))
     }
 
-    pub fn read_section_checked(&self, tag: TileSectionTag) -> anyhow::Result<Vec<u8>> {
+    pub fn is_chunked(&self, tag: TileSectionTag) -> bool {
+        self.section(tag)
+            .is_some_and(|entry| entry.flags & SECTION_FLAG_CHUNKED != 0)
+    }
+
+    /// Reads and verifies a single chunk of a chunked grid section without decoding the rest of
+    /// the section. Only the chunk header, chunk table and the requested block are read.
+    pub fn read_chunk(&self, tag: TileSectionTag, coord: ChunkCoord) -> anyhow::Result<TileChunk> {
+        let entry = self.checked_entry(tag)?;
+        if entry.flags & SECTION_FLAG_CHUNKED == 0 {
+            bail!("section {} is not chunked", tag);
+        }
+        self.ensure_chunked_allowed(tag)?;
+        if !is_supported_codec(entry.codec) {
+            bail!("unsupported codec {} for section {}", entry.codec, tag);
+        }
+
+        let header_bytes = self.read_range(entry, 0, CHUNKED_HEADER_SIZE as u64)?;
+        let header = ChunkedHeader::from_bytes(&header_bytes)?;
+        let table_bytes =
+            self.read_range(entry, CHUNKED_HEADER_SIZE as u64, header.table_len() as u64)?;
+        let table = parse_chunk_table(&header, &table_bytes)?;
+        let (_, _, width, height) = header.chunk_rect(coord)?;
+        let index = 1 + coord.y as usize * header.chunks_x as usize + coord.x as usize;
+        let block = table[index];
+        let stored =
+            self.read_range(entry, u64::from(block.offset), u64::from(block.stored_len))?;
+        let data = decode_block(&block, &stored, entry.codec)
+            .with_context(|| format!("chunk ({}, {}) of section {}", coord.x, coord.y, tag))?;
+        if data.len() != width * height * header.elem_size as usize {
+            bail!(
+                "chunk ({}, {}) of section {} has wrong size",
+                coord.x,
+                coord.y,
+                tag
+            );
+        }
+
+        Ok(TileChunk {
+            coord,
+            width: width as u16,
+            height: height as u16,
+            elem_size: header.elem_size,
+            data,
+        })
+    }
+
+    fn ensure_chunked_allowed(&self, tag: TileSectionTag) -> anyhow::Result<()> {
+        if self.header.container_version < CHUNKED_CONTAINER_VERSION {
+            bail!(
+                "section {} is chunked but container version {} predates chunked sections",
+                tag,
+                self.header.container_version
+            );
+        }
+        Ok(())
+    }
+
+    /// Reads `len` bytes at `relative` offset inside a section's stored payload.
+    fn read_range(
+        &self,
+        entry: &TileSectionDirEntry,
+        relative: u64,
+        len: u64,
+    ) -> anyhow::Result<Cow<'_, [u8]>> {
+        let end = relative
+            .checked_add(len)
+            .filter(|end| *end <= entry.stored_len)
+            .ok_or_else(|| anyhow!("range outside section {}", entry.tag))?;
+        let start = entry.offset + relative;
+        match &self.source {
+            TileSource::Bytes(bytes) => Ok(Cow::Borrowed(
+                &bytes[start as usize..(entry.offset + end) as usize],
+            )),
+            TileSource::File(file) => {
+                let mut file = file
+                    .lock()
+                    .map_err(|_| anyhow!("tile file handle poisoned"))?;
+                file.seek(SeekFrom::Start(start))?;
+                let mut buffer = vec![0u8; len as usize];
+                file.read_exact(&mut buffer)?;
+                Ok(Cow::Owned(buffer))
+            }
+        }
+    }
+
+    fn stored_checked(&self, tag: TileSectionTag) -> anyhow::Result<Cow<'_, [u8]>> {
         let entry = self
             .section(tag)
             .ok_or_else(|| anyhow!("section {} not found", tag))?;
-        let buffer = self.read_section(tag)?;
+        let buffer = match &self.source {
+            TileSource::Bytes(_) => Cow::Borrowed(self.section_bytes(tag)?),
+            TileSource::File(_) => Cow::Owned(self.read_section(tag)?),
+        };
         let mut hasher = Hasher::new();
         hasher.update(&buffer);
         let crc = hasher.finalize();
@@ -114,13 +271,68 @@ impl TileContainerReader {
         Ok(buffer)
     }
 
-    pub fn decode_section(&self, tag: TileSectionTag) -> anyhow::Result<Vec<u8>> {
+    fn checked_entry(&self, tag: TileSectionTag) -> anyhow::Result<&TileSectionDirEntry> {
         let entry = self
             .section(tag)
             .ok_or_else(|| anyhow!("section {} not found", tag))?;
-        if entry.codec != 0 {
-            bail!("unsupported codec {} for section {}", entry.codec, tag);
+        let dir_start = self.header.section_dir_offset;
+        let dir_end = dir_start + self.header.section_count as u64 * DIR_ENTRY_SIZE as u64;
+        if entry.stored_len == 0 {
+            bail!("section {} has zero length", tag);
+        }
+        let end = entry.offset.saturating_add(entry.stored_len);
+        if entry.offset < HEADER_SIZE as u64 {
+            bail!("section {} overlaps header", tag);
+        }
+        if entry.offset < dir_end && end > dir_start {
+            bail!("section {} overlaps directory", tag);
+        }
+        if end > self.file_len {
+            bail!("section {} out of bounds", tag);
         }
-        self.read_section_checked(tag)
+        Ok(entry)
+    }
+}
+
+fn slice_entry<'a>(bytes: &'a [u8], entry: &TileSectionDirEntry) -> &'a [u8] {
+    let start = entry.offset as usize;
+    &bytes[start..start + entry.stored_len as usize]
+}
+
+fn parse_header(bytes: &[u8], file_len: u64) -> anyhow::Result<TileContainerHeader> {
+    let header = TileContainerHeader::from_bytes(bytes)?;
+
+    if header.container_version < MIN_CONTAINER_VERSION {
+        bail!(
+            "container version {} below minimum {}",
+            header.container_version,
+            MIN_CONTAINER_VERSION
+        );
+    }
+    if header.container_version > CONTAINER_VERSION {
+        bail!(
+            "container version {} exceeds supported {}",
+            header.container_version,
+            CONTAINER_VERSION
+        );
+    }
+
+    if header.section_count > MAX_SECTION_COUNT {
+        bail!("section_count {} exceeds cap", header.section_count);
+    }
+
+    let dir_bytes_len = header.section_count as u64 * DIR_ENTRY_SIZE as u64;
+    let dir_end = header.section_dir_offset.saturating_add(dir_bytes_len);
+    if dir_end > file_len {
+        return Err(anyhow!("section directory exceeds file bounds"));
+    }
+    Ok(header)
+}
+
+fn parse_directory(dir_bytes: &[u8]) -> anyhow::Result<Vec<TileSectionDirEntry>> {
+    let mut directory = Vec::with_capacity(dir_bytes.len() / DIR_ENTRY_SIZE);
+    for chunk in dir_bytes.chunks_exact(DIR_ENTRY_SIZE) {
+        directory.push(TileSectionDirEntry::from_bytes(chunk)?);
     }
+    Ok(directory)
 }
@@ -0,0 +1,120 @@
+use anyhow::{anyhow, bail};
+
+use super::{read_string, write_string};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AddxSection {
+    pub entries: Vec<AddxEntry>,
+}
+
+/// A namespaced, versioned extension blob (e.g. `studio:spawn_points`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddxEntry {
+    pub key: String,
+    pub version: u16,
+    pub data: Vec<u8>,
+}
+
+impl AddxSection {
+    pub fn get(&self, key: &str) -> Option<&AddxEntry> {
+        self.entries.iter().find(|entry| entry.key == key)
+    }
+
+    /// Inserts or replaces the entry stored under `key`.
+    pub fn insert(&mut self, key: impl Into<String>, version: u16, data: Vec<u8>) {
+        let key = key.into();
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.key == key) {
+            entry.version = version;
+            entry.data = data;
+        } else {
+            self.entries.push(AddxEntry { key, version, data });
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<AddxEntry> {
+        let index = self.entries.iter().position(|entry| entry.key == key)?;
+        Some(self.entries.remove(index))
+    }
+}
+
+pub(super) const ADDX_VERSION: u16 = 1;
+
+/// Extension keys are `namespace:name`, each part lowercase ASCII, digits, `_`, `-` or `.`.
+pub fn is_valid_addx_key(key: &str) -> bool {
+    let Some((namespace, name)) = key.split_once(':') else {
+        return false;
+    };
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part.bytes().all(|byte| {
+                byte.is_ascii_lowercase()
+                    || byte.is_ascii_digit()
+                    || byte == b'_'
+                    || byte == b'-'
+                    || byte == b'.'
+            })
+    };
+    valid_part(namespace) && valid_part(name)
+}
+
+pub fn encode_addx(addx: &AddxSection) -> anyhow::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&ADDX_VERSION.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&(addx.entries.len() as u32).to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    let mut entries = addx.entries.clone();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    for pair in entries.windows(2) {
+        if pair[0].key == pair[1].key {
+            bail!("duplicate ADDX key {}", pair[0].key);
+        }
+    }
+    for entry in &entries {
+        if !is_valid_addx_key(&entry.key) {
+            bail!("invalid ADDX key {:?}", entry.key);
+        }
+        let data_len = u32::try_from(entry.data.len())
+            .map_err(|_| anyhow!("ADDX entry {} too large", entry.key))?;
+        write_string(&mut out, &entry.key)?;
+        out.extend_from_slice(&entry.version.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&data_len.to_le_bytes());
+        out.extend_from_slice(&entry.data);
+    }
+    Ok(out)
+}
+
+pub fn decode_addx(bytes: &[u8]) -> anyhow::Result<AddxSection> {
+    if bytes.len() < 12 {
+        bail!("ADDX section too small");
+    }
+    let version = u16::from_le_bytes(bytes[0..2].try_into()?);
+    if version != ADDX_VERSION {
+        bail!("unsupported ADDX version {}", version);
+    }
+    let count = u32::from_le_bytes(bytes[4..8].try_into()?);
+    let mut cursor = 12;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let (key, key_len) = read_string(bytes, cursor)?;
+        cursor += key_len;
+        if bytes.len() < cursor + 8 {
+            bail!("ADDX entry header truncated");
+        }
+        let entry_version = u16::from_le_bytes(bytes[cursor..cursor + 2].try_into()?);
+        let data_len = u32::from_le_bytes(bytes[cursor + 4..cursor + 8].try_into()?) as usize;
+        cursor += 8;
+        if bytes.len() < cursor + data_len {
+            bail!("ADDX entry {} data truncated", key);
+        }
+        let data = bytes[cursor..cursor + data_len].to_vec();
+        cursor += data_len;
+        entries.push(AddxEntry {
+            key,
+            version: entry_version,
+            data,
+        });
+    }
+    Ok(AddxSection { entries })
+}
@@ -1,4 +1,4 @@
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct HmapSection {
@@ -7,24 +7,89 @@ pub struct HmapSection {
     pub samples: Vec<f32>,
 }
 
-const HMAP_VERSION: u16 = 1;
+/// Sample encoding stored in the HMAP header `encoding` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HmapEncoding {
+    /// Raw little-endian f32 samples (lossless).
+    #[default]
+    F32,
+    /// u16 samples mapped onto `min + q * scale` for the tile.
+    Quantized16,
+    /// Quantized u16 samples stored as wrapping deltas from the previous sample in the row.
+    RowDelta16,
+}
+
+impl HmapEncoding {
+    pub fn as_u16(self) -> u16 {
+        match self {
+            Self::F32 => 0,
+            Self::Quantized16 => 1,
+            Self::RowDelta16 => 2,
+        }
+    }
+
+    pub fn from_u16(raw: u16) -> anyhow::Result<Self> {
+        match raw {
+            0 => Ok(Self::F32),
+            1 => Ok(Self::Quantized16),
+            2 => Ok(Self::RowDelta16),
+            other => Err(anyhow!("unknown HMAP encoding {}", other)),
+        }
+    }
+}
+
+pub(super) const HMAP_VERSION: u16 = 1;
+const HMAP_HEADER_LEN: usize = 12;
+const QUANT_PARAMS_LEN: usize = 8;
 
 pub fn encode_hmap(hmap: &HmapSection) -> Vec<u8> {
-    let mut out = Vec::with_capacity(12 + hmap.samples.len() * 4);
-    out.extend_from_slice(&HMAP_VERSION.to_le_bytes());
-    out.extend_from_slice(&0u16.to_le_bytes());
-    out.extend_from_slice(&hmap.width.to_le_bytes());
-    out.extend_from_slice(&hmap.height.to_le_bytes());
-    out.extend_from_slice(&0u16.to_le_bytes()); // encoding: f32
-    out.extend_from_slice(&0u16.to_le_bytes());
+    let mut out = Vec::with_capacity(HMAP_HEADER_LEN + hmap.samples.len() * 4);
+    write_header(&mut out, hmap, HmapEncoding::F32);
     for sample in &hmap.samples {
         out.extend_from_slice(&sample.to_le_bytes());
     }
     out
 }
 
+/// Encodes `hmap` with the requested sample encoding.
+///
+/// Quantized encodings require finite samples whose range `max - min` is itself finite; the
+/// per-tile range is derived from the data so the worst-case error is half of
+/// `(max - min) / 65535`.
+pub fn encode_hmap_with(hmap: &HmapSection, encoding: HmapEncoding) -> anyhow::Result<Vec<u8>> {
+    if encoding == HmapEncoding::F32 {
+        return Ok(encode_hmap(hmap));
+    }
+    if hmap.samples.len() != hmap.width as usize * hmap.height as usize {
+        bail!("HMAP sample count mismatch");
+    }
+
+    let (min, scale) = quantization_params(&hmap.samples)?;
+    let mut quantized: Vec<u16> = hmap
+        .samples
+        .iter()
+        .map(|sample| quantize(*sample, min, scale))
+        .collect();
+    if encoding == HmapEncoding::RowDelta16 && hmap.width > 0 {
+        for row in quantized.chunks_exact_mut(hmap.width as usize) {
+            for index in (1..row.len()).rev() {
+                row[index] = row[index].wrapping_sub(row[index - 1]);
+            }
+        }
+    }
+
+    let mut out = Vec::with_capacity(HMAP_HEADER_LEN + QUANT_PARAMS_LEN + hmap.samples.len() * 2);
+    write_header(&mut out, hmap, encoding);
+    out.extend_from_slice(&min.to_le_bytes());
+    out.extend_from_slice(&scale.to_le_bytes());
+    for value in quantized {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    Ok(out)
+}
+
 pub fn decode_hmap(bytes: &[u8]) -> anyhow::Result<HmapSection> {
-    if bytes.len() < 12 {
+    if bytes.len() < HMAP_HEADER_LEN {
         bail!("HMAP section too small");
     }
     let version = u16::from_le_bytes(bytes[0..2].try_into()?);
@@ -33,21 +98,138 @@ pub fn decode_hmap(bytes: &[u8]) -> anyhow::Result<HmapSection> {
     }
     let width = u16::from_le_bytes(bytes[4..6].try_into()?);
     let height = u16::from_le_bytes(bytes[6..8].try_into()?);
+    let encoding = HmapEncoding::from_u16(u16::from_le_bytes(bytes[8..10].try_into()?))?;
     let expected = width as usize * height as usize;
-    let data_offset = 12;
+    let samples = match encoding {
+        HmapEncoding::F32 => decode_f32_samples(&bytes[HMAP_HEADER_LEN..], expected)?,
+        HmapEncoding::Quantized16 | HmapEncoding::RowDelta16 => decode_quantized_samples(
+            &bytes[HMAP_HEADER_LEN..],
+            width as usize,
+            expected,
+            encoding == HmapEncoding::RowDelta16,
+        )?,
+    };
+    Ok(HmapSection {
+        width,
+        height,
+        samples,
+    })
+}
+
+/// Returns the sample encoding recorded in an encoded HMAP payload without decoding samples.
+pub fn hmap_encoding(bytes: &[u8]) -> anyhow::Result<HmapEncoding> {
+    if bytes.len() < HMAP_HEADER_LEN {
+        bail!("HMAP section too small");
+    }
+    HmapEncoding::from_u16(u16::from_le_bytes(bytes[8..10].try_into()?))
+}
+
+/// Largest error, in meters, the payload's encoding adds to a sample: half the quantization
+/// step for quantized encodings, 0 for `F32`.
+pub fn hmap_quantization_error(bytes: &[u8]) -> anyhow::Result<f32> {
+    if hmap_encoding(bytes)? == HmapEncoding::F32 {
+        return Ok(0.0);
+    }
+    let params = bytes
+        .get(HMAP_HEADER_LEN..HMAP_HEADER_LEN + QUANT_PARAMS_LEN)
+        .ok_or_else(|| anyhow!("HMAP quantization params truncated"))?;
+    let scale = f32::from_le_bytes(params[4..8].try_into()?);
+    if !scale.is_finite() || scale < 0.0 {
+        bail!("HMAP quantization params invalid");
+    }
+    Ok(scale * 0.5)
+}
+
+fn write_header(out: &mut Vec<u8>, hmap: &HmapSection, encoding: HmapEncoding) {
+    out.extend_from_slice(&HMAP_VERSION.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&hmap.width.to_le_bytes());
+    out.extend_from_slice(&hmap.height.to_le_bytes());
+    out.extend_from_slice(&encoding.as_u16().to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+}
+
+fn decode_f32_samples(data: &[u8], expected: usize) -> anyhow::Result<Vec<f32>> {
     let mut samples = Vec::with_capacity(expected);
-    let mut cursor = data_offset;
-    while cursor + 4 <= bytes.len() && samples.len() < expected {
-        let sample = f32::from_le_bytes(bytes[cursor..cursor + 4].try_into()?);
+    let mut cursor = 0;
+    while cursor + 4 <= data.len() && samples.len() < expected {
+        let sample = f32::from_le_bytes(data[cursor..cursor + 4].try_into()?);
         samples.push(sample);
         cursor += 4;
     }
     if samples.len() != expected {
         bail!("HMAP sample count mismatch");
     }
-    Ok(HmapSection {
-        width,
-        height,
-        samples,
-    })
+    Ok(samples)
+}
+
+fn decode_quantized_samples(
+    data: &[u8],
+    width: usize,
+    expected: usize,
+    row_delta: bool,
+) -> anyhow::Result<Vec<f32>> {
+    if data.len() < QUANT_PARAMS_LEN {
+        bail!("HMAP quantization params truncated");
+    }
+    let min = f32::from_le_bytes(data[0..4].try_into()?);
+    let scale = f32::from_le_bytes(data[4..8].try_into()?);
+    if !min.is_finite() || !scale.is_finite() || scale < 0.0 {
+        bail!("HMAP quantization params invalid");
+    }
+    let values = &data[QUANT_PARAMS_LEN..];
+    if values.len() < expected * 2 {
+        bail!("HMAP sample count mismatch");
+    }
+    let mut quantized: Vec<u16> = values
+        .chunks_exact(2)
+        .take(expected)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    if row_delta && width > 0 {
+        for row in quantized.chunks_exact_mut(width) {
+            for index in 1..row.len() {
+                row[index] = row[index].wrapping_add(row[index - 1]);
+            }
+        }
+    }
+    Ok(quantized
+        .into_iter()
+        .map(|value| min + f32::from(value) * scale)
+        .collect())
+}
+
+/// Returns `(min, scale)` for the samples; both are checked here so the encoder never writes
+/// params the decoder rejects.
+fn quantization_params(samples: &[f32]) -> anyhow::Result<(f32, f32)> {
+    if samples.iter().any(|sample| !sample.is_finite()) {
+        bail!("HMAP quantization requires finite samples");
+    }
+    if samples.is_empty() {
+        return Ok((0.0, 0.0));
+    }
+    let mut min = f32::INFINITY;
+    let mut max = f32::NEG_INFINITY;
+    for sample in samples {
+        min = min.min(*sample);
+        max = max.max(*sample);
+    }
+    let scale = (max - min) / f32::from(u16::MAX);
+    if !scale.is_finite() {
+        bail!(
+            "HMAP sample range {} .. {} is too large to quantize",
+            min,
+            max
+        );
+    }
+    Ok((min, scale))
+}
+
+fn quantize(sample: f32, min: f32, scale: f32) -> u16 {
+    if scale == 0.0 {
+        return 0;
+    }
+    ((sample - min) / scale)
+        .round()
+        .clamp(0.0, f32::from(u16::MAX)) as u16
 }
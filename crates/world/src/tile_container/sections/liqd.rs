@@ -1,5 +1,7 @@
 use anyhow::{anyhow, bail};
 
+use super::{read_string, write_string};
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct LiqdSection {
     pub width: u16,
@@ -23,24 +25,54 @@ pub enum LiqdKind {
     Custom(String),
 }
 
-const LIQD_VERSION: u16 = 1;
+pub(super) const LIQD_VERSION: u16 = 2;
+const LIQD_VERSION_V1: u16 = 1;
+const LIQD_KIND_CUSTOM: u16 = 255;
+/// Name v1 files decode custom kinds to, since v1 only stored a sentinel.
+const LEGACY_CUSTOM_NAME: &str = "custom";
+
+pub fn encode_liqd(liqd: &LiqdSection) -> anyhow::Result<Vec<u8>> {
+    let mut names: Vec<&str> = Vec::new();
+    let mut body_kinds = Vec::with_capacity(liqd.bodies.len());
+    for body in &liqd.bodies {
+        let (kind, name_index) = match &body.kind {
+            LiqdKind::Custom(name) => {
+                let index = match names.iter().position(|existing| *existing == name) {
+                    Some(index) => index,
+                    None => {
+                        names.push(name);
+                        names.len() - 1
+                    }
+                };
+                let index =
+                    u16::try_from(index).map_err(|_| anyhow!("too many custom LIQD kinds"))?;
+                (LIQD_KIND_CUSTOM, index)
+            }
+            builtin => (encode_builtin_kind(builtin), 0),
+        };
+        body_kinds.push((kind, name_index));
+    }
+    let body_count =
+        u16::try_from(liqd.bodies.len()).map_err(|_| anyhow!("too many LIQD bodies"))?;
 
-pub fn encode_liqd(liqd: &LiqdSection) -> Vec<u8> {
     let mut out = Vec::with_capacity(12 + liqd.mask.len() + liqd.bodies.len() * 12);
     out.extend_from_slice(&LIQD_VERSION.to_le_bytes());
     out.extend_from_slice(&0u16.to_le_bytes());
     out.extend_from_slice(&liqd.width.to_le_bytes());
     out.extend_from_slice(&liqd.height.to_le_bytes());
-    out.extend_from_slice(&(liqd.bodies.len() as u16).to_le_bytes());
-    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&body_count.to_le_bytes());
+    out.extend_from_slice(&(names.len() as u16).to_le_bytes());
     out.extend_from_slice(&liqd.mask);
-    for body in &liqd.bodies {
+    for (body, (kind, name_index)) in liqd.bodies.iter().zip(body_kinds) {
         out.extend_from_slice(&body.id.to_le_bytes());
         out.extend_from_slice(&body.height.to_le_bytes());
-        out.extend_from_slice(&encode_liqd_kind(&body.kind).to_le_bytes());
-        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&kind.to_le_bytes());
+        out.extend_from_slice(&name_index.to_le_bytes());
     }
-    out
+    for name in names {
+        write_string(&mut out, name)?;
+    }
+    Ok(out)
 }
 
 pub fn decode_liqd(bytes: &[u8]) -> anyhow::Result<LiqdSection> {
@@ -48,12 +80,17 @@ pub fn decode_liqd(bytes: &[u8]) -> anyhow::Result<LiqdSection> {
         bail!("LIQD section too small");
     }
     let version = u16::from_le_bytes(bytes[0..2].try_into()?);
-    if version != LIQD_VERSION {
+    if version != LIQD_VERSION && version != LIQD_VERSION_V1 {
         bail!("unsupported LIQD version {}", version);
     }
     let width = u16::from_le_bytes(bytes[4..6].try_into()?);
     let height = u16::from_le_bytes(bytes[6..8].try_into()?);
     let body_count = u16::from_le_bytes(bytes[8..10].try_into()?);
+    let name_count = if version == LIQD_VERSION {
+        u16::from_le_bytes(bytes[10..12].try_into()?)
+    } else {
+        0
+    };
     let mask_len = width as usize * height as usize;
     let mut cursor = 12;
     if bytes.len() < cursor + mask_len {
@@ -61,7 +98,7 @@ pub fn decode_liqd(bytes: &[u8]) -> anyhow::Result<LiqdSection> {
     }
     let mask = bytes[cursor..cursor + mask_len].to_vec();
     cursor += mask_len;
-    let mut bodies = Vec::with_capacity(body_count as usize);
+    let mut raw_bodies = Vec::with_capacity(body_count as usize);
     for _ in 0..body_count {
         if bytes.len() < cursor + 12 {
             bail!("LIQD body truncated");
@@ -69,10 +106,31 @@ pub fn decode_liqd(bytes: &[u8]) -> anyhow::Result<LiqdSection> {
         let id = u32::from_le_bytes(bytes[cursor..cursor + 4].try_into()?);
         let height = f32::from_le_bytes(bytes[cursor + 4..cursor + 8].try_into()?);
         let kind_raw = u16::from_le_bytes(bytes[cursor + 8..cursor + 10].try_into()?);
-        let kind = decode_liqd_kind(kind_raw)?;
-        bodies.push(LiqdBody { id, height, kind });
+        let name_index = u16::from_le_bytes(bytes[cursor + 10..cursor + 12].try_into()?);
+        raw_bodies.push((id, height, kind_raw, name_index));
         cursor += 12;
     }
+    let mut names = Vec::with_capacity(name_count as usize);
+    for _ in 0..name_count {
+        let (name, name_len) = read_string(bytes, cursor)?;
+        cursor += name_len;
+        names.push(name);
+    }
+
+    let mut bodies = Vec::with_capacity(raw_bodies.len());
+    for (id, height, kind_raw, name_index) in raw_bodies {
+        let kind = if kind_raw != LIQD_KIND_CUSTOM {
+            decode_builtin_kind(kind_raw)?
+        } else if version == LIQD_VERSION_V1 {
+            LiqdKind::Custom(LEGACY_CUSTOM_NAME.to_string())
+        } else {
+            let name = names
+                .get(name_index as usize)
+                .ok_or_else(|| anyhow!("LIQD custom kind index {} out of range", name_index))?;
+            LiqdKind::Custom(name.clone())
+        };
+        bodies.push(LiqdBody { id, height, kind });
+    }
     Ok(LiqdSection {
         width,
         height,
@@ -81,23 +139,20 @@ pub fn decode_liqd(bytes: &[u8]) -> anyhow::Result<LiqdSection> {
     })
 }
 
-// NOTE: Custom liquid names are not serialized yet; only a sentinel is stored.
-// Keep this behavior until the liquids schema checklist defines a stable encoding.
-fn encode_liqd_kind(kind: &LiqdKind) -> u16 {
+fn encode_builtin_kind(kind: &LiqdKind) -> u16 {
     match kind {
         LiqdKind::Water => 0,
         LiqdKind::Lava => 1,
         LiqdKind::Slime => 2,
-        LiqdKind::Custom(_) => 255,
+        LiqdKind::Custom(_) => LIQD_KIND_CUSTOM,
     }
 }
 
-fn decode_liqd_kind(raw: u16) -> anyhow::Result<LiqdKind> {
+fn decode_builtin_kind(raw: u16) -> anyhow::Result<LiqdKind> {
     match raw {
         0 => Ok(LiqdKind::Water),
         1 => Ok(LiqdKind::Lava),
         2 => Ok(LiqdKind::Slime),
-        255 => Ok(LiqdKind::Custom("custom".to_string())),
         other => Err(anyhow!("unknown LIQD kind {}", other)),
     }
 }
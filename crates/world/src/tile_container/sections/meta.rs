@@ -9,7 +9,7 @@ pub struct MetaSection {
     pub created_timestamp: u64,
 }
 
-const META_VERSION: u16 = 1;
+pub(super) const META_VERSION: u16 = 1;
 
 pub fn encode_meta(meta: &MetaSection) -> Vec<u8> {
     let mut out = Vec::with_capacity(32);
@@ -0,0 +1,100 @@
+//! Section payload migrations. The reader returns payloads exactly as stored; callers that decode
+//! sections into structs go through [`decode_current_section`] to get the current layout.
+
+use crate::tile_container::{TileContainerReader, TileSectionTag};
+use anyhow::{anyhow, bail, Context};
+use std::borrow::Cow;
+
+use super::{current_section_version, decode_liqd, encode_liqd, payload_version};
+
+/// Rewrites one tile section payload from `from` to `to`. Keyed by the payload's leading
+/// `version u16`, which is what the section decoders check.
+pub struct SectionMigration {
+    pub tag: TileSectionTag,
+    pub from: u16,
+    pub to: u16,
+    pub apply: fn(&[u8]) -> anyhow::Result<Vec<u8>>,
+    /// What the step loses or fills with defaults; empty when lossless. Shown in dry-run plans.
+    pub note: &'static str,
+}
+
+const SECTION_MIGRATIONS: &[SectionMigration] = &[SectionMigration {
+    tag: TileSectionTag::LIQD,
+    from: 1,
+    to: 2,
+    apply: migrate_liqd_v1_to_v2,
+    note: "custom liquid kinds have no stored name in v1 and become \"custom\"",
+}];
+
+pub fn section_migrations() -> &'static [SectionMigration] {
+    SECTION_MIGRATIONS
+}
+
+/// Brings a decoded section payload up to the current encoder version.
+///
+/// Returns `None` when the payload is already current or the tag is unknown (unknown sections
+/// are carried through untouched).
+pub fn migrate_section_payload(
+    tag: TileSectionTag,
+    bytes: &[u8],
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let Some(current) = current_section_version(tag) else {
+        return Ok(None);
+    };
+    let version = payload_version(bytes)?;
+    if version == current {
+        return Ok(None);
+    }
+
+    let mut payload = bytes.to_vec();
+    for migration in section_chain(tag, version, current)? {
+        payload = (migration.apply)(&payload)
+            .with_context(|| format!("migrate {} {} -> {}", tag, migration.from, migration.to))?;
+    }
+    Ok(Some(payload))
+}
+
+/// Decodes a section and migrates it to the current payload version. Borrows from the reader's
+/// buffer when it can (see `TileContainerReader::decode_section_cow`) and no migration was needed.
+pub fn decode_current_section(
+    reader: &TileContainerReader,
+    tag: TileSectionTag,
+) -> anyhow::Result<Cow<'_, [u8]>> {
+    let decoded = reader.decode_section_cow(tag)?;
+    match migrate_section_payload(tag, &decoded)? {
+        Some(migrated) => Ok(Cow::Owned(migrated)),
+        None => Ok(decoded),
+    }
+}
+
+/// Registered steps that take a `tag` payload from `from` to `current`.
+pub(crate) fn section_chain(
+    tag: TileSectionTag,
+    from: u16,
+    current: u16,
+) -> anyhow::Result<Vec<&'static SectionMigration>> {
+    if from > current {
+        bail!(
+            "{} version {} is newer than supported {}",
+            tag,
+            from,
+            current
+        );
+    }
+    let mut chain = Vec::new();
+    let mut version = from;
+    while version < current {
+        let migration = SECTION_MIGRATIONS
+            .iter()
+            .find(|entry| entry.tag == tag && entry.from == version)
+            .ok_or_else(|| anyhow!("no {} migration registered from {}", tag, version))?;
+        chain.push(migration);
+        version = migration.to;
+    }
+    Ok(chain)
+}
+
+fn migrate_liqd_v1_to_v2(bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+    // The LIQD decoder still understands v1; re-encoding writes the v2 string-table layout.
+    encode_liqd(&decode_liqd(bytes)?)
+}
@@ -1,14 +1,59 @@
+mod addx;
 mod hmap;
 mod liqd;
 mod meta;
+mod migrate;
 mod prop;
+mod spln;
 mod strings;
 mod wmap;
 
-pub use hmap::{decode_hmap, encode_hmap, HmapSection};
+pub use addx::{decode_addx, encode_addx, is_valid_addx_key, AddxEntry, AddxSection};
+pub use hmap::{
+    decode_hmap, encode_hmap, encode_hmap_with, hmap_encoding, hmap_quantization_error,
+    HmapEncoding, HmapSection,
+};
 pub use liqd::{decode_liqd, encode_liqd, LiqdBody, LiqdKind, LiqdSection};
 pub use meta::{decode_meta, encode_meta, MetaSection};
+pub(crate) use migrate::section_chain;
+pub use migrate::{
+    decode_current_section, migrate_section_payload, section_migrations, SectionMigration,
+};
 pub use prop::{decode_prop, encode_prop, PropRecord, PropSection};
+pub use spln::{decode_spln, encode_spln, SplinePoint, SplineRecord, SplnSection};
 pub use wmap::{decode_wmap, encode_wmap, WmapSection};
 
 use strings::{read_string, write_string};
+
+use crate::tile_container::TileSectionTag;
+use anyhow::bail;
+
+/// Payload version the current encoder writes for a known section tag.
+pub fn current_section_version(tag: TileSectionTag) -> Option<u16> {
+    let version = if tag == TileSectionTag::META {
+        meta::META_VERSION
+    } else if tag == TileSectionTag::HMAP {
+        hmap::HMAP_VERSION
+    } else if tag == TileSectionTag::WMAP {
+        wmap::WMAP_VERSION
+    } else if tag == TileSectionTag::LIQD {
+        liqd::LIQD_VERSION
+    } else if tag == TileSectionTag::PROP {
+        prop::PROP_VERSION
+    } else if tag == TileSectionTag::SPLN {
+        spln::SPLN_VERSION
+    } else if tag == TileSectionTag::ADDX {
+        addx::ADDX_VERSION
+    } else {
+        return None;
+    };
+    Some(version)
+}
+
+/// Reads the leading `version u16` every section payload starts with.
+pub fn payload_version(bytes: &[u8]) -> anyhow::Result<u16> {
+    if bytes.len() < 2 {
+        bail!("section payload too small for a version");
+    }
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
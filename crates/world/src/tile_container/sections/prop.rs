@@ -17,7 +17,7 @@ pub struct PropRecord {
     pub scale: [f32; 3],
 }
 
-const PROP_VERSION: u16 = 1;
+pub(super) const PROP_VERSION: u16 = 1;
 
 pub fn encode_prop(prop: &PropSection) -> anyhow::Result<Vec<u8>> {
     let mut out = Vec::new();
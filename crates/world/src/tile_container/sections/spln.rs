@@ -0,0 +1,124 @@
+use anyhow::{anyhow, bail};
+
+use super::{read_string, write_string};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplnSection {
+    pub splines: Vec<SplineRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplineRecord {
+    pub id: u32,
+    pub closed: bool,
+    pub tags: Vec<String>,
+    pub points: Vec<SplinePoint>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplinePoint {
+    pub position: [f32; 3],
+    pub width: f32,
+    pub tangent_in: [f32; 3],
+    pub tangent_out: [f32; 3],
+}
+
+pub(super) const SPLN_VERSION: u16 = 1;
+const SPLINE_FLAG_CLOSED: u16 = 1;
+const SPLINE_POINT_SIZE: usize = 40;
+
+pub fn encode_spln(spln: &SplnSection) -> anyhow::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&SPLN_VERSION.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&(spln.splines.len() as u32).to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    let mut splines = spln.splines.clone();
+    splines.sort_by_key(|spline| spline.id);
+    for spline in &splines {
+        let tag_count = u16::try_from(spline.tags.len())
+            .map_err(|_| anyhow!("spline {} has too many tags", spline.id))?;
+        let point_count = u32::try_from(spline.points.len())
+            .map_err(|_| anyhow!("spline {} has too many points", spline.id))?;
+        let flags = if spline.closed { SPLINE_FLAG_CLOSED } else { 0 };
+        out.extend_from_slice(&spline.id.to_le_bytes());
+        out.extend_from_slice(&flags.to_le_bytes());
+        out.extend_from_slice(&tag_count.to_le_bytes());
+        out.extend_from_slice(&point_count.to_le_bytes());
+        for tag in &spline.tags {
+            write_string(&mut out, tag)?;
+        }
+        for point in &spline.points {
+            for value in point.position {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            out.extend_from_slice(&point.width.to_le_bytes());
+            for value in point.tangent_in {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            for value in point.tangent_out {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+    Ok(out)
+}
+
+pub fn decode_spln(bytes: &[u8]) -> anyhow::Result<SplnSection> {
+    if bytes.len() < 12 {
+        bail!("SPLN section too small");
+    }
+    let version = u16::from_le_bytes(bytes[0..2].try_into()?);
+    if version != SPLN_VERSION {
+        bail!("unsupported SPLN version {}", version);
+    }
+    let count = u32::from_le_bytes(bytes[4..8].try_into()?);
+    let mut cursor = 12;
+    let mut splines = Vec::new();
+    for _ in 0..count {
+        if bytes.len() < cursor + 12 {
+            bail!("SPLN record truncated");
+        }
+        let id = u32::from_le_bytes(bytes[cursor..cursor + 4].try_into()?);
+        let flags = u16::from_le_bytes(bytes[cursor + 4..cursor + 6].try_into()?);
+        let tag_count = u16::from_le_bytes(bytes[cursor + 6..cursor + 8].try_into()?);
+        let point_count = u32::from_le_bytes(bytes[cursor + 8..cursor + 12].try_into()?) as usize;
+        cursor += 12;
+
+        let mut tags = Vec::with_capacity(tag_count as usize);
+        for _ in 0..tag_count {
+            let (tag, tag_len) = read_string(bytes, cursor)?;
+            cursor += tag_len;
+            tags.push(tag);
+        }
+
+        let points_len = point_count
+            .checked_mul(SPLINE_POINT_SIZE)
+            .ok_or_else(|| anyhow!("SPLN point count overflow"))?;
+        if bytes.len() < cursor + points_len {
+            bail!("SPLN points truncated");
+        }
+        let mut points = Vec::with_capacity(point_count);
+        for chunk in bytes[cursor..cursor + points_len].chunks_exact(SPLINE_POINT_SIZE) {
+            let mut values = [0f32; 10];
+            for (index, value) in values.iter_mut().enumerate() {
+                *value = f32::from_le_bytes(chunk[index * 4..index * 4 + 4].try_into()?);
+            }
+            points.push(SplinePoint {
+                position: [values[0], values[1], values[2]],
+                width: values[3],
+                tangent_in: [values[4], values[5], values[6]],
+                tangent_out: [values[7], values[8], values[9]],
+            });
+        }
+        cursor += points_len;
+
+        splines.push(SplineRecord {
+            id,
+            closed: flags & SPLINE_FLAG_CLOSED != 0,
+            tags,
+            points,
+        });
+    }
+    Ok(SplnSection { splines })
+}
@@ -8,7 +8,7 @@ pub struct WmapSection {
     pub weights: Vec<u8>,
 }
 
-const WMAP_VERSION: u16 = 1;
+pub(super) const WMAP_VERSION: u16 = 1;
 
 pub fn encode_wmap(wmap: &WmapSection) -> Vec<u8> {
     let mut out = Vec::with_capacity(12 + wmap.weights.len());
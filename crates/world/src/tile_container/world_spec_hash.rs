@@ -36,7 +36,7 @@ pub fn hash_region(region: &str) -> u64 {
     fnv1a_64(region.as_bytes())
 }
 
-fn fnv1a_64(data: &[u8]) -> u64 {
+pub(crate) fn fnv1a_64(data: &[u8]) -> u64 {
     let mut hash = 0xcbf29ce484222325u64;
     for byte in data {
         hash ^= u64::from(*byte);
@@ -1,6 +1,7 @@
+use crate::tile_container::chunked::encode_chunked;
 use crate::tile_container::{
-    alignment_padding, TileContainerHeader, TileSectionDirEntry, TileSectionTag, CONTAINER_VERSION,
-    DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE, HEADER_SIZE,
+    alignment_padding, encode_payload, TileContainerHeader, TileSectionDirEntry, TileSectionTag,
+    CONTAINER_VERSION, DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE, HEADER_SIZE, SECTION_FLAG_CHUNKED,
 };
 use anyhow::{anyhow, Context};
 use crc32fast::Hasher;
@@ -22,6 +23,7 @@ pub struct TileSectionPayload {
 pub struct TileContainerWriter {
     sections: Vec<TileSectionPayload>,
     alignment: u64,
+    chunks_per_tile: u16,
 }
 
 impl Default for TileContainerWriter {
@@ -35,6 +37,7 @@ impl TileContainerWriter {
         Self {
             sections: Vec::new(),
             alignment: DEFAULT_ALIGNMENT,
+            chunks_per_tile: 0,
         }
     }
 
@@ -43,6 +46,13 @@ impl TileContainerWriter {
         self
     }
 
+    /// Chunk grid used for sections flagged with `SECTION_FLAG_CHUNKED`
+    /// (normally `WorldSpec::chunks_per_tile`).
+    pub fn chunks_per_tile(mut self, chunks_per_tile: u16) -> Self {
+        self.chunks_per_tile = chunks_per_tile;
+        self
+    }
+
     pub fn add_section(&mut self, payload: TileSectionPayload) {
         self.sections.push(payload);
     }
@@ -71,11 +81,9 @@ impl TileContainerWriter {
         for payload in sections {
             let padding = alignment_padding(current_offset, self.alignment);
             current_offset += padding;
-            let stored_len = payload.decoded.len() as u64;
-            let decoded_len = stored_len;
-            let mut hasher = Hasher::new();
-            hasher.update(&payload.decoded);
-            let crc32 = hasher.finalize();
+            let (stored, crc32) = encode_section(&payload, self.chunks_per_tile)?;
+            let stored_len = stored.len() as u64;
+            let decoded_len = payload.decoded.len() as u64;
 
             directory.push(TileSectionDirEntry {
                 tag: payload.tag,
@@ -88,7 +96,7 @@ impl TileContainerWriter {
                 crc32,
             });
 
-            stored_payloads.push(payload.decoded);
+            stored_payloads.push(stored);
             current_offset += stored_len;
         }
 
@@ -118,24 +126,59 @@ impl TileContainerWriter {
 
         file.sync_all()?;
 
-        if path.exists() {
-            let backup_path = path.with_extension("tile.bak");
-            if backup_path.exists() {
-                fs::remove_file(&backup_path)
-                    .with_context(|| format!("remove backup {:?}", backup_path))?;
-            }
-            fs::rename(&path, &backup_path)
-                .with_context(|| format!("backup existing tile {:?}", path))?;
-        }
-
-        fs::rename(&tmp_path, &path)
-            .with_context(|| format!("rename temp tile {:?} -> {:?}", tmp_path, path))?;
+        replace_with_backup(&tmp_path, &path)?;
 
         Ok(path)
     }
 }
 
-fn compare_sections(a: TileSectionTag, b: TileSectionTag) -> Ordering {
+/// Encodes a payload into its stored bytes (codec and, if flagged, chunk layout) and returns
+/// them together with their crc32.
+pub(crate) fn encode_section(
+    payload: &TileSectionPayload,
+    chunks_per_tile: u16,
+) -> anyhow::Result<(Vec<u8>, u32)> {
+    let stored = if payload.flags & SECTION_FLAG_CHUNKED != 0 {
+        if chunks_per_tile == 0 {
+            return Err(anyhow!(
+                "section {} is chunked but chunks_per_tile is not set",
+                payload.tag
+            ));
+        }
+        encode_chunked(
+            payload.tag,
+            &payload.decoded,
+            payload.codec,
+            chunks_per_tile,
+        )
+    } else {
+        encode_payload(payload.codec, &payload.decoded)
+    }
+    .with_context(|| format!("encode section {}", payload.tag))?;
+    let mut hasher = Hasher::new();
+    hasher.update(&stored);
+    let crc32 = hasher.finalize();
+    Ok((stored, crc32))
+}
+
+/// Moves a fully written `tmp_path` over `path`, rotating the previous file to `*.tile.bak`.
+pub(crate) fn replace_with_backup(tmp_path: &Path, path: &Path) -> anyhow::Result<()> {
+    if path.exists() {
+        let backup_path = path.with_extension("tile.bak");
+        if backup_path.exists() {
+            fs::remove_file(&backup_path)
+                .with_context(|| format!("remove backup {:?}", backup_path))?;
+        }
+        fs::rename(path, &backup_path)
+            .with_context(|| format!("backup existing tile {:?}", path))?;
+    }
+
+    fs::rename(tmp_path, path)
+        .with_context(|| format!("rename temp tile {:?} -> {:?}", tmp_path, path))?;
+    Ok(())
+}
+
+pub(crate) fn compare_sections(a: TileSectionTag, b: TileSectionTag) -> Ordering {
     canonical_rank(a)
         .cmp(&canonical_rank(b))
         .then_with(|| a.0.cmp(&b.0))
@@ -0,0 +1,84 @@
+//! PROP asset references. Every `AssetId` a PROP record names should exist in the project's asset
+//! catalog; dangling ones are reported per tile and asset, so they surface before export rather
+//! than when the runtime fails to load them.
+
+use crate::assets::AssetCatalog;
+use crate::tile_container::world_spec_hash::fnv1a_64;
+use crate::tile_container::{PropSection, TileSectionTag};
+use foundation::ids::AssetId;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use super::instances::InstanceLocation;
+use super::{codes, ValidationIssue};
+
+/// Reports one issue per tile and missing asset, with the number of instances referencing it.
+pub(super) fn validate_tile_assets(
+    tile: &InstanceLocation,
+    prop: &PropSection,
+    catalog: &AssetCatalog,
+) -> Vec<ValidationIssue> {
+    let mut missing: BTreeMap<&AssetId, usize> = BTreeMap::new();
+    for instance in &prop.instances {
+        if !catalog.contains(&instance.asset) {
+            *missing.entry(&instance.asset).or_default() += 1;
+        }
+    }
+    missing
+        .into_iter()
+        .map(|(asset, count)| {
+            ValidationIssue::error(
+                codes::PROP_ASSET_MISSING,
+                format!(
+                    "PROP references missing asset {}/{} ({} instance{})",
+                    asset.namespace,
+                    asset.name,
+                    count,
+                    if count == 1 { "" } else { "s" }
+                ),
+            )
+            .with_path(tile.path.clone())
+            .with_tile(tile.tile)
+            .with_section(TileSectionTag::PROP)
+        })
+        .collect()
+}
+
+/// Without an assets directory every reference is missing, so the per-tile `PROP_ASSET_MISSING`
+/// issues are replaced by one issue for the directory (none when no tile references an asset).
+pub(super) fn fold_missing_assets_dir(assets_dir: &Path, issues: &mut Vec<ValidationIssue>) {
+    let mut tiles = BTreeSet::new();
+    issues.retain(|issue| {
+        if issue.code != codes::PROP_ASSET_MISSING {
+            return true;
+        }
+        tiles.insert(issue.path.clone());
+        false
+    });
+    if tiles.is_empty() {
+        return;
+    }
+    issues.push(
+        ValidationIssue::error(
+            codes::ASSETS_DIR_MISSING,
+            format!(
+                "assets directory missing; PROP sections of {} tile{} reference assets",
+                tiles.len(),
+                if tiles.len() == 1 { "" } else { "s" }
+            ),
+        )
+        .with_path(assets_dir.to_path_buf()),
+    );
+}
+
+/// Identifies the catalog's contents, so cached results are dropped when assets come or go.
+pub(super) fn catalog_key(catalog: &AssetCatalog) -> u64 {
+    let mut bytes = Vec::new();
+    for asset in catalog.iter() {
+        bytes.extend_from_slice(asset.namespace.as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(asset.name.as_bytes());
+        bytes.push(0);
+    }
+    fnv1a_64(&bytes)
+}
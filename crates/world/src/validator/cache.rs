@@ -0,0 +1,288 @@
+//! On-disk cache of validation results, so unchanged tiles are not re-read and re-decoded.
+//!
+//! Each world gets one JSON file under `<cache_dir>/validation/`. A tile's entry is reused while
+//! the file's size and modification time and its container header and section directory (which
+//! carries every section's CRC) are unchanged, and while the run's settings match those the
+//! entry was computed with. Results of world passes (region seams, world-wide instance ids) are
+//! reused while every tile they read is unchanged. A tile's asset reference issues are kept per
+//! tile and reused while the tile and the asset catalog are unchanged.
+//! ADDX validator functions are not part of the fingerprint; disable the cache after changing
+//! one without changing its key or version range.
+
+use crate::schema::WorldManifest;
+use crate::storage::WorldLayout;
+use crate::tile_container::world_spec_hash::{
+    fnv1a_64, hash_world_spec_from_manifest, hash_world_spec_legacy,
+};
+use crate::tile_container::TileContainerReader;
+use anyhow::Context;
+use foundation::ids::TileCoord;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::{codes, Severity, ValidationContext, ValidationIssue};
+
+/// Bumped whenever checks change in a way that makes stored results stale.
+const CACHE_VERSION: u32 = 3;
+const CACHE_SUBDIR: &str = "validation";
+
+/// Identifies one version of a tile file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct TileKey {
+    size: u64,
+    mtime_ns: u64,
+    /// FNV-1a of the header and directory bytes.
+    container: u64,
+}
+
+impl TileKey {
+    /// Returns `None` when the header or directory cannot be read; such tiles are never cached.
+    pub(super) fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let mtime_ns = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos() as u64;
+        let reader = TileContainerReader::open(path).ok()?;
+        let mut bytes = reader.header.to_bytes().to_vec();
+        for entry in &reader.directory {
+            bytes.extend_from_slice(&entry.to_bytes());
+        }
+        Some(Self {
+            size: metadata.len(),
+            mtime_ns,
+            container: fnv1a_64(&bytes),
+        })
+    }
+
+    fn to_bytes(self) -> [u8; 24] {
+        let mut bytes = [0u8; 24];
+        bytes[0..8].copy_from_slice(&self.size.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.mtime_ns.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.container.to_le_bytes());
+        bytes
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedIssue {
+    severity: Severity,
+    code: String,
+    message: String,
+    /// Relative to the world root.
+    path: Option<PathBuf>,
+    tile: Option<TileCoord>,
+    section: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedEntry<K> {
+    key: K,
+    issues: Vec<CachedIssue>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    /// Fingerprint of the settings the entries were computed with.
+    context: u64,
+    /// Keyed by tile path relative to the world root.
+    tiles: BTreeMap<String, CachedEntry<TileKey>>,
+    /// Cross-tile passes, keyed by pass name (e.g. `seams/<region>`).
+    passes: BTreeMap<String, CachedEntry<u64>>,
+    /// Asset reference issues by tile path, keyed by the tile and the catalog they were checked
+    /// against.
+    assets: BTreeMap<String, CachedEntry<(TileKey, u64)>>,
+}
+
+/// Results of the previous run for one world, plus the results of the current run. Only the
+/// latter are saved, so entries for removed tiles and regions drop out.
+pub(super) struct WorldCache {
+    file: PathBuf,
+    world_root: PathBuf,
+    previous: CacheFile,
+    current: CacheFile,
+}
+
+impl WorldCache {
+    /// Loads the world's cache; a missing, unreadable or outdated file starts an empty one.
+    pub(super) fn load(
+        cache_dir: &Path,
+        world: &WorldLayout,
+        manifest: &WorldManifest,
+        context: &ValidationContext,
+    ) -> Self {
+        let fingerprint = context_fingerprint(manifest, context);
+        let file = cache_dir
+            .join(CACHE_SUBDIR)
+            .join(format!("{}.json", world.world_id));
+        let previous = std::fs::read(&file)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|cache| cache.version == CACHE_VERSION && cache.context == fingerprint)
+            .unwrap_or_default();
+        Self {
+            file,
+            world_root: world.world_root.clone(),
+            previous,
+            current: CacheFile {
+                version: CACHE_VERSION,
+                context: fingerprint,
+                ..CacheFile::default()
+            },
+        }
+    }
+
+    pub(super) fn tile_issues(&self, path: &Path, key: TileKey) -> Option<Vec<ValidationIssue>> {
+        let entry = self.previous.tiles.get(&self.relative(path))?;
+        if entry.key != key {
+            return None;
+        }
+        self.restore(&entry.issues)
+    }
+
+    pub(super) fn store_tile(&mut self, path: &Path, key: TileKey, issues: &[ValidationIssue]) {
+        let entry = CachedEntry {
+            key,
+            issues: self.capture(issues),
+        };
+        self.current.tiles.insert(self.relative(path), entry);
+    }
+
+    pub(super) fn asset_issues(
+        &self,
+        path: &Path,
+        key: TileKey,
+        catalog_key: u64,
+    ) -> Option<Vec<ValidationIssue>> {
+        let entry = self.previous.assets.get(&self.relative(path))?;
+        if entry.key != (key, catalog_key) {
+            return None;
+        }
+        self.restore(&entry.issues)
+    }
+
+    pub(super) fn store_assets(
+        &mut self,
+        path: &Path,
+        key: TileKey,
+        catalog_key: u64,
+        issues: &[ValidationIssue],
+    ) {
+        let entry = CachedEntry {
+            key: (key, catalog_key),
+            issues: self.capture(issues),
+        };
+        self.current.assets.insert(self.relative(path), entry);
+    }
+
+    /// Key for a cross-tile pass over `tiles`: the path and key of every tile it reads, plus
+    /// `salt` for any other input the pass depends on.
+    pub(super) fn tiles_key(&self, salt: u64, tiles: &[(&Path, TileKey)]) -> u64 {
+        let mut bytes = salt.to_le_bytes().to_vec();
+        for (path, key) in tiles {
+            bytes.extend_from_slice(self.relative(path).as_bytes());
+            bytes.push(0);
+            bytes.extend_from_slice(&key.to_bytes());
+        }
+        fnv1a_64(&bytes)
+    }
+
+    pub(super) fn pass_issues(&self, pass: &str, key: u64) -> Option<Vec<ValidationIssue>> {
+        let entry = self.previous.passes.get(pass)?;
+        if entry.key != key {
+            return None;
+        }
+        self.restore(&entry.issues)
+    }
+
+    pub(super) fn store_pass(&mut self, pass: &str, key: u64, issues: &[ValidationIssue]) {
+        let entry = CachedEntry {
+            key,
+            issues: self.capture(issues),
+        };
+        self.current.passes.insert(pass.to_string(), entry);
+    }
+
+    /// Writes the current run's results, replacing the file atomically.
+    pub(super) fn save(&self) -> anyhow::Result<()> {
+        let dir = self.file.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(dir).with_context(|| format!("create cache dir {:?}", dir))?;
+        let tmp = self.file.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&self.current)?)
+            .with_context(|| format!("write validation cache {:?}", tmp))?;
+        std::fs::rename(&tmp, &self.file)
+            .with_context(|| format!("replace validation cache {:?}", self.file))?;
+        Ok(())
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.world_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    fn capture(&self, issues: &[ValidationIssue]) -> Vec<CachedIssue> {
+        issues
+            .iter()
+            .map(|issue| CachedIssue {
+                severity: issue.severity,
+                code: issue.code.to_string(),
+                message: issue.message.clone(),
+                path: issue
+                    .path
+                    .as_ref()
+                    .map(|path| path.strip_prefix(&self.world_root).unwrap_or(path).into()),
+                tile: issue.tile,
+                section: issue.section.clone(),
+            })
+            .collect()
+    }
+
+    /// Rebuilds cached issues; an unknown code (from a newer build) invalidates the entry.
+    fn restore(&self, cached: &[CachedIssue]) -> Option<Vec<ValidationIssue>> {
+        cached
+            .iter()
+            .map(|issue| {
+                Some(ValidationIssue {
+                    severity: issue.severity,
+                    code: codes::lookup(&issue.code)?,
+                    message: issue.message.clone(),
+                    path: issue.path.as_ref().map(|path| self.world_root.join(path)),
+                    tile: issue.tile,
+                    section: issue.section.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Hashes every setting that tile and cross-tile checks depend on besides the tile bytes themselves.
+fn context_fingerprint(manifest: &WorldManifest, context: &ValidationContext) -> u64 {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&hash_world_spec_from_manifest(manifest).to_le_bytes());
+    bytes.extend_from_slice(&hash_world_spec_legacy(manifest.world_spec).to_le_bytes());
+    bytes.extend_from_slice(&context.seam_tolerance.to_le_bytes());
+    for kind in context.liquid_kinds {
+        bytes.extend_from_slice(kind.as_bytes());
+        bytes.push(0);
+    }
+    bytes.push(0xff);
+    let mut keys: Vec<&str> = context.extensions.keys().collect();
+    keys.sort_unstable();
+    for key in keys {
+        bytes.extend_from_slice(key.as_bytes());
+        bytes.push(0);
+        if let Some(extension) = context.extensions.get(key) {
+            bytes.extend_from_slice(&extension.min_version.to_le_bytes());
+            bytes.extend_from_slice(&extension.max_version.to_le_bytes());
+        }
+    }
+    fnv1a_64(&bytes)
+}
@@ -0,0 +1,75 @@
+//! Stable identifiers for validation issues. Tools and CI filters match on these, so existing
+//! codes are never renamed or reused; messages may change freely.
+
+/// Declares each code as a `&str` constant holding its own name, plus [`ALL`].
+macro_rules! codes {
+    ($($name:ident,)*) => {
+        $(pub const $name: &str = stringify!($name);)*
+
+        /// Every code, e.g. for mapping stored strings back to the constants.
+        pub const ALL: &[&str] = &[$($name),*];
+    };
+}
+
+codes! {
+    // Project / world / region layout.
+    MANIFEST_READ_FAILED,
+    MANIFEST_VERSION_UNSUPPORTED,
+    MANIFEST_MIGRATION_FAILED,
+    WORLDS_DIR_MISSING,
+    WORLDS_DIR_READ_FAILED,
+    WORLD_ID_MISMATCH,
+    REGIONS_DIR_MISSING,
+    REGIONS_DIR_READ_FAILED,
+    REGION_NOT_LISTED,
+    REGION_ID_EMPTY,
+    REGION_BOUNDS_INVALID,
+    TILES_DIR_MISSING,
+    TILES_DIR_READ_FAILED,
+    TILE_FILENAME_INVALID,
+    ASSETS_DIR_READ_FAILED,
+    ASSETS_DIR_MISSING,
+
+    // Container header and directory.
+    TILE_HEADER_READ_FAILED,
+    SECTION_COUNT_EXCEEDED,
+    CONTAINER_VERSION_UNSUPPORTED,
+    DIRECTORY_OVERLAPS_HEADER,
+    REGION_HASH_MISMATCH,
+    WORLD_SPEC_HASH_MISMATCH,
+    TILE_ID_MISMATCH,
+    SECTION_TAG_INVALID,
+    SECTION_RANGE_INVALID,
+    SECTION_OVERLAP,
+    SECTION_MISALIGNED,
+
+    // Section payloads.
+    META_MISSING,
+    SECTION_READ_FAILED,
+    SECTION_DECODE_FAILED,
+    HMAP_DIM_MISMATCH,
+    HMAP_SAMPLE_INVALID,
+    HMAP_SEAM_MISMATCH,
+    WMAP_DIM_MISMATCH,
+    WMAP_WEIGHTS_UNNORMALIZED,
+    LIQD_DIM_MISMATCH,
+    LIQD_UNKNOWN_BODY,
+    LIQD_UNUSED_BODY,
+    LIQD_UNREGISTERED_KIND,
+    LIQD_HEIGHT_RANGE,
+    PROP_DUPLICATE_ID,
+    PROP_INSTANCE_COLLISION,
+    PROP_ASSET_MISSING,
+    PROP_TRANSFORM_INVALID,
+    SPLN_DUPLICATE_ID,
+    SPLN_TOO_FEW_POINTS,
+    SPLN_POINT_INVALID,
+    ADDX_KEY_NOT_NAMESPACED,
+    ADDX_DUPLICATE_KEY,
+    ADDX_ENTRY_INVALID,
+}
+
+/// Returns the constant for a code read back from storage, or `None` for unknown codes.
+pub fn lookup(code: &str) -> Option<&'static str> {
+    ALL.iter().copied().find(|known| *known == code)
+}
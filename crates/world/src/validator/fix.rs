@@ -0,0 +1,365 @@
+//! Safe, logged repairs for tiles that would otherwise fail validation.
+//!
+//! A repair only touches data whose intent is unambiguous: clamping heights, renormalizing
+//! weights, dropping unreferenced liquid bodies, giving duplicated prop instances fresh ids and
+//! re-deriving header hashes. Anything else (CRC failures, undecodable sections, NaN samples) is
+//! left for validation to report and quarantine. Repaired tiles are rewritten through
+//! `TileContainerWriter`, which keeps the previous file as `*.tile.bak`.
+
+use crate::schema::WorldManifest;
+use crate::storage::{project_layout, read_project_manifest, read_world_manifest, world_layout};
+use crate::tile_container::world_spec_hash::{
+    hash_region, hash_world_spec_from_manifest, hash_world_spec_legacy,
+};
+use crate::tile_container::{
+    decode_hmap, decode_liqd, decode_prop, decode_wmap, encode_hmap_with, encode_liqd, encode_prop,
+    encode_wmap, hmap_encoding, payload_version, HmapSection, LiqdSection, PropRecord, PropSection,
+    TileContainerReader, TileContainerWriter, TileSectionPayload, TileSectionTag, WmapSection,
+    CONTAINER_VERSION,
+};
+use anyhow::{bail, Context};
+use foundation::ids::{InstanceId, TileCoord};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::tile::parse_tile_filename;
+use super::world::list_project_tiles;
+use super::{codes, MAX_HEIGHT, MIN_HEIGHT, WMAP_WEIGHT_SUM};
+
+/// One change made to a tile.
+#[derive(Debug, Clone, Serialize)]
+pub struct TileRepair {
+    pub path: PathBuf,
+    pub tile: TileCoord,
+    /// Code of the validation issue the repair resolves.
+    pub code: &'static str,
+    pub section: Option<String>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FixReport {
+    pub repairs: Vec<TileRepair>,
+    pub tiles_rewritten: Vec<PathBuf>,
+    /// Tiles that could not be rewritten, with the reason.
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Applies safe repairs to every tile of every world in the project.
+pub fn fix_project(project_root: &Path) -> anyhow::Result<FixReport> {
+    let layout = project_layout(project_root, &read_project_manifest(project_root)?);
+    let mut report = FixReport::default();
+    let mut manifests = HashMap::new();
+    for tile in list_project_tiles(project_root)? {
+        if !manifests.contains_key(&tile.world_id) {
+            let world = world_layout(&layout, &tile.world_id);
+            manifests.insert(
+                tile.world_id.clone(),
+                read_world_manifest(&world.world_root)?,
+            );
+        }
+        match fix_tile(&tile.path, &tile.region_id, &manifests[&tile.world_id]) {
+            Ok(repairs) if repairs.is_empty() => {}
+            Ok(repairs) => {
+                report.repairs.extend(repairs);
+                report.tiles_rewritten.push(tile.path);
+            }
+            Err(err) => report.failed.push((tile.path, format!("{:#}", err))),
+        }
+    }
+    Ok(report)
+}
+
+/// Repairs one tile in place and returns what was changed. Tiles that cannot be read, or whose
+/// problems have no safe repair, are returned unchanged with no repairs.
+pub fn fix_tile(
+    path: &Path,
+    region: &str,
+    manifest: &WorldManifest,
+) -> anyhow::Result<Vec<TileRepair>> {
+    let Some(tile) = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(parse_tile_filename)
+    else {
+        return Ok(Vec::new());
+    };
+    let Ok(reader) = TileContainerReader::open(path) else {
+        return Ok(Vec::new());
+    };
+    if reader.header.container_version > CONTAINER_VERSION {
+        return Ok(Vec::new());
+    }
+
+    let spec = manifest.world_spec;
+    let mut repairs = Vec::new();
+    let mut log = |code: &'static str, tag: Option<TileSectionTag>, description: String| {
+        repairs.push(TileRepair {
+            path: path.to_path_buf(),
+            tile: tile.coord,
+            code,
+            section: tag.map(|tag| tag.to_string()),
+            description,
+        });
+    };
+
+    let mut sections = Vec::with_capacity(reader.directory.len());
+    let mut dims_match = true;
+    for entry in &reader.directory {
+        // A section that does not read back cleanly cannot be carried over safely.
+        let Ok(decoded) = reader.decode_section_cow(entry.tag) else {
+            return Ok(Vec::new());
+        };
+        let tag = entry.tag;
+        let repaired = if tag == TileSectionTag::HMAP {
+            let Ok(mut hmap) = decode_hmap(&decoded) else {
+                return Ok(Vec::new());
+            };
+            dims_match &=
+                hmap.width == spec.heightfield_samples && hmap.height == spec.heightfield_samples;
+            let clamped = clamp_hmap(&mut hmap);
+            if clamped > 0 {
+                log(
+                    codes::HMAP_SAMPLE_INVALID,
+                    Some(tag),
+                    format!("clamped {clamped} samples to {MIN_HEIGHT}..{MAX_HEIGHT} m"),
+                );
+                Some(encode_hmap_with(&hmap, hmap_encoding(&decoded)?)?)
+            } else {
+                None
+            }
+        } else if tag == TileSectionTag::WMAP {
+            let Ok(mut wmap) = decode_wmap(&decoded) else {
+                return Ok(Vec::new());
+            };
+            dims_match &=
+                wmap.width == spec.weightmap_resolution && wmap.height == spec.weightmap_resolution;
+            let cells = normalize_wmap(&mut wmap);
+            if cells > 0 {
+                log(
+                    codes::WMAP_WEIGHTS_UNNORMALIZED,
+                    Some(tag),
+                    format!("renormalized weights of {cells} cells"),
+                );
+                Some(encode_wmap(&wmap))
+            } else {
+                None
+            }
+        } else if tag == TileSectionTag::LIQD {
+            let Ok(mut liqd) = decode_liqd(&decoded) else {
+                return Ok(Vec::new());
+            };
+            dims_match &=
+                liqd.width == spec.liquids_resolution && liqd.height == spec.liquids_resolution;
+            let dropped = drop_unused_bodies(&mut liqd);
+            if dropped.is_empty() {
+                None
+            } else {
+                log(
+                    codes::LIQD_UNUSED_BODY,
+                    Some(tag),
+                    format!("dropped bodies without mask cells: {:?}", dropped),
+                );
+                Some(encode_liqd(&liqd)?)
+            }
+        } else if tag == TileSectionTag::PROP {
+            let Ok(mut prop) = decode_prop(&decoded) else {
+                return Ok(Vec::new());
+            };
+            let changes = dedup_prop_ids(&mut prop)?;
+            if changes.is_empty() {
+                None
+            } else {
+                for change in changes {
+                    log(codes::PROP_DUPLICATE_ID, Some(tag), change);
+                }
+                Some(encode_prop(&prop)?)
+            }
+        } else {
+            None
+        };
+
+        let payload = match repaired {
+            Some(decoded) => TileSectionPayload {
+                tag,
+                section_version: payload_version(&decoded)?,
+                codec: entry.codec,
+                flags: entry.flags,
+                decoded,
+            },
+            None => TileSectionPayload {
+                tag,
+                section_version: entry.section_version,
+                codec: entry.codec,
+                flags: entry.flags,
+                decoded: decoded.into_owned(),
+            },
+        };
+        sections.push(payload);
+    }
+
+    let mut header = reader.header;
+    let region_hash = hash_region(region);
+    if header.region_hash != region_hash {
+        header.region_hash = region_hash;
+        log(
+            codes::REGION_HASH_MISMATCH,
+            None,
+            format!("re-derived region hash for {region:?}"),
+        );
+    }
+    let spec_hash = hash_world_spec_from_manifest(manifest);
+    // A different spec hash is only a stale stamp if the payloads fit the current spec.
+    if header.world_spec_hash != spec_hash
+        && header.world_spec_hash != hash_world_spec_legacy(spec)
+        && dims_match
+    {
+        header.world_spec_hash = spec_hash;
+        log(
+            codes::WORLD_SPEC_HASH_MISMATCH,
+            None,
+            "re-derived world spec hash".to_string(),
+        );
+    }
+    drop(reader);
+
+    if repairs.is_empty() {
+        return Ok(repairs);
+    }
+    header.container_version = CONTAINER_VERSION;
+    let mut writer = TileContainerWriter::new().chunks_per_tile(spec.chunks_per_tile);
+    for payload in sections {
+        writer.add_section(payload);
+    }
+    writer
+        .write(path, header)
+        .with_context(|| format!("write repaired tile {:?}", path))?;
+    Ok(repairs)
+}
+
+/// Clamps finite and infinite samples into the valid height range. NaN has no meaningful
+/// replacement and is left for validation to report.
+fn clamp_hmap(hmap: &mut HmapSection) -> usize {
+    let mut clamped = 0;
+    for sample in &mut hmap.samples {
+        if !sample.is_nan() && (*sample < MIN_HEIGHT || *sample > MAX_HEIGHT) {
+            *sample = sample.clamp(MIN_HEIGHT, MAX_HEIGHT);
+            clamped += 1;
+        }
+    }
+    clamped
+}
+
+/// Scales every painted cell so its weights sum to 255, giving the rounding remainder to the
+/// heaviest layer. Unpainted (all-zero) cells stay as they are.
+fn normalize_wmap(wmap: &mut WmapSection) -> usize {
+    let layers = wmap.layers as usize;
+    if layers == 0 {
+        return 0;
+    }
+    let mut cells = 0;
+    for cell in wmap.weights.chunks_exact_mut(layers) {
+        let sum: u32 = cell.iter().map(|weight| *weight as u32).sum();
+        if sum == 0 || sum == WMAP_WEIGHT_SUM {
+            continue;
+        }
+        // `max_by_key` keeps the last maximum; iterate backwards so ties go to the lowest layer.
+        let heaviest = (0..layers)
+            .rev()
+            .max_by_key(|layer| cell[*layer])
+            .unwrap_or(0);
+        let mut total = 0;
+        for weight in cell.iter_mut() {
+            *weight = (*weight as u32 * WMAP_WEIGHT_SUM / sum) as u8;
+            total += *weight as u32;
+        }
+        cell[heaviest] += (WMAP_WEIGHT_SUM - total) as u8;
+        cells += 1;
+    }
+    cells
+}
+
+/// Drops bodies no mask cell refers to and re-indexes the mask. Returns the dropped body ids.
+/// Masks with out-of-range indices are left alone: which body they meant is unknown.
+fn drop_unused_bodies(liqd: &mut LiqdSection) -> Vec<u32> {
+    let body_count = liqd.bodies.len();
+    if body_count == 0 || liqd.mask.iter().any(|value| *value as usize >= body_count) {
+        return Vec::new();
+    }
+    let mut used = vec![false; body_count];
+    for value in &liqd.mask {
+        used[*value as usize] = true;
+    }
+    if used.iter().all(|used| *used) {
+        return Vec::new();
+    }
+
+    let mut remap = vec![0u8; body_count];
+    let mut next = 0u8;
+    for (index, used) in used.iter().enumerate() {
+        if *used {
+            remap[index] = next;
+            next += 1;
+        }
+    }
+    for value in &mut liqd.mask {
+        *value = remap[*value as usize];
+    }
+    let mut dropped = Vec::new();
+    let mut index = 0;
+    liqd.bodies.retain(|body| {
+        let keep = used[index];
+        index += 1;
+        if !keep {
+            dropped.push(body.id);
+        }
+        keep
+    });
+    dropped
+}
+
+/// Removes exact duplicate records and gives the remaining clashing instances fresh ids above
+/// the tile's current maximum. Returns a description per change; fails when the maximum is
+/// `u64::MAX` and a clash needs a fresh id.
+fn dedup_prop_ids(prop: &mut PropSection) -> anyhow::Result<Vec<String>> {
+    let mut changes = Vec::new();
+    // Next free id, or `None` once the tile's largest id is `u64::MAX`.
+    let mut next_id = prop
+        .instances
+        .iter()
+        .map(|instance| instance.id.0)
+        .max()
+        .map_or(Some(1), |id| id.checked_add(1));
+    let mut seen = std::collections::HashMap::new();
+    let mut kept: Vec<PropRecord> = Vec::new();
+    for mut instance in std::mem::take(&mut prop.instances) {
+        match seen.get(&instance.id.0) {
+            None => {
+                seen.insert(instance.id.0, kept.len());
+            }
+            Some(&first) if kept[first] == instance => {
+                changes.push(format!("dropped duplicate of instance {}", instance.id.0));
+                continue;
+            }
+            Some(_) => {
+                let Some(new_id) = next_id else {
+                    bail!(
+                        "no free instance id left to reassign duplicate instance {}",
+                        instance.id.0
+                    );
+                };
+                next_id = new_id.checked_add(1);
+                changes.push(format!(
+                    "reassigned duplicate instance {} to {}",
+                    instance.id.0, new_id
+                ));
+                instance.id = InstanceId(new_id);
+                seen.insert(new_id, kept.len());
+            }
+        }
+        kept.push(instance);
+    }
+    prop.instances = kept;
+    Ok(changes)
+}
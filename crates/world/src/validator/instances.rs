@@ -0,0 +1,233 @@
+//! World-wide `InstanceId` uniqueness. The per-tile PROP check only sees one tile; this pass
+//! indexes the PROP sections of every tile in every region to find ids shared between tiles (e.g.
+//! after copying props from one tile to another) and can give the copies fresh ids.
+
+use crate::schema::WorldManifest;
+use crate::storage::WorldLayout;
+use crate::tile_container::{
+    compact_tile, decode_current_section, decode_prop, encode_prop, payload_version, PropSection,
+    TileContainerPatch, TileContainerReader, TileSectionPayload, TileSectionTag,
+};
+use anyhow::{bail, Context};
+use foundation::ids::{InstanceId, TileCoord};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use super::seams::region_tile_paths;
+use super::{codes, ValidationIssue};
+
+/// A tile whose PROP section holds an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceLocation {
+    pub region_id: String,
+    pub tile: TileCoord,
+    pub path: PathBuf,
+}
+
+/// An instance id used by more than one tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceCollision {
+    pub id: InstanceId,
+    /// Every tile using the id, in manifest region order and then tile order. The first one keeps
+    /// the id when duplicates are reassigned.
+    pub locations: Vec<InstanceLocation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceReassignment {
+    pub location: InstanceLocation,
+    pub old_id: InstanceId,
+    pub new_id: InstanceId,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InstanceReassignReport {
+    pub reassigned: Vec<InstanceReassignment>,
+    /// Tiles whose PROP section was rewritten.
+    pub tiles_rewritten: Vec<PathBuf>,
+}
+
+/// Lists instance ids shared by more than one tile of the world. Tiles without a readable PROP
+/// section are skipped; the per-tile checks report those.
+pub fn find_instance_collisions(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+) -> anyhow::Result<Vec<InstanceCollision>> {
+    let tiles = world_tiles(layout, manifest)?;
+    Ok(collisions(&tiles, &index_instances(&tiles)))
+}
+
+/// Gives every colliding instance except the first location's a fresh id above the largest id in
+/// the world, and rewrites the affected PROP sections.
+pub fn reassign_duplicate_instances(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+) -> anyhow::Result<InstanceReassignReport> {
+    let tiles = world_tiles(layout, manifest)?;
+    let index = index_instances(&tiles);
+    // Next free id, or `None` once the largest id is `u64::MAX`.
+    let mut next_id = index
+        .keys()
+        .next_back()
+        .map_or(Some(0), |id| id.checked_add(1));
+
+    // Tile index -> (old id, new id) pairs.
+    let mut remaps: BTreeMap<usize, Vec<(u64, u64)>> = BTreeMap::new();
+    let mut report = InstanceReassignReport::default();
+    for (id, owners) in &index {
+        for owner in owners.iter().skip(1) {
+            let Some(new_id) = next_id else {
+                bail!("no free instance id left to reassign duplicate instance {id}");
+            };
+            next_id = new_id.checked_add(1);
+            remaps.entry(*owner).or_default().push((*id, new_id));
+            report.reassigned.push(InstanceReassignment {
+                location: tiles[*owner].clone(),
+                old_id: InstanceId(*id),
+                new_id: InstanceId(new_id),
+            });
+        }
+    }
+
+    for (owner, remap) in remaps {
+        let path = &tiles[owner].path;
+        let mut prop = read_prop(path).with_context(|| format!("PROP of {:?} unreadable", path))?;
+        for instance in &mut prop.instances {
+            if let Some((_, new_id)) = remap.iter().find(|(old, _)| *old == instance.id.0) {
+                instance.id = InstanceId(*new_id);
+            }
+        }
+        write_prop(path, &prop, manifest.world_spec.chunks_per_tile)?;
+        report.tiles_rewritten.push(path.clone());
+    }
+    Ok(report)
+}
+
+/// Reports one issue per tile and shared id, naming the other tiles that use it. `ids[i]` holds
+/// the instance ids of `tiles[i]` (see [`instance_ids`]).
+pub(super) fn validate_world_instances(
+    tiles: &[InstanceLocation],
+    ids: &[BTreeSet<u64>],
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for collision in collisions(tiles, &index_ids(ids)) {
+        for (index, location) in collision.locations.iter().enumerate() {
+            let others: Vec<String> = collision
+                .locations
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, other)| {
+                    format!(
+                        "tile ({}, {}) in {}",
+                        other.tile.x, other.tile.y, other.region_id
+                    )
+                })
+                .collect();
+            issues.push(
+                ValidationIssue::error(
+                    codes::PROP_INSTANCE_COLLISION,
+                    format!(
+                        "PROP instance {} is also used by {}",
+                        collision.id.0,
+                        others.join(", ")
+                    ),
+                )
+                .with_path(location.path.clone())
+                .with_tile(location.tile)
+                .with_section(TileSectionTag::PROP),
+            );
+        }
+    }
+}
+
+fn world_tiles(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+) -> anyhow::Result<Vec<InstanceLocation>> {
+    let mut tiles = Vec::new();
+    for region in &manifest.regions {
+        for (tile, path) in region_tile_paths(layout, &region.region_id)? {
+            tiles.push(InstanceLocation {
+                region_id: region.region_id.clone(),
+                tile,
+                path,
+            });
+        }
+    }
+    Ok(tiles)
+}
+
+/// Maps every instance id to the indices (into `tiles`) of the tiles using it, in order.
+fn index_instances(tiles: &[InstanceLocation]) -> BTreeMap<u64, Vec<usize>> {
+    let ids: Vec<BTreeSet<u64>> = tiles
+        .iter()
+        .map(|tile| {
+            read_prop(&tile.path)
+                .map(|prop| instance_ids(&prop))
+                .unwrap_or_default()
+        })
+        .collect();
+    index_ids(&ids)
+}
+
+fn index_ids(ids: &[BTreeSet<u64>]) -> BTreeMap<u64, Vec<usize>> {
+    let mut index: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+    for (owner, tile_ids) in ids.iter().enumerate() {
+        for id in tile_ids {
+            index.entry(*id).or_default().push(owner);
+        }
+    }
+    index
+}
+
+/// The distinct ids of a PROP section; repeats within one tile are the per-tile check's business.
+pub(super) fn instance_ids(prop: &PropSection) -> BTreeSet<u64> {
+    prop.instances
+        .iter()
+        .map(|instance| instance.id.0)
+        .collect()
+}
+
+fn collisions(
+    tiles: &[InstanceLocation],
+    index: &BTreeMap<u64, Vec<usize>>,
+) -> Vec<InstanceCollision> {
+    index
+        .iter()
+        .filter(|(_, owners)| owners.len() > 1)
+        .map(|(id, owners)| InstanceCollision {
+            id: InstanceId(*id),
+            locations: owners.iter().map(|owner| tiles[*owner].clone()).collect(),
+        })
+        .collect()
+}
+
+pub(super) fn read_prop(path: &Path) -> Option<PropSection> {
+    let reader = TileContainerReader::open(path).ok()?;
+    reader.section(TileSectionTag::PROP)?;
+    let payload = decode_current_section(&reader, TileSectionTag::PROP).ok()?;
+    decode_prop(&payload).ok()
+}
+
+fn write_prop(path: &Path, prop: &PropSection, chunks_per_tile: u16) -> anyhow::Result<()> {
+    let reader = TileContainerReader::open(path)?;
+    let entry = reader
+        .section(TileSectionTag::PROP)
+        .context("PROP section disappeared")?
+        .clone();
+    drop(reader);
+
+    let decoded = encode_prop(prop)?;
+    let mut patch = TileContainerPatch::new().chunks_per_tile(chunks_per_tile);
+    patch.replace_section(TileSectionPayload {
+        tag: TileSectionTag::PROP,
+        section_version: payload_version(&decoded)?,
+        codec: entry.codec,
+        flags: entry.flags,
+        decoded,
+    });
+    patch.apply(path)?;
+    compact_tile(path)?;
+    Ok(())
+}
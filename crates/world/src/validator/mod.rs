@@ -1,38 +1,217 @@
+use crate::assets::{scan_asset_catalog, AssetCatalog};
 use crate::migrations::migrate_project_manifest;
 use crate::schema::PROJECT_FORMAT_VERSION;
-use crate::storage::{project_layout, read_project_manifest, PROJECT_MANIFEST_FILE};
-use serde::Serialize;
+use crate::storage::{
+    project_layout, read_project_manifest, read_world_manifest, world_layout, QuarantineEntry,
+    QuarantineKind, PROJECT_MANIFEST_FILE,
+};
+use crate::tile_container::extensions::AddxRegistry;
+use crate::tile_container::world_spec_hash::{
+    hash_world_spec_from_manifest, hash_world_spec_legacy,
+};
+use crate::tile_container::TileSectionTag;
+use foundation::ids::{TileCoord, TileId};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
+mod assets;
+mod cache;
+pub mod codes;
+mod fix;
+mod instances;
+mod report;
+mod seams;
 mod tile;
 mod world;
 
+pub use fix::{fix_project, fix_tile, FixReport, TileRepair};
+pub use instances::{
+    find_instance_collisions, reassign_duplicate_instances, InstanceCollision, InstanceLocation,
+    InstanceReassignReport, InstanceReassignment,
+};
+pub use report::{junit_report, sarif_report};
+pub use seams::{
+    find_world_seams, repair_world_seams, SeamAxis, SeamMismatch, SeamRepairReport,
+    DEFAULT_SEAM_TOLERANCE,
+};
+pub use world::{list_project_tiles, ProjectTile};
+
+/// Terrain and liquid heights outside this range (in meters) are reported and clamped by fixes.
+const MIN_HEIGHT: f32 = -500.0;
+const MAX_HEIGHT: f32 = 5000.0;
+/// Every painted WMAP cell's layer weights should add up to this.
+const WMAP_WEIGHT_SUM: u32 = 255;
+
+/// Project-wide settings shared by every world/tile check in one validation run.
+struct ValidationContext<'a> {
+    quarantine: bool,
+    extensions: &'a AddxRegistry,
+    liquid_kinds: &'a [String],
+    seam_tolerance: f32,
+    /// Assets PROP records may reference; `None` skips the check (the scan failed).
+    assets: Option<AssetCatalog>,
+    /// Where per-world result caches live; `None` disables caching.
+    cache_dir: Option<PathBuf>,
+    /// Worker threads for tile checks; always at least 1.
+    threads: usize,
+}
+
+/// Knobs for a validation run; `Default` matches `validate_project`.
+#[derive(Debug, Clone, Copy)]
+pub struct ValidationOptions {
+    /// Move tiles with `error` issues to the region's quarantine folder.
+    pub quarantine: bool,
+    /// Largest height difference along a shared HMAP edge that is not reported.
+    pub seam_tolerance: f32,
+    /// Reuse results for tiles unchanged since the last cached run, and record new ones under
+    /// the project's `cache_dir`.
+    pub cache: bool,
+    /// Threads to validate tiles on; `0` uses the available parallelism.
+    pub threads: usize,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            quarantine: false,
+            seam_tolerance: DEFAULT_SEAM_TOLERANCE,
+            cache: false,
+            threads: 0,
+        }
+    }
+}
+
+/// How serious an issue is. Ordered so that `Error` compares greatest, which lets callers filter
+/// with `issue.severity >= threshold`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Worth knowing about; the data loads as-is.
+    Info,
+    /// Suspicious but usable (e.g. unregistered names, stray directories).
+    Warning,
+    /// The data is unreadable or violates the format; affected tiles get quarantined.
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Self> {
+        match value {
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            other => Err(anyhow::anyhow!(
+                "unknown severity {other:?} (expected error, warning or info)"
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ValidationIssue {
+    pub severity: Severity,
+    /// Stable identifier from [`codes`]; safe to match on, unlike `message`.
+    pub code: &'static str,
     pub message: String,
     pub path: Option<PathBuf>,
+    /// Coordinate of the offending tile, for issues found inside a tile container.
+    pub tile: Option<TileCoord>,
+    /// FourCC of the offending section, for issues tied to one section.
+    pub section: Option<String>,
 }
 
 impl ValidationIssue {
-    pub fn new(message: impl Into<String>) -> Self {
+    pub fn new(severity: Severity, code: &'static str, message: impl Into<String>) -> Self {
         Self {
+            severity,
+            code,
             message: message.into(),
             path: None,
+            tile: None,
+            section: None,
         }
     }
 
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, message)
+    }
+
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, code, message)
+    }
+
+    pub fn info(code: &'static str, message: impl Into<String>) -> Self {
+        Self::new(Severity::Info, code, message)
+    }
+
     pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
         self.path = Some(path.into());
         self
     }
+
+    pub fn with_tile(mut self, tile: TileCoord) -> Self {
+        self.tile = Some(tile);
+        self
+    }
+
+    pub fn with_section(mut self, tag: TileSectionTag) -> Self {
+        self.section = Some(tag.to_string());
+        self
+    }
+}
+
+/// Highest severity among `issues`, or `None` when there are none.
+pub fn max_severity(issues: &[ValidationIssue]) -> Option<Severity> {
+    issues.iter().map(|issue| issue.severity).max()
 }
 
 pub fn validate_project(project_root: &Path) -> Vec<ValidationIssue> {
-    validate_project_impl(project_root, false)
+    validate_project_with_options(
+        project_root,
+        &AddxRegistry::default(),
+        ValidationOptions::default(),
+    )
 }
 
 pub fn validate_project_and_quarantine(project_root: &Path) -> Vec<ValidationIssue> {
-    validate_project_impl(project_root, true)
+    let options = ValidationOptions {
+        quarantine: true,
+        ..ValidationOptions::default()
+    };
+    validate_project_with_options(project_root, &AddxRegistry::default(), options)
+}
+
+/// Validates a project, checking ADDX entries whose keys are registered in `extensions`.
+pub fn validate_project_with_extensions(
+    project_root: &Path,
+    extensions: &AddxRegistry,
+    quarantine: bool,
+) -> Vec<ValidationIssue> {
+    let options = ValidationOptions {
+        quarantine,
+        ..ValidationOptions::default()
+    };
+    validate_project_with_options(project_root, extensions, options)
 }
 
 pub fn validate_project_json(project_root: &Path) -> anyhow::Result<String> {
@@ -45,15 +224,22 @@ pub fn validate_project_and_quarantine_json(project_root: &Path) -> anyhow::Resu
     Ok(serde_json::to_string_pretty(&issues)?)
 }
 
-fn validate_project_impl(project_root: &Path, quarantine: bool) -> Vec<ValidationIssue> {
+pub fn validate_project_with_options(
+    project_root: &Path,
+    extensions: &AddxRegistry,
+    options: ValidationOptions,
+) -> Vec<ValidationIssue> {
     let mut issues = Vec::new();
 
     let manifest = match read_project_manifest(project_root) {
         Ok(manifest) => manifest,
         Err(err) => {
             issues.push(
-                ValidationIssue::new(format!("manifest read failed: {err}"))
-                    .with_path(project_root.join(PROJECT_MANIFEST_FILE)),
+                ValidationIssue::error(
+                    codes::MANIFEST_READ_FAILED,
+                    format!("manifest read failed: {err}"),
+                )
+                .with_path(project_root.join(PROJECT_MANIFEST_FILE)),
             );
             return issues;
         }
@@ -61,22 +247,89 @@ fn validate_project_impl(project_root: &Path, quarantine: bool) -> Vec<Validatio
 
     if manifest.format_version > PROJECT_FORMAT_VERSION {
         issues.push(
-            ValidationIssue::new(format!(
-                "manifest format version {} exceeds supported {}",
-                manifest.format_version, PROJECT_FORMAT_VERSION
-            ))
+            ValidationIssue::error(
+                codes::MANIFEST_VERSION_UNSUPPORTED,
+                format!(
+                    "manifest format version {} exceeds supported {}",
+                    manifest.format_version, PROJECT_FORMAT_VERSION
+                ),
+            )
             .with_path(project_root.join(PROJECT_MANIFEST_FILE)),
         );
     }
 
     if let Err(err) = migrate_project_manifest(&mut manifest.clone()) {
-        issues.push(ValidationIssue::new(format!(
-            "manifest migration check failed: {err}"
-        )));
+        issues.push(ValidationIssue::error(
+            codes::MANIFEST_MIGRATION_FAILED,
+            format!("manifest migration check failed: {err}"),
+        ));
     }
 
     let layout = project_layout(project_root, &manifest);
-    world::scan_worlds(&layout, quarantine, &mut issues);
+    let assets_dir_missing = !layout.assets_dir.exists();
+    let assets = match scan_asset_catalog(&layout.assets_dir) {
+        Ok(catalog) => Some(catalog),
+        Err(err) => {
+            issues.push(
+                ValidationIssue::error(
+                    codes::ASSETS_DIR_READ_FAILED,
+                    format!("asset catalog scan failed: {err:#}"),
+                )
+                .with_path(layout.assets_dir.clone()),
+            );
+            None
+        }
+    };
+    let context = ValidationContext {
+        quarantine: options.quarantine,
+        extensions,
+        liquid_kinds: &manifest.liquid_kinds,
+        seam_tolerance: options.seam_tolerance,
+        assets,
+        cache_dir: options.cache.then(|| layout.cache_dir.clone()),
+        threads: match options.threads {
+            0 => std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+            threads => threads,
+        },
+    };
+    world::scan_worlds(&layout, &context, &mut issues);
+    if assets_dir_missing {
+        assets::fold_missing_assets_dir(&layout.assets_dir, &mut issues);
+    }
 
     issues
 }
+
+/// Re-runs the tile checks on a quarantined `.tile`, e.g. to show what is still wrong with it
+/// before restoring it. Legacy tile directories have no container to check and yield no issues.
+pub fn validate_quarantined_tile(
+    project_root: &Path,
+    world_id: &str,
+    entry: &QuarantineEntry,
+    extensions: &AddxRegistry,
+) -> anyhow::Result<Vec<ValidationIssue>> {
+    if entry.kind != QuarantineKind::TileFile {
+        return Ok(Vec::new());
+    }
+    let manifest = read_project_manifest(project_root)?;
+    let world = world_layout(&project_layout(project_root, &manifest), world_id);
+    let world_manifest = read_world_manifest(&world.world_root)?;
+    let context = ValidationContext {
+        quarantine: false,
+        extensions,
+        liquid_kinds: &manifest.liquid_kinds,
+        seam_tolerance: DEFAULT_SEAM_TOLERANCE,
+        assets: None,
+        cache_dir: None,
+        threads: 1,
+    };
+    Ok(tile::tile_issues(
+        &entry.path,
+        TileId { coord: entry.tile },
+        &entry.region_id,
+        hash_world_spec_from_manifest(&world_manifest),
+        hash_world_spec_legacy(world_manifest.world_spec),
+        world_manifest.world_spec,
+        &context,
+    ))
+}
@@ -0,0 +1,298 @@
+//! CI-facing renderings of validation results: SARIF 2.1.0 for code-scanning annotations and
+//! JUnit XML with one test case per tile.
+
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use super::{ProjectTile, Severity, ValidationIssue};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+/// `uriBaseId` that result paths are relative to.
+const PROJECT_ROOT_BASE: &str = "PROJECTROOT";
+
+/// Renders issues as a SARIF log with one result per issue. Paths are made relative to
+/// `project_root`, codes become rule ids, and tile coordinates and section tags are carried as a
+/// logical location and result properties.
+pub fn sarif_report(issues: &[ValidationIssue], project_root: &Path) -> Value {
+    let rules: BTreeSet<&str> = issues.iter().map(|issue| issue.code).collect();
+    let results: Vec<Value> = issues
+        .iter()
+        .map(|issue| {
+            let mut location = json!({});
+            if let Some(path) = &issue.path {
+                let artifact = match path.strip_prefix(project_root) {
+                    Ok(relative) => json!({
+                        "uri": encode_uri_path(&slashed(relative)),
+                        "uriBaseId": PROJECT_ROOT_BASE,
+                    }),
+                    Err(_) => json!({ "uri": file_uri(path) }),
+                };
+                location["physicalLocation"] = json!({ "artifactLocation": artifact });
+            }
+            if let Some(name) = logical_name(issue) {
+                location["logicalLocations"] = json!([{ "name": name, "kind": "object" }]);
+            }
+            let mut result = json!({
+                "ruleId": issue.code,
+                "level": sarif_level(issue.severity),
+                "message": { "text": issue.message },
+                "locations": [location],
+            });
+            if issue.tile.is_some() || issue.section.is_some() {
+                result["properties"] = json!({
+                    "tile": issue.tile,
+                    "section": issue.section,
+                });
+            }
+            result
+        })
+        .collect();
+
+    let mut root_uri = file_uri(project_root);
+    if !root_uri.ends_with('/') {
+        root_uri.push('/');
+    }
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "validate_world",
+                    "rules": rules
+                        .into_iter()
+                        .map(|code| json!({ "id": code }))
+                        .collect::<Vec<_>>(),
+                }
+            },
+            "originalUriBaseIds": {
+                PROJECT_ROOT_BASE: { "uri": root_uri },
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// Renders a JUnit XML report. Every tile in `tiles` is a test case (grouped into one suite per
+/// world/region) so passing tiles are tracked too; issues not tied to a listed tile go to a
+/// `project` case. A case fails when it has an issue at or above `fail_on`; lower-severity issues
+/// are listed in its `system-out`.
+pub fn junit_report(
+    issues: &[ValidationIssue],
+    tiles: &[ProjectTile],
+    project_root: &Path,
+    fail_on: Severity,
+) -> String {
+    let mut suites: Vec<(String, Vec<TestCase>)> = Vec::new();
+    let mut project_case = TestCase {
+        classname: "project".to_string(),
+        name: "project".to_string(),
+        issues: Vec::new(),
+    };
+    let mut tile_issues: Vec<Vec<&ValidationIssue>> = vec![Vec::new(); tiles.len()];
+    for issue in issues {
+        let owner = issue
+            .path
+            .as_ref()
+            .and_then(|path| tiles.iter().position(|tile| &tile.path == path));
+        match owner {
+            Some(index) => tile_issues[index].push(issue),
+            None => project_case.issues.push(issue),
+        }
+    }
+    suites.push(("project".to_string(), vec![project_case]));
+
+    for (tile, issues) in tiles.iter().zip(tile_issues) {
+        let suite = format!("{}/{}", tile.world_id, tile.region_id);
+        let case = TestCase {
+            classname: format!("{}.{}", tile.world_id, tile.region_id),
+            name: tile
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| format!("{}_{}", tile.tile.x, tile.tile.y)),
+            issues,
+        };
+        match suites.last_mut() {
+            Some((name, cases)) if *name == suite => cases.push(case),
+            _ => suites.push((suite, vec![case])),
+        }
+    }
+
+    let failed = |case: &TestCase| case.issues.iter().any(|issue| issue.severity >= fail_on);
+    let total: usize = suites.iter().map(|(_, cases)| cases.len()).sum();
+    let failures: usize = suites
+        .iter()
+        .flat_map(|(_, cases)| cases)
+        .filter(|case| failed(case))
+        .count();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"validate_world\" tests=\"{}\" failures=\"{}\">\n",
+        total, failures
+    ));
+    for (name, cases) in &suites {
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+            xml_escape(name),
+            cases.len(),
+            cases.iter().filter(|case| failed(case)).count()
+        ));
+        for case in cases {
+            out.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                xml_escape(&case.classname),
+                xml_escape(&case.name)
+            ));
+            if case.issues.is_empty() {
+                out.push_str("/>\n");
+                continue;
+            }
+            out.push_str(">\n");
+            let (failing, other): (Vec<&ValidationIssue>, Vec<&ValidationIssue>) = case
+                .issues
+                .iter()
+                .partition(|issue| issue.severity >= fail_on);
+            for issue in failing {
+                out.push_str(&format!(
+                    "      <failure type=\"{}\" message=\"{}\">{}</failure>\n",
+                    issue.code,
+                    xml_escape(&issue.message),
+                    xml_escape(&issue_line(issue, project_root))
+                ));
+            }
+            if !other.is_empty() {
+                let lines: Vec<String> = other
+                    .iter()
+                    .map(|issue| issue_line(issue, project_root))
+                    .collect();
+                out.push_str(&format!(
+                    "      <system-out>{}</system-out>\n",
+                    xml_escape(&lines.join("\n"))
+                ));
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+struct TestCase<'a> {
+    classname: String,
+    name: String,
+    issues: Vec<&'a ValidationIssue>,
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+fn relative_path(path: &Path, project_root: &Path) -> String {
+    slashed(path.strip_prefix(project_root).unwrap_or(path))
+}
+
+fn slashed(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Absolute `file:` URI of `path`. Windows verbatim prefixes (`\\?\`, as `canonicalize` returns)
+/// are dropped, so drive paths become `file:///C:/...` and UNC paths `file://server/share/...`.
+fn file_uri(path: &Path) -> String {
+    let path = slashed(path);
+    let path = if let Some(unc) = path.strip_prefix("//?/UNC/") {
+        format!("//{unc}")
+    } else if let Some(local) = path.strip_prefix("//?/") {
+        local.to_string()
+    } else {
+        path
+    };
+    let encoded = encode_uri_path(&path);
+    if path.starts_with("//") {
+        format!("file:{encoded}")
+    } else if path.starts_with('/') {
+        format!("file://{encoded}")
+    } else {
+        format!("file:///{encoded}")
+    }
+}
+
+/// Percent-encodes everything in a `/`-separated path except unreserved characters, `/` and `:`.
+fn encode_uri_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                out.push(byte as char)
+            }
+            byte => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+fn logical_name(issue: &ValidationIssue) -> Option<String> {
+    match (issue.tile, &issue.section) {
+        (Some(tile), Some(section)) => Some(format!("({}, {})/{}", tile.x, tile.y, section)),
+        (Some(tile), None) => Some(format!("({}, {})", tile.x, tile.y)),
+        (None, Some(section)) => Some(section.clone()),
+        (None, None) => None,
+    }
+}
+
+fn issue_line(issue: &ValidationIssue, project_root: &Path) -> String {
+    let mut line = format!("{}[{}] ", issue.severity, issue.code);
+    if let Some(path) = &issue.path {
+        line.push_str(&format!("{}: ", relative_path(path, project_root)));
+    }
+    if let Some(section) = &issue.section {
+        line.push_str(&format!("{} ", section));
+    }
+    line.push_str(&issue.message);
+    line
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            ch if ch.is_control() && ch != '\n' && ch != '\t' => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_uris_drop_verbatim_prefixes_and_percent_encode() {
+        assert_eq!(
+            file_uri(Path::new("/home/me/my project#1")),
+            "file:///home/me/my%20project%231"
+        );
+        assert_eq!(
+            file_uri(Path::new(r"\\?\C:\Users\me\Welt ü")),
+            "file:///C:/Users/me/Welt%20%C3%BC"
+        );
+        assert_eq!(
+            file_uri(Path::new(r"\\?\UNC\server\share\proj")),
+            "file://server/share/proj"
+        );
+        assert_eq!(file_uri(Path::new(r"C:\proj")), "file:///C:/proj");
+    }
+}
@@ -0,0 +1,458 @@
+//! Cross-tile heightfield seam checks.
+//!
+//! Neighbouring tiles share their edge samples: the last column of tile `(x, y)` is the first
+//! column of `(x + 1, y)`, and its last row is the first row of `(x, y + 1)` (samples are stored
+//! row-major). Any difference along a shared edge renders as a crack between the two tiles.
+
+use crate::schema::WorldManifest;
+use crate::storage::{region_tiles_dir, WorldLayout};
+use crate::tile_container::{
+    compact_tile, decode_current_section, decode_hmap, encode_hmap_with, hmap_encoding,
+    hmap_quantization_error, payload_version, HmapEncoding, HmapSection, TileContainerPatch,
+    TileContainerReader, TileSectionPayload, TileSectionTag,
+};
+use anyhow::Context;
+use foundation::ids::TileCoord;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use super::tile::parse_tile_filename;
+use super::{codes, ValidationIssue};
+
+/// Largest height difference (in meters) along a shared edge that is not reported. Quantized
+/// HMAP encodings round each tile independently; each seam's tolerance is widened by both tiles'
+/// rounding error (half their quantization step) on top of this.
+pub const DEFAULT_SEAM_TOLERANCE: f32 = 0.01;
+
+/// Which edge two tiles share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeamAxis {
+    /// `tile` and `neighbor` are horizontal neighbours (`neighbor.x == tile.x + 1`); the edge
+    /// index is a row.
+    X,
+    /// `tile` and `neighbor` are vertical neighbours (`neighbor.y == tile.y + 1`); the edge
+    /// index is a column.
+    Y,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeamMismatch {
+    pub region_id: String,
+    pub tile: TileCoord,
+    pub neighbor: TileCoord,
+    pub axis: SeamAxis,
+    /// Largest absolute difference between the two edges, in meters.
+    pub max_delta: f32,
+    /// Row (for `X`) or column (for `Y`) where `max_delta` occurs.
+    pub max_index: u16,
+    /// Number of edge samples whose difference exceeds the tolerance.
+    pub samples_over: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SeamRepairReport {
+    /// Seams that were averaged, as found before the repair.
+    pub repaired: Vec<SeamMismatch>,
+    /// Tiles whose HMAP section was rewritten.
+    pub tiles_rewritten: Vec<PathBuf>,
+}
+
+/// Edge samples of one tile; enough to compare it with its neighbours.
+struct TileEdges {
+    width: u16,
+    height: u16,
+    /// Largest error the tile's HMAP encoding adds to a sample.
+    error: f32,
+    left: Vec<f32>,
+    right: Vec<f32>,
+    top: Vec<f32>,
+    bottom: Vec<f32>,
+}
+
+impl TileEdges {
+    fn new(hmap: &HmapSection, error: f32) -> Self {
+        let width = hmap.width as usize;
+        let height = hmap.height as usize;
+        let column = |x: usize| (0..height).map(|y| hmap.samples[y * width + x]).collect();
+        let row = |y: usize| hmap.samples[y * width..(y + 1) * width].to_vec();
+        Self {
+            width: hmap.width,
+            height: hmap.height,
+            error,
+            left: column(0),
+            right: column(width - 1),
+            top: row(0),
+            bottom: row(height - 1),
+        }
+    }
+}
+
+/// Reports every seam in the world whose edges differ by more than `tolerance`. Tiles without a
+/// readable, well-formed HMAP are skipped; the per-tile checks report those.
+pub fn find_world_seams(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+    tolerance: f32,
+) -> anyhow::Result<Vec<SeamMismatch>> {
+    let mut seams = Vec::new();
+    for region in &manifest.regions {
+        let tiles = region_tile_paths(layout, &region.region_id)?;
+        seams.extend(find_region_seams(&tiles, &region.region_id, tolerance));
+    }
+    Ok(seams)
+}
+
+/// Makes every mismatched seam in the world continuous by setting both edges to their average.
+/// Corner samples are averaged across all tiles that share the corner, so fixing one seam never
+/// opens another.
+pub fn repair_world_seams(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+    tolerance: f32,
+) -> anyhow::Result<SeamRepairReport> {
+    let mut report = SeamRepairReport::default();
+    for region in &manifest.regions {
+        let tiles = region_tile_paths(layout, &region.region_id)?;
+        let seams = find_region_seams(&tiles, &region.region_id, tolerance);
+        if seams.is_empty() {
+            continue;
+        }
+        let rewritten = repair_region_seams(&tiles, &seams, manifest.world_spec.chunks_per_tile)?;
+        report.tiles_rewritten.extend(rewritten);
+        report.repaired.extend(seams);
+    }
+    Ok(report)
+}
+
+pub(super) fn validate_region_seams(
+    layout: &WorldLayout,
+    region_id: &str,
+    tolerance: f32,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    // An unreadable tiles directory is already reported by the tile scan.
+    let Ok(tiles) = region_tile_paths(layout, region_id) else {
+        return;
+    };
+    for seam in find_region_seams(&tiles, region_id, tolerance) {
+        let location = match seam.axis {
+            SeamAxis::X => "row",
+            SeamAxis::Y => "column",
+        };
+        let mut issue = ValidationIssue::warning(
+            codes::HMAP_SEAM_MISMATCH,
+            format!(
+                "HMAP seam with tile ({}, {}) differs by up to {:.3} m at {} {} ({} samples above tolerance)",
+                seam.neighbor.x,
+                seam.neighbor.y,
+                seam.max_delta,
+                location,
+                seam.max_index,
+                seam.samples_over
+            ),
+        )
+        .with_tile(seam.tile)
+        .with_section(TileSectionTag::HMAP);
+        if let Some(path) = tiles.get(&seam.tile) {
+            issue = issue.with_path(path.clone());
+        }
+        issues.push(issue);
+    }
+}
+
+fn find_region_seams(
+    tiles: &BTreeMap<TileCoord, PathBuf>,
+    region_id: &str,
+    tolerance: f32,
+) -> Vec<SeamMismatch> {
+    let mut edges = BTreeMap::new();
+    for (&coord, path) in tiles {
+        if let Some(stored) = read_hmap(path) {
+            edges.insert(coord, TileEdges::new(&stored.hmap, stored.error));
+        }
+    }
+
+    let mut seams = Vec::new();
+    for (&coord, tile) in &edges {
+        let neighbors = [
+            (
+                SeamAxis::X,
+                TileCoord {
+                    x: coord.x + 1,
+                    y: coord.y,
+                },
+            ),
+            (
+                SeamAxis::Y,
+                TileCoord {
+                    x: coord.x,
+                    y: coord.y + 1,
+                },
+            ),
+        ];
+        for (axis, neighbor_coord) in neighbors {
+            let Some(neighbor) = edges.get(&neighbor_coord) else {
+                continue;
+            };
+            if neighbor.width != tile.width || neighbor.height != tile.height {
+                continue;
+            }
+            let (ours, theirs) = match axis {
+                SeamAxis::X => (&tile.right, &neighbor.left),
+                SeamAxis::Y => (&tile.bottom, &neighbor.top),
+            };
+            let tolerance = tolerance + tile.error + neighbor.error;
+            if let Some((max_delta, max_index, samples_over)) =
+                compare_edges(ours, theirs, tolerance)
+            {
+                seams.push(SeamMismatch {
+                    region_id: region_id.to_string(),
+                    tile: coord,
+                    neighbor: neighbor_coord,
+                    axis,
+                    max_delta,
+                    max_index,
+                    samples_over,
+                });
+            }
+        }
+    }
+    seams
+}
+
+/// Returns `(max_delta, max_index, samples_over)` if any sample differs by more than `tolerance`.
+fn compare_edges(a: &[f32], b: &[f32], tolerance: f32) -> Option<(f32, u16, usize)> {
+    let mut max_delta = 0.0f32;
+    let mut max_index = 0usize;
+    let mut samples_over = 0usize;
+    for (index, (a, b)) in a.iter().zip(b).enumerate() {
+        let delta = (a - b).abs();
+        // NaN never compares greater, so count it explicitly.
+        if delta > tolerance || delta.is_nan() {
+            samples_over += 1;
+        }
+        if delta > max_delta || (delta.is_nan() && !max_delta.is_nan()) {
+            max_delta = delta;
+            max_index = index;
+        }
+    }
+    if samples_over == 0 {
+        return None;
+    }
+    Some((max_delta, max_index as u16, samples_over))
+}
+
+fn repair_region_seams(
+    tiles: &BTreeMap<TileCoord, PathBuf>,
+    seams: &[SeamMismatch],
+    chunks_per_tile: u16,
+) -> anyhow::Result<Vec<PathBuf>> {
+    // Corners of repaired seams, in tile-grid vertex coordinates.
+    let mut corners = BTreeSet::new();
+    for seam in seams {
+        let first = TileCoord {
+            x: seam.tile.x + 1,
+            y: seam.tile.y + 1,
+        };
+        let second = match seam.axis {
+            SeamAxis::X => TileCoord {
+                x: seam.tile.x + 1,
+                y: seam.tile.y,
+            },
+            SeamAxis::Y => TileCoord {
+                x: seam.tile.x,
+                y: seam.tile.y + 1,
+            },
+        };
+        corners.insert(first);
+        corners.insert(second);
+    }
+
+    let mut needed = BTreeSet::new();
+    for seam in seams {
+        needed.insert(seam.tile);
+        needed.insert(seam.neighbor);
+    }
+    for corner in &corners {
+        needed.extend(corner_tiles(*corner));
+    }
+    let mut loaded = BTreeMap::new();
+    for coord in needed {
+        let Some(path) = tiles.get(&coord) else {
+            continue;
+        };
+        if let Some(stored) = read_hmap(path) {
+            loaded.insert(coord, stored);
+        }
+    }
+    let stored_samples: BTreeMap<TileCoord, Vec<f32>> = loaded
+        .iter()
+        .map(|(coord, stored)| (*coord, stored.hmap.samples.clone()))
+        .collect();
+
+    let mut changed = BTreeSet::new();
+    for seam in seams {
+        let (Some(StoredHmap { hmap: a, .. }), Some(StoredHmap { hmap: b, .. })) =
+            (loaded.get(&seam.tile), loaded.get(&seam.neighbor))
+        else {
+            continue;
+        };
+        let (width, height) = (a.width as usize, a.height as usize);
+        let pairs: Vec<(usize, usize)> = match seam.axis {
+            SeamAxis::X => (1..height - 1)
+                .map(|y| (y * width + width - 1, y * width))
+                .collect(),
+            SeamAxis::Y => (1..width - 1)
+                .map(|x| ((height - 1) * width + x, x))
+                .collect(),
+        };
+        let averaged: Vec<f32> = pairs
+            .iter()
+            .map(|&(ia, ib)| (a.samples[ia] + b.samples[ib]) * 0.5)
+            .collect();
+        for (&(ia, ib), value) in pairs.iter().zip(averaged) {
+            loaded.get_mut(&seam.tile).unwrap().hmap.samples[ia] = value;
+            loaded.get_mut(&seam.neighbor).unwrap().hmap.samples[ib] = value;
+        }
+        changed.insert(seam.tile);
+        changed.insert(seam.neighbor);
+    }
+
+    for corner in corners {
+        let mut samples = Vec::new();
+        for coord in corner_tiles(corner) {
+            if let Some(stored) = loaded.get(&coord) {
+                samples.push((coord, corner_index(&stored.hmap, coord, corner)));
+            }
+        }
+        if samples.len() < 2 {
+            continue;
+        }
+        let mean = samples
+            .iter()
+            .map(|(coord, index)| loaded[coord].hmap.samples[*index])
+            .sum::<f32>()
+            / samples.len() as f32;
+        for (coord, index) in samples {
+            let sample = &mut loaded.get_mut(&coord).unwrap().hmap.samples[index];
+            if *sample != mean {
+                *sample = mean;
+                changed.insert(coord);
+            }
+        }
+    }
+
+    let mut rewritten = Vec::new();
+    for coord in changed {
+        let stored = &loaded[&coord];
+        let path = &tiles[&coord];
+        let decoded = encode_hmap_with(&stored.hmap, stored.encoding)
+            .with_context(|| format!("encode repaired HMAP for {:?}", path))?;
+        // A quantized tile can round the averaged edge straight back to what it stores; writing
+        // it again would change nothing but leave another backup behind.
+        if decode_hmap(&decoded)?.samples == stored_samples[&coord] {
+            continue;
+        }
+        write_hmap(path, decoded, chunks_per_tile)?;
+        rewritten.push(path.clone());
+    }
+    Ok(rewritten)
+}
+
+/// Tiles that share the grid vertex `corner` (the top-left corner of tile `corner`).
+fn corner_tiles(corner: TileCoord) -> [TileCoord; 4] {
+    [
+        TileCoord {
+            x: corner.x - 1,
+            y: corner.y - 1,
+        },
+        TileCoord {
+            x: corner.x,
+            y: corner.y - 1,
+        },
+        TileCoord {
+            x: corner.x - 1,
+            y: corner.y,
+        },
+        corner,
+    ]
+}
+
+fn corner_index(hmap: &HmapSection, tile: TileCoord, corner: TileCoord) -> usize {
+    let width = hmap.width as usize;
+    let x = (corner.x - tile.x) as usize * (width - 1);
+    let y = (corner.y - tile.y) as usize * (hmap.height as usize - 1);
+    y * width + x
+}
+
+pub(super) fn region_tile_paths(
+    layout: &WorldLayout,
+    region_id: &str,
+) -> anyhow::Result<BTreeMap<TileCoord, PathBuf>> {
+    let dir = region_tiles_dir(layout, region_id);
+    let mut tiles = BTreeMap::new();
+    if !dir.exists() {
+        return Ok(tiles);
+    }
+    for entry in std::fs::read_dir(&dir).with_context(|| format!("read tiles dir {:?}", dir))? {
+        let path = entry?.path();
+        let Some(tile_id) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_tile_filename)
+        else {
+            continue;
+        };
+        tiles.insert(tile_id.coord, path);
+    }
+    Ok(tiles)
+}
+
+/// A tile's decoded HMAP with how it is stored.
+struct StoredHmap {
+    hmap: HmapSection,
+    encoding: HmapEncoding,
+    /// Largest error the encoding adds to a sample.
+    error: f32,
+}
+
+/// Reads a tile's HMAP if it decodes and has at least a 2x2 grid.
+fn read_hmap(path: &Path) -> Option<StoredHmap> {
+    let reader = TileContainerReader::open(path).ok()?;
+    reader.section(TileSectionTag::HMAP)?;
+    let payload = decode_current_section(&reader, TileSectionTag::HMAP).ok()?;
+    let hmap = decode_hmap(&payload).ok()?;
+    let encoding = hmap_encoding(&payload).ok()?;
+    let error = hmap_quantization_error(&payload).ok()?;
+    if hmap.width < 2 || hmap.height < 2 {
+        return None;
+    }
+    Some(StoredHmap {
+        hmap,
+        encoding,
+        error,
+    })
+}
+
+/// Replaces a tile's HMAP payload with `decoded`, an encoded HMAP section.
+fn write_hmap(path: &Path, decoded: Vec<u8>, chunks_per_tile: u16) -> anyhow::Result<()> {
+    let reader = TileContainerReader::open(path)?;
+    let entry = reader
+        .section(TileSectionTag::HMAP)
+        .context("HMAP section disappeared")?
+        .clone();
+    drop(reader);
+
+    let section_version = payload_version(&decoded)?;
+    let mut patch = TileContainerPatch::new().chunks_per_tile(chunks_per_tile);
+    patch.replace_section(TileSectionPayload {
+        tag: TileSectionTag::HMAP,
+        section_version,
+        codec: entry.codec,
+        flags: entry.flags,
+        decoded,
+    });
+    patch.apply(path)?;
+    compact_tile(path)?;
+    Ok(())
+}
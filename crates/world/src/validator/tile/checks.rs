@@ -1,7 +1,10 @@
 use crate::schema::WorldSpec;
+use crate::tile_container::extensions::{AddxEntryStatus, AddxRegistry};
+use crate::tile_container::is_valid_addx_key;
 use std::path::Path;
 
-use super::ValidationIssue;
+use super::{codes, ValidationIssue};
+use crate::validator::{MAX_HEIGHT, MIN_HEIGHT, WMAP_WEIGHT_SUM};
 
 pub(super) fn validate_hmap(
     hmap: &crate::tile_container::HmapSection,
@@ -13,14 +16,18 @@ pub(super) fn validate_hmap(
         || hmap.height != expected_spec.heightfield_samples
     {
         issues.push(
-            ValidationIssue::new("HMAP dimensions do not match world spec")
-                .with_path(tile_path.to_path_buf()),
+            ValidationIssue::error(
+                codes::HMAP_DIM_MISMATCH,
+                "HMAP dimensions do not match world spec",
+            )
+            .with_path(tile_path.to_path_buf()),
         );
     }
     for sample in &hmap.samples {
-        if !sample.is_finite() || *sample < -500.0 || *sample > 5000.0 {
+        if !sample.is_finite() || *sample < MIN_HEIGHT || *sample > MAX_HEIGHT {
             issues.push(
-                ValidationIssue::new("HMAP sample out of range").with_path(tile_path.to_path_buf()),
+                ValidationIssue::error(codes::HMAP_SAMPLE_INVALID, "HMAP sample out of range")
+                    .with_path(tile_path.to_path_buf()),
             );
             break;
         }
@@ -37,15 +44,39 @@ pub(super) fn validate_wmap(
         || wmap.height != expected_spec.weightmap_resolution
     {
         issues.push(
-            ValidationIssue::new("WMAP dimensions do not match world spec")
-                .with_path(tile_path.to_path_buf()),
+            ValidationIssue::error(
+                codes::WMAP_DIM_MISMATCH,
+                "WMAP dimensions do not match world spec",
+            )
+            .with_path(tile_path.to_path_buf()),
         );
     }
+    let layers = wmap.layers as usize;
+    if layers > 0 {
+        let unnormalized = wmap
+            .weights
+            .chunks_exact(layers)
+            .filter(|cell| {
+                let sum: u32 = cell.iter().map(|weight| *weight as u32).sum();
+                sum != 0 && sum != WMAP_WEIGHT_SUM
+            })
+            .count();
+        if unnormalized > 0 {
+            issues.push(
+                ValidationIssue::warning(
+                    codes::WMAP_WEIGHTS_UNNORMALIZED,
+                    format!("WMAP has {unnormalized} cells whose weights do not sum to 255"),
+                )
+                .with_path(tile_path.to_path_buf()),
+            );
+        }
+    }
 }
 
 pub(super) fn validate_liqd(
     liqd: &crate::tile_container::LiqdSection,
     expected_spec: WorldSpec,
+    liquid_kinds: &[String],
     tile_path: &Path,
     issues: &mut Vec<ValidationIssue>,
 ) {
@@ -53,8 +84,11 @@ pub(super) fn validate_liqd(
         || liqd.height != expected_spec.liquids_resolution
     {
         issues.push(
-            ValidationIssue::new("LIQD dimensions do not match world spec")
-                .with_path(tile_path.to_path_buf()),
+            ValidationIssue::error(
+                codes::LIQD_DIM_MISMATCH,
+                "LIQD dimensions do not match world spec",
+            )
+            .with_path(tile_path.to_path_buf()),
         );
     }
     let body_count = liqd.bodies.len() as u8;
@@ -62,17 +96,47 @@ pub(super) fn validate_liqd(
         for value in &liqd.mask {
             if *value >= body_count {
                 issues.push(
-                    ValidationIssue::new("LIQD mask references unknown body")
-                        .with_path(tile_path.to_path_buf()),
+                    ValidationIssue::error(
+                        codes::LIQD_UNKNOWN_BODY,
+                        "LIQD mask references unknown body",
+                    )
+                    .with_path(tile_path.to_path_buf()),
                 );
                 break;
             }
         }
     }
+    for (index, body) in liqd.bodies.iter().enumerate() {
+        if !liqd.mask.iter().any(|value| *value as usize == index) {
+            issues.push(
+                ValidationIssue::info(
+                    codes::LIQD_UNUSED_BODY,
+                    format!("LIQD body {} has no mask cells", body.id),
+                )
+                .with_path(tile_path.to_path_buf()),
+            );
+        }
+    }
+    for body in &liqd.bodies {
+        if let crate::tile_container::LiqdKind::Custom(name) = &body.kind {
+            if !liquid_kinds.iter().any(|kind| kind == name) {
+                issues.push(
+                    ValidationIssue::warning(
+                        codes::LIQD_UNREGISTERED_KIND,
+                        format!(
+                            "LIQD body {} uses unregistered liquid kind {:?}",
+                            body.id, name
+                        ),
+                    )
+                    .with_path(tile_path.to_path_buf()),
+                );
+            }
+        }
+    }
     for body in &liqd.bodies {
-        if !body.height.is_finite() || body.height < -500.0 || body.height > 5000.0 {
+        if !body.height.is_finite() || body.height < MIN_HEIGHT || body.height > MAX_HEIGHT {
             issues.push(
-                ValidationIssue::new("LIQD body height out of range")
+                ValidationIssue::error(codes::LIQD_HEIGHT_RANGE, "LIQD body height out of range")
                     .with_path(tile_path.to_path_buf()),
             );
             break;
@@ -85,6 +149,22 @@ pub(super) fn validate_prop(
     tile_path: &Path,
     issues: &mut Vec<ValidationIssue>,
 ) {
+    let mut ids = std::collections::HashSet::new();
+    let mut duplicates = std::collections::BTreeSet::new();
+    for instance in &prop.instances {
+        if !ids.insert(instance.id.0) {
+            duplicates.insert(instance.id.0);
+        }
+    }
+    for id in duplicates {
+        issues.push(
+            ValidationIssue::error(
+                codes::PROP_DUPLICATE_ID,
+                format!("PROP duplicate instance id {id}"),
+            )
+            .with_path(tile_path.to_path_buf()),
+        );
+    }
     for instance in &prop.instances {
         for value in instance
             .translation
@@ -94,11 +174,111 @@ pub(super) fn validate_prop(
         {
             if !value.is_finite() {
                 issues.push(
-                    ValidationIssue::new("PROP transform contains NaN/inf")
-                        .with_path(tile_path.to_path_buf()),
+                    ValidationIssue::error(
+                        codes::PROP_TRANSFORM_INVALID,
+                        "PROP transform contains NaN/inf",
+                    )
+                    .with_path(tile_path.to_path_buf()),
                 );
                 return;
             }
         }
     }
 }
+
+pub(super) fn validate_spln(
+    spln: &crate::tile_container::SplnSection,
+    tile_path: &Path,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let mut ids = std::collections::HashSet::new();
+    for spline in &spln.splines {
+        if !ids.insert(spline.id) {
+            issues.push(
+                ValidationIssue::error(
+                    codes::SPLN_DUPLICATE_ID,
+                    format!("SPLN duplicate spline id {}", spline.id),
+                )
+                .with_path(tile_path.to_path_buf()),
+            );
+        }
+        if spline.points.len() < 2 {
+            issues.push(
+                ValidationIssue::error(
+                    codes::SPLN_TOO_FEW_POINTS,
+                    format!("SPLN spline {} has fewer than 2 control points", spline.id),
+                )
+                .with_path(tile_path.to_path_buf()),
+            );
+        }
+        for point in &spline.points {
+            let finite = point
+                .position
+                .iter()
+                .chain(point.tangent_in.iter())
+                .chain(point.tangent_out.iter())
+                .chain(std::iter::once(&point.width))
+                .all(|value| value.is_finite());
+            if !finite {
+                issues.push(
+                    ValidationIssue::error(
+                        codes::SPLN_POINT_INVALID,
+                        format!("SPLN spline {} contains NaN/inf", spline.id),
+                    )
+                    .with_path(tile_path.to_path_buf()),
+                );
+                break;
+            }
+            if point.width < 0.0 {
+                issues.push(
+                    ValidationIssue::error(
+                        codes::SPLN_POINT_INVALID,
+                        format!("SPLN spline {} has negative width", spline.id),
+                    )
+                    .with_path(tile_path.to_path_buf()),
+                );
+                break;
+            }
+        }
+    }
+}
+
+pub(super) fn validate_addx(
+    addx: &crate::tile_container::AddxSection,
+    extensions: &AddxRegistry,
+    tile_path: &Path,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let mut keys = std::collections::HashSet::new();
+    for entry in &addx.entries {
+        if !is_valid_addx_key(&entry.key) {
+            issues.push(
+                ValidationIssue::error(
+                    codes::ADDX_KEY_NOT_NAMESPACED,
+                    format!("ADDX key {:?} is not namespaced", entry.key),
+                )
+                .with_path(tile_path.to_path_buf()),
+            );
+            continue;
+        }
+        if !keys.insert(entry.key.as_str()) {
+            issues.push(
+                ValidationIssue::error(
+                    codes::ADDX_DUPLICATE_KEY,
+                    format!("ADDX duplicate key {}", entry.key),
+                )
+                .with_path(tile_path.to_path_buf()),
+            );
+            continue;
+        }
+        if let AddxEntryStatus::Invalid(reason) = extensions.check_entry(entry) {
+            issues.push(
+                ValidationIssue::error(
+                    codes::ADDX_ENTRY_INVALID,
+                    format!("ADDX entry {} invalid: {reason}", entry.key),
+                )
+                .with_path(tile_path.to_path_buf()),
+            );
+        }
+    }
+}
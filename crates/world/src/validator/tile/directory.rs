@@ -1,48 +1,79 @@
 use crate::tile_container::{
-    TileContainerReader, TileSectionTag, DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE,
+    TileContainerReader, TileSectionTag, DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE, HEADER_SIZE,
 };
 use std::path::Path;
 
-use super::ValidationIssue;
+use super::{codes, ValidationIssue};
 
 pub(super) fn validate_directory(
     reader: &TileContainerReader,
     tile_path: &Path,
     issues: &mut Vec<ValidationIssue>,
 ) {
-    let dir_end = reader.header.section_dir_offset
-        + reader.header.section_count as u64 * DIR_ENTRY_SIZE as u64;
+    // Patched tiles keep their directory after the payloads, so check for a real overlap.
+    let dir_start = reader.header.section_dir_offset;
+    let dir_end = dir_start + reader.header.section_count as u64 * DIR_ENTRY_SIZE as u64;
     let mut ranges = Vec::new();
     for entry in &reader.directory {
         if !is_ascii_tag(entry.tag) {
             issues.push(
-                ValidationIssue::new(format!("section tag {} is not ASCII FourCC", entry.tag))
-                    .with_path(tile_path.to_path_buf()),
+                ValidationIssue::error(
+                    codes::SECTION_TAG_INVALID,
+                    format!("section tag {} is not ASCII FourCC", entry.tag),
+                )
+                .with_path(tile_path.to_path_buf())
+                .with_section(entry.tag),
             );
         }
-        if entry.offset < dir_end {
+        let end = entry.offset.saturating_add(entry.stored_len);
+        if entry.offset < HEADER_SIZE as u64 {
             issues.push(
-                ValidationIssue::new(format!("section {} overlaps directory region", entry.tag))
-                    .with_path(tile_path.to_path_buf()),
+                ValidationIssue::error(
+                    codes::SECTION_OVERLAP,
+                    format!("section {} overlaps header", entry.tag),
+                )
+                .with_path(tile_path.to_path_buf())
+                .with_section(entry.tag),
+            );
+        }
+        if entry.offset < dir_end && end > dir_start {
+            issues.push(
+                ValidationIssue::error(
+                    codes::SECTION_OVERLAP,
+                    format!("section {} overlaps directory region", entry.tag),
+                )
+                .with_path(tile_path.to_path_buf())
+                .with_section(entry.tag),
             );
         }
         if entry.stored_len == 0 {
             issues.push(
-                ValidationIssue::new(format!("section {} has zero length", entry.tag))
-                    .with_path(tile_path.to_path_buf()),
+                ValidationIssue::error(
+                    codes::SECTION_RANGE_INVALID,
+                    format!("section {} has zero length", entry.tag),
+                )
+                .with_path(tile_path.to_path_buf())
+                .with_section(entry.tag),
             );
         }
-        let end = entry.offset.saturating_add(entry.stored_len);
         if end > reader.file_len {
             issues.push(
-                ValidationIssue::new(format!("section {} out of bounds", entry.tag))
-                    .with_path(tile_path.to_path_buf()),
+                ValidationIssue::error(
+                    codes::SECTION_RANGE_INVALID,
+                    format!("section {} out of bounds", entry.tag),
+                )
+                .with_path(tile_path.to_path_buf())
+                .with_section(entry.tag),
             );
         }
         if entry.offset % DEFAULT_ALIGNMENT != 0 {
             issues.push(
-                ValidationIssue::new(format!("section {} not aligned", entry.tag))
-                    .with_path(tile_path.to_path_buf()),
+                ValidationIssue::error(
+                    codes::SECTION_MISALIGNED,
+                    format!("section {} not aligned", entry.tag),
+                )
+                .with_path(tile_path.to_path_buf())
+                .with_section(entry.tag),
             );
         }
         ranges.push((entry.offset, end, entry.tag));
@@ -54,8 +85,12 @@ pub(super) fn validate_directory(
         let b = &window[1];
         if b.0 < a.1 {
             issues.push(
-                ValidationIssue::new(format!("section overlap: {} overlaps {}", a.2, b.2))
-                    .with_path(tile_path.to_path_buf()),
+                ValidationIssue::error(
+                    codes::SECTION_OVERLAP,
+                    format!("section overlap: {} overlaps {}", a.2, b.2),
+                )
+                .with_path(tile_path.to_path_buf())
+                .with_section(b.2),
             );
         }
     }
@@ -1,125 +1,173 @@
 use crate::schema::WorldSpec;
-use crate::storage::{quarantine_tile_file, WorldLayout};
+use crate::storage::{quarantine_tile_file_with_details, WorldLayout};
 use crate::tile_container::world_spec_hash::hash_region;
 use crate::tile_container::{
     TileContainerReader, CONTAINER_VERSION, HEADER_SIZE, MAX_SECTION_COUNT, MIN_CONTAINER_VERSION,
 };
-use foundation::ids::{TileCoord, TileId};
+use foundation::ids::TileId;
 use std::path::Path;
 
-use super::ValidationIssue;
+use super::{codes, Severity, ValidationContext, ValidationIssue};
 
 mod checks;
 mod directory;
 mod sections;
 
-pub(super) fn parse_tile_filename(name: &str) -> Option<TileId> {
-    let stem = name.strip_suffix(".tile")?;
-    let mut parts = stem.split('_');
-    let x_part = parts.next()?;
-    let y_part = parts.next()?;
-    if !x_part.starts_with('x') || !y_part.starts_with('y') {
-        return None;
+pub(super) use crate::storage::parse_tile_container_filename as parse_tile_filename;
+
+/// Runs every check on one tile container and tags the issues with the tile's coordinate.
+pub(super) fn tile_issues(
+    tile_path: &Path,
+    tile_id: TileId,
+    region: &str,
+    expected_spec_hash: u64,
+    legacy_spec_hash: u64,
+    expected_spec: WorldSpec,
+    context: &ValidationContext,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    check_tile_container(
+        tile_path,
+        tile_id,
+        region,
+        expected_spec_hash,
+        legacy_spec_hash,
+        expected_spec,
+        context,
+        &mut issues,
+    );
+    for issue in &mut issues {
+        issue.tile = Some(tile_id.coord);
     }
-    let x = x_part[1..].parse::<i32>().ok()?;
-    let y = y_part[1..].parse::<i32>().ok()?;
-    Some(TileId {
-        coord: TileCoord { x, y },
-    })
+    issues
 }
 
-#[allow(clippy::too_many_arguments)]
-pub(super) fn validate_tile_container(
+/// Moves the tile to the region's quarantine folder if quarantining is enabled and `issues`
+/// (the tile's own) contain an error. Returns whether the tile was moved.
+pub(super) fn quarantine_if_failed(
     layout: &WorldLayout,
     region: &str,
     tile_id: TileId,
+    issues: &[ValidationIssue],
+    context: &ValidationContext,
+) -> bool {
+    // Warnings and infos never move a tile out of the world.
+    if !context.quarantine || !issues.iter().any(|issue| issue.severity == Severity::Error) {
+        return false;
+    }
+    let reason = if issues[0].code == codes::TILE_HEADER_READ_FAILED {
+        "tile header read failed"
+    } else {
+        "tile validation failed"
+    };
+    let details: Vec<String> = issues
+        .iter()
+        .map(|issue| match &issue.section {
+            Some(section) => format!(
+                "{}[{}] {} {}",
+                issue.severity, issue.code, section, issue.message
+            ),
+            None => format!("{}[{}] {}", issue.severity, issue.code, issue.message),
+        })
+        .collect();
+    quarantine_tile_file_with_details(layout, region, tile_id, reason, &details).is_ok()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_tile_container(
     tile_path: &Path,
+    tile_id: TileId,
+    region: &str,
     expected_spec_hash: u64,
     legacy_spec_hash: u64,
     expected_spec: WorldSpec,
-    quarantine: bool,
+    context: &ValidationContext,
     issues: &mut Vec<ValidationIssue>,
 ) {
-    let reader = match TileContainerReader::open(tile_path) {
+    let reader = match TileContainerReader::open_buffered(tile_path) {
         Ok(reader) => reader,
         Err(err) => {
             issues.push(
-                ValidationIssue::new(format!("tile header read failed: {err}"))
-                    .with_path(tile_path.to_path_buf()),
+                ValidationIssue::error(
+                    codes::TILE_HEADER_READ_FAILED,
+                    format!("tile header read failed: {err}"),
+                )
+                .with_path(tile_path.to_path_buf()),
             );
-            if quarantine {
-                let _ = quarantine_tile_file(layout, region, tile_id, "tile header read failed");
-            }
             return;
         }
     };
 
     if reader.header.section_count > MAX_SECTION_COUNT {
         issues.push(
-            ValidationIssue::new(format!(
-                "section_count {} exceeds cap",
-                reader.header.section_count
-            ))
+            ValidationIssue::error(
+                codes::SECTION_COUNT_EXCEEDED,
+                format!("section_count {} exceeds cap", reader.header.section_count),
+            )
             .with_path(tile_path.to_path_buf()),
         );
     }
 
     if reader.header.container_version < MIN_CONTAINER_VERSION {
         issues.push(
-            ValidationIssue::new(format!(
-                "container version {} below minimum {}",
-                reader.header.container_version, MIN_CONTAINER_VERSION
-            ))
+            ValidationIssue::error(
+                codes::CONTAINER_VERSION_UNSUPPORTED,
+                format!(
+                    "container version {} below minimum {}",
+                    reader.header.container_version, MIN_CONTAINER_VERSION
+                ),
+            )
             .with_path(tile_path.to_path_buf()),
         );
     }
 
     if reader.header.container_version > CONTAINER_VERSION {
         issues.push(
-            ValidationIssue::new(format!(
-                "container version {} exceeds supported {}",
-                reader.header.container_version, CONTAINER_VERSION
-            ))
+            ValidationIssue::error(
+                codes::CONTAINER_VERSION_UNSUPPORTED,
+                format!(
+                    "container version {} exceeds supported {}",
+                    reader.header.container_version, CONTAINER_VERSION
+                ),
+            )
             .with_path(tile_path.to_path_buf()),
         );
     }
 
     if reader.header.section_dir_offset < HEADER_SIZE as u64 {
         issues.push(
-            ValidationIssue::new("section directory overlaps header")
-                .with_path(tile_path.to_path_buf()),
+            ValidationIssue::error(
+                codes::DIRECTORY_OVERLAPS_HEADER,
+                "section directory overlaps header",
+            )
+            .with_path(tile_path.to_path_buf()),
         );
     }
 
     let expected_hash = hash_region(region);
     if reader.header.region_hash != expected_hash {
-        issues
-            .push(ValidationIssue::new("region hash mismatch").with_path(tile_path.to_path_buf()));
+        issues.push(
+            ValidationIssue::error(codes::REGION_HASH_MISMATCH, "region hash mismatch")
+                .with_path(tile_path.to_path_buf()),
+        );
     }
 
     if reader.header.world_spec_hash != expected_spec_hash
         && reader.header.world_spec_hash != legacy_spec_hash
     {
         issues.push(
-            ValidationIssue::new("world spec hash mismatch").with_path(tile_path.to_path_buf()),
+            ValidationIssue::error(codes::WORLD_SPEC_HASH_MISMATCH, "world spec hash mismatch")
+                .with_path(tile_path.to_path_buf()),
         );
     }
 
     if reader.header.tile_x != tile_id.coord.x || reader.header.tile_y != tile_id.coord.y {
         issues.push(
-            ValidationIssue::new("tile_id does not match filename")
+            ValidationIssue::error(codes::TILE_ID_MISMATCH, "tile_id does not match filename")
                 .with_path(tile_path.to_path_buf()),
         );
     }
 
     directory::validate_directory(&reader, tile_path, issues);
-    sections::validate_sections(&reader, tile_path, expected_spec, issues);
-
-    if quarantine
-        && issues
-            .iter()
-            .any(|issue| issue.path.as_deref() == Some(tile_path))
-    {
-        let _ = quarantine_tile_file(layout, region, tile_id, "tile validation failed");
-    }
+    sections::validate_sections(&reader, tile_path, expected_spec, context, issues);
 }
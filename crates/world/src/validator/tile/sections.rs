@@ -1,74 +1,140 @@
 use crate::schema::WorldSpec;
 use crate::tile_container::{
-    decode_hmap, decode_liqd, decode_meta, decode_prop, decode_wmap, TileContainerReader,
-    TileSectionTag,
+    decode_addx, decode_current_section, decode_hmap, decode_liqd, decode_meta, decode_prop,
+    decode_spln, decode_wmap, TileContainerReader, TileSectionTag,
 };
 use std::path::Path;
 
-use super::checks::{validate_hmap, validate_liqd, validate_prop, validate_wmap};
-use super::ValidationIssue;
+use super::checks::{
+    validate_addx, validate_hmap, validate_liqd, validate_prop, validate_spln, validate_wmap,
+};
+use super::{codes, ValidationContext, ValidationIssue};
 
 pub(super) fn validate_sections(
     reader: &TileContainerReader,
     tile_path: &Path,
     expected_spec: WorldSpec,
+    context: &ValidationContext,
     issues: &mut Vec<ValidationIssue>,
 ) {
     if reader.section(TileSectionTag::META).is_none() {
-        issues
-            .push(ValidationIssue::new("missing META section").with_path(tile_path.to_path_buf()));
+        issues.push(
+            ValidationIssue::error(codes::META_MISSING, "missing META section")
+                .with_path(tile_path.to_path_buf())
+                .with_section(TileSectionTag::META),
+        );
     }
 
     for entry in &reader.directory {
-        let payload = match reader.decode_section(entry.tag) {
-            Ok(payload) => payload,
-            Err(err) => {
+        let start = issues.len();
+        validate_section(reader, entry.tag, tile_path, expected_spec, context, issues);
+        for issue in &mut issues[start..] {
+            issue.section = Some(entry.tag.to_string());
+        }
+    }
+}
+
+/// Checks one section; the caller tags every issue pushed here with the section.
+fn validate_section(
+    reader: &TileContainerReader,
+    tag: TileSectionTag,
+    tile_path: &Path,
+    expected_spec: WorldSpec,
+    context: &ValidationContext,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let payload = match decode_current_section(reader, tag) {
+        Ok(payload) => payload,
+        Err(err) => {
+            issues.push(
+                ValidationIssue::error(
+                    codes::SECTION_READ_FAILED,
+                    format!("section {} read failed: {err}", tag),
+                )
+                .with_path(tile_path.to_path_buf()),
+            );
+            return;
+        }
+    };
+
+    match tag {
+        tag if tag == TileSectionTag::META => {
+            if let Err(err) = decode_meta(&payload) {
                 issues.push(
-                    ValidationIssue::new(format!("section {} read failed: {err}", entry.tag))
-                        .with_path(tile_path.to_path_buf()),
+                    ValidationIssue::error(
+                        codes::SECTION_DECODE_FAILED,
+                        format!("META decode failed: {err}"),
+                    )
+                    .with_path(tile_path.to_path_buf()),
                 );
-                continue;
-            }
-        };
-
-        match entry.tag {
-            tag if tag == TileSectionTag::META => {
-                if let Err(err) = decode_meta(&payload) {
-                    issues.push(
-                        ValidationIssue::new(format!("META decode failed: {err}"))
-                            .with_path(tile_path.to_path_buf()),
-                    );
-                }
             }
-            tag if tag == TileSectionTag::HMAP => match decode_hmap(&payload) {
-                Ok(hmap) => validate_hmap(&hmap, expected_spec, tile_path, issues),
-                Err(err) => issues.push(
-                    ValidationIssue::new(format!("HMAP decode failed: {err}"))
-                        .with_path(tile_path.to_path_buf()),
-                ),
-            },
-            tag if tag == TileSectionTag::WMAP => match decode_wmap(&payload) {
-                Ok(wmap) => validate_wmap(&wmap, expected_spec, tile_path, issues),
-                Err(err) => issues.push(
-                    ValidationIssue::new(format!("WMAP decode failed: {err}"))
-                        .with_path(tile_path.to_path_buf()),
-                ),
-            },
-            tag if tag == TileSectionTag::LIQD => match decode_liqd(&payload) {
-                Ok(liqd) => validate_liqd(&liqd, expected_spec, tile_path, issues),
-                Err(err) => issues.push(
-                    ValidationIssue::new(format!("LIQD decode failed: {err}"))
-                        .with_path(tile_path.to_path_buf()),
-                ),
-            },
-            tag if tag == TileSectionTag::PROP => match decode_prop(&payload) {
-                Ok(prop) => validate_prop(&prop, tile_path, issues),
-                Err(err) => issues.push(
-                    ValidationIssue::new(format!("PROP decode failed: {err}"))
-                        .with_path(tile_path.to_path_buf()),
-                ),
-            },
-            _ => {}
         }
+        tag if tag == TileSectionTag::HMAP => match decode_hmap(&payload) {
+            Ok(hmap) => validate_hmap(&hmap, expected_spec, tile_path, issues),
+            Err(err) => issues.push(
+                ValidationIssue::error(
+                    codes::SECTION_DECODE_FAILED,
+                    format!("HMAP decode failed: {err}"),
+                )
+                .with_path(tile_path.to_path_buf()),
+            ),
+        },
+        tag if tag == TileSectionTag::WMAP => match decode_wmap(&payload) {
+            Ok(wmap) => validate_wmap(&wmap, expected_spec, tile_path, issues),
+            Err(err) => issues.push(
+                ValidationIssue::error(
+                    codes::SECTION_DECODE_FAILED,
+                    format!("WMAP decode failed: {err}"),
+                )
+                .with_path(tile_path.to_path_buf()),
+            ),
+        },
+        tag if tag == TileSectionTag::LIQD => match decode_liqd(&payload) {
+            Ok(liqd) => validate_liqd(
+                &liqd,
+                expected_spec,
+                context.liquid_kinds,
+                tile_path,
+                issues,
+            ),
+            Err(err) => issues.push(
+                ValidationIssue::error(
+                    codes::SECTION_DECODE_FAILED,
+                    format!("LIQD decode failed: {err}"),
+                )
+                .with_path(tile_path.to_path_buf()),
+            ),
+        },
+        tag if tag == TileSectionTag::PROP => match decode_prop(&payload) {
+            Ok(prop) => validate_prop(&prop, tile_path, issues),
+            Err(err) => issues.push(
+                ValidationIssue::error(
+                    codes::SECTION_DECODE_FAILED,
+                    format!("PROP decode failed: {err}"),
+                )
+                .with_path(tile_path.to_path_buf()),
+            ),
+        },
+        tag if tag == TileSectionTag::SPLN => match decode_spln(&payload) {
+            Ok(spln) => validate_spln(&spln, tile_path, issues),
+            Err(err) => issues.push(
+                ValidationIssue::error(
+                    codes::SECTION_DECODE_FAILED,
+                    format!("SPLN decode failed: {err}"),
+                )
+                .with_path(tile_path.to_path_buf()),
+            ),
+        },
+        tag if tag == TileSectionTag::ADDX => match decode_addx(&payload) {
+            Ok(addx) => validate_addx(&addx, context.extensions, tile_path, issues),
+            Err(err) => issues.push(
+                ValidationIssue::error(
+                    codes::SECTION_DECODE_FAILED,
+                    format!("ADDX decode failed: {err}"),
+                )
+                .with_path(tile_path.to_path_buf()),
+            ),
+        },
+        _ => {}
     }
 }
@@ -1,25 +1,83 @@
 use crate::migrations::migrate_world_manifest;
 use crate::schema::{RegionManifest, WorldManifest, WorldSpec};
 use crate::storage::{
-    read_world_manifest, region_tiles_dir, world_layout, ProjectLayout, WorldLayout,
-    WORLD_MANIFEST_FILE,
+    list_world_ids, project_layout, read_project_manifest, read_world_manifest, region_tiles_dir,
+    world_layout, ProjectLayout, WorldLayout, WORLD_MANIFEST_FILE,
 };
 use crate::tile_container::world_spec_hash::{
     hash_world_spec_from_manifest, hash_world_spec_legacy,
 };
-use std::collections::HashSet;
+use anyhow::Context;
+use foundation::ids::TileCoord;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use super::tile;
-use super::ValidationIssue;
+use super::cache::{TileKey, WorldCache};
+use super::instances::{self, InstanceLocation};
+use super::{assets, seams, tile};
+use super::{codes, ValidationContext, ValidationIssue};
+
+/// A `.tile` file of a region listed in its world manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectTile {
+    pub world_id: String,
+    pub region_id: String,
+    pub tile: TileCoord,
+    pub path: PathBuf,
+}
+
+/// Lists every well-named tile in the project, ordered by world, region and path. Worlds whose
+/// manifest cannot be read are skipped; validation reports them.
+pub fn list_project_tiles(project_root: &Path) -> anyhow::Result<Vec<ProjectTile>> {
+    let layout = project_layout(project_root, &read_project_manifest(project_root)?);
+    let mut tiles = Vec::new();
+    for world_id in list_world_ids(&layout)? {
+        let world = world_layout(&layout, &world_id);
+        let Ok(manifest) = read_world_manifest(&world.world_root) else {
+            continue;
+        };
+        for region in &manifest.regions {
+            let dir = region_tiles_dir(&world, &region.region_id);
+            if !dir.exists() {
+                continue;
+            }
+            let mut region_tiles = Vec::new();
+            for entry in
+                std::fs::read_dir(&dir).with_context(|| format!("read tiles dir {:?}", dir))?
+            {
+                let path = entry?.path();
+                let Some(tile_id) = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(tile::parse_tile_filename)
+                else {
+                    continue;
+                };
+                region_tiles.push(ProjectTile {
+                    world_id: world_id.clone(),
+                    region_id: region.region_id.clone(),
+                    tile: tile_id.coord,
+                    path,
+                });
+            }
+            region_tiles.sort_by(|a, b| a.path.cmp(&b.path));
+            tiles.extend(region_tiles);
+        }
+    }
+    Ok(tiles)
+}
 
 pub(super) fn scan_worlds(
     layout: &ProjectLayout,
-    quarantine: bool,
+    context: &ValidationContext,
     issues: &mut Vec<ValidationIssue>,
 ) {
     if !layout.worlds_dir.exists() {
         issues.push(
-            ValidationIssue::new("worlds directory missing").with_path(layout.worlds_dir.clone()),
+            ValidationIssue::error(codes::WORLDS_DIR_MISSING, "worlds directory missing")
+                .with_path(layout.worlds_dir.clone()),
         );
         return;
     }
@@ -28,8 +86,11 @@ pub(super) fn scan_worlds(
         Ok(entries) => entries,
         Err(err) => {
             issues.push(
-                ValidationIssue::new(format!("read worlds dir failed: {err}"))
-                    .with_path(layout.worlds_dir.clone()),
+                ValidationIssue::error(
+                    codes::WORLDS_DIR_READ_FAILED,
+                    format!("read worlds dir failed: {err}"),
+                )
+                .with_path(layout.worlds_dir.clone()),
             );
             return;
         }
@@ -50,8 +111,11 @@ pub(super) fn scan_worlds(
             Ok(manifest) => manifest,
             Err(err) => {
                 issues.push(
-                    ValidationIssue::new(format!("world manifest read failed: {err}"))
-                        .with_path(world_root.join(WORLD_MANIFEST_FILE)),
+                    ValidationIssue::error(
+                        codes::MANIFEST_READ_FAILED,
+                        format!("world manifest read failed: {err}"),
+                    )
+                    .with_path(world_root.join(WORLD_MANIFEST_FILE)),
                 );
                 continue;
             }
@@ -59,31 +123,51 @@ pub(super) fn scan_worlds(
 
         if world_manifest.world_id != dir_name {
             issues.push(
-                ValidationIssue::new("world_id does not match directory name")
-                    .with_path(world_root.join(WORLD_MANIFEST_FILE)),
+                ValidationIssue::error(
+                    codes::WORLD_ID_MISMATCH,
+                    "world_id does not match directory name",
+                )
+                .with_path(world_root.join(WORLD_MANIFEST_FILE)),
             );
         }
 
         if let Err(err) = migrate_world_manifest(&mut world_manifest.clone()) {
-            issues.push(ValidationIssue::new(format!(
-                "world migration check failed: {err}"
-            )));
+            issues.push(ValidationIssue::error(
+                codes::MANIFEST_MIGRATION_FAILED,
+                format!("world migration check failed: {err}"),
+            ));
         }
 
         let world_layout = world_layout(layout, &dir_name);
-        scan_world_tiles(&world_layout, &world_manifest, quarantine, issues);
+        let mut cache = context
+            .cache_dir
+            .as_ref()
+            .map(|dir| WorldCache::load(dir, &world_layout, &world_manifest, context));
+        scan_world_tiles(
+            &world_layout,
+            &world_manifest,
+            context,
+            cache.as_mut(),
+            issues,
+        );
+        // A cache that cannot be written only costs the next run its speed-up.
+        if let Some(cache) = cache {
+            let _ = cache.save();
+        }
     }
 }
 
 pub(super) fn scan_world_tiles(
     layout: &WorldLayout,
     manifest: &WorldManifest,
-    quarantine: bool,
+    context: &ValidationContext,
+    mut cache: Option<&mut WorldCache>,
     issues: &mut Vec<ValidationIssue>,
 ) {
     if !layout.regions_dir.exists() {
         issues.push(
-            ValidationIssue::new("regions directory missing").with_path(layout.regions_dir.clone()),
+            ValidationIssue::error(codes::REGIONS_DIR_MISSING, "regions directory missing")
+                .with_path(layout.regions_dir.clone()),
         );
         return;
     }
@@ -98,8 +182,11 @@ pub(super) fn scan_world_tiles(
         Ok(entries) => entries,
         Err(err) => {
             issues.push(
-                ValidationIssue::new(format!("read regions dir failed: {err}"))
-                    .with_path(layout.regions_dir.clone()),
+                ValidationIssue::error(
+                    codes::REGIONS_DIR_READ_FAILED,
+                    format!("read regions dir failed: {err}"),
+                )
+                .with_path(layout.regions_dir.clone()),
             );
             return;
         }
@@ -118,8 +205,11 @@ pub(super) fn scan_world_tiles(
 
         if !manifest_regions.contains(&region_name) {
             issues.push(
-                ValidationIssue::new("region directory not listed in world manifest")
-                    .with_path(region_path.clone()),
+                ValidationIssue::warning(
+                    codes::REGION_NOT_LISTED,
+                    "region directory not listed in world manifest",
+                )
+                .with_path(region_path.clone()),
             );
         }
     }
@@ -127,63 +217,177 @@ pub(super) fn scan_world_tiles(
     let expected_spec_hash = hash_world_spec_from_manifest(manifest);
     let legacy_spec_hash = hash_world_spec_legacy(manifest.world_spec);
     let expected_spec = manifest.world_spec;
+    let mut world_tiles = Vec::new();
     for region in &manifest.regions {
-        scan_region_tiles(
+        let tiles = scan_region_tiles(
             layout,
             region,
             expected_spec_hash,
             legacy_spec_hash,
             expected_spec,
-            quarantine,
+            context,
+            cache.as_deref_mut(),
             issues,
         );
+        let pass = format!("seams/{}", region.region_id);
+        run_cached_pass(&pass, 0, &tiles, cache.as_deref_mut(), issues, |found| {
+            seams::validate_region_seams(layout, &region.region_id, context.seam_tolerance, found)
+        });
+        world_tiles.extend(tiles);
+    }
+
+    scan_world_props(&world_tiles, context, cache, issues);
+}
+
+/// Runs the world-wide instance id pass and the per-tile asset reference check. Both read PROP
+/// sections, so each tile's PROP is decoded at most once, and only when the instance pass or the
+/// tile's cached asset issues are stale.
+fn scan_world_props(
+    tiles: &[ScannedTile],
+    context: &ValidationContext,
+    mut cache: Option<&mut WorldCache>,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let catalog = context
+        .assets
+        .as_ref()
+        .map(|catalog| (catalog, assets::catalog_key(catalog)));
+    let instances_key = cache.as_deref().and_then(|cache| {
+        let keys: Option<Vec<(&Path, TileKey)>> = tiles
+            .iter()
+            .map(|tile| Some((tile.location.path.as_path(), tile.key?)))
+            .collect();
+        Some(cache.tiles_key(0, &keys?))
+    });
+    let cached_instances = cache
+        .as_deref()
+        .zip(instances_key)
+        .and_then(|(cache, key)| cache.pass_issues("instances", key));
+    let cached_assets: Vec<Option<Vec<ValidationIssue>>> = tiles
+        .iter()
+        .map(|tile| {
+            let (_, catalog_key) = catalog?;
+            cache
+                .as_deref()?
+                .asset_issues(&tile.location.path, tile.key?, catalog_key)
+        })
+        .collect();
+
+    let need_ids = cached_instances.is_none();
+    let jobs: Vec<(&InstanceLocation, bool)> = tiles
+        .iter()
+        .zip(&cached_assets)
+        .map(|(tile, cached)| (&tile.location, catalog.is_some() && cached.is_none()))
+        .collect();
+    let scanned = parallel_map(&jobs, context.threads, |(location, check_assets)| {
+        if !need_ids && !*check_assets {
+            return (BTreeSet::new(), None);
+        }
+        // Tiles without a readable PROP section are skipped; the per-tile checks report those.
+        let prop = instances::read_prop(&location.path);
+        let ids = prop
+            .as_ref()
+            .map(instances::instance_ids)
+            .unwrap_or_default();
+        let asset_issues = match (check_assets, &prop, catalog) {
+            (true, Some(prop), Some((catalog, _))) => {
+                Some(assets::validate_tile_assets(location, prop, catalog))
+            }
+            (true, _, _) => Some(Vec::new()),
+            (false, _, _) => None,
+        };
+        (ids, asset_issues)
+    });
+    let (ids, fresh_assets): (Vec<_>, Vec<_>) = scanned.into_iter().unzip();
+
+    let instance_issues = cached_instances.unwrap_or_else(|| {
+        let locations: Vec<InstanceLocation> =
+            tiles.iter().map(|tile| tile.location.clone()).collect();
+        let mut found = Vec::new();
+        instances::validate_world_instances(&locations, &ids, &mut found);
+        found
+    });
+    if let (Some(cache), Some(key)) = (cache.as_deref_mut(), instances_key) {
+        cache.store_pass("instances", key, &instance_issues);
+    }
+    issues.extend(instance_issues);
+
+    let Some((_, catalog_key)) = catalog else {
+        return;
+    };
+    for ((tile, cached), fresh) in tiles.iter().zip(cached_assets).zip(fresh_assets) {
+        let tile_issues = cached.or(fresh).unwrap_or_default();
+        if let (Some(cache), Some(key)) = (cache.as_deref_mut(), tile.key) {
+            cache.store_assets(&tile.location.path, key, catalog_key, &tile_issues);
+        }
+        issues.extend(tile_issues);
     }
 }
 
+/// A tile still in its region after the per-tile checks (i.e. not quarantined).
+struct ScannedTile {
+    location: InstanceLocation,
+    /// `None` when caching is off or the tile's header is unreadable.
+    key: Option<TileKey>,
+}
+
 fn validate_region_entry(
     layout: &WorldLayout,
     region: &RegionManifest,
     issues: &mut Vec<ValidationIssue>,
 ) {
     if region.region_id.trim().is_empty() {
-        issues.push(ValidationIssue::new("region_id is empty"));
+        issues.push(ValidationIssue::error(
+            codes::REGION_ID_EMPTY,
+            "region_id is empty",
+        ));
     }
     if !region.bounds.is_valid() {
         issues.push(
-            ValidationIssue::new("region bounds are invalid")
+            ValidationIssue::error(codes::REGION_BOUNDS_INVALID, "region bounds are invalid")
                 .with_path(layout.world_root.join(WORLD_MANIFEST_FILE)),
         );
     }
 }
 
+/// Validates every tile of a region across `context.threads` workers, reusing cached results
+/// for unchanged tiles. Issues are reported in tile path order. Returns the tiles still in the
+/// region afterwards (i.e. not quarantined) with their cache keys.
+#[allow(clippy::too_many_arguments)]
 fn scan_region_tiles(
     layout: &WorldLayout,
     region: &RegionManifest,
     expected_spec_hash: u64,
     legacy_spec_hash: u64,
     expected_spec: WorldSpec,
-    quarantine: bool,
+    context: &ValidationContext,
+    mut cache: Option<&mut WorldCache>,
     issues: &mut Vec<ValidationIssue>,
-) {
+) -> Vec<ScannedTile> {
     let tiles_dir = region_tiles_dir(layout, &region.region_id);
     if !tiles_dir.exists() {
         issues.push(
-            ValidationIssue::new("region tiles directory missing").with_path(tiles_dir.clone()),
+            ValidationIssue::warning(codes::TILES_DIR_MISSING, "region tiles directory missing")
+                .with_path(tiles_dir.clone()),
         );
-        return;
+        return Vec::new();
     }
 
     let tiles = match std::fs::read_dir(&tiles_dir) {
         Ok(entries) => entries,
         Err(err) => {
             issues.push(
-                ValidationIssue::new(format!("read region tiles failed: {err}"))
-                    .with_path(tiles_dir.clone()),
+                ValidationIssue::error(
+                    codes::TILES_DIR_READ_FAILED,
+                    format!("read region tiles failed: {err}"),
+                )
+                .with_path(tiles_dir.clone()),
             );
-            return;
+            return Vec::new();
         }
     };
 
+    let mut jobs = Vec::new();
     for tile_entry in tiles.flatten() {
         let tile_path = tile_entry.path();
         if tile_path.extension().and_then(|ext| ext.to_str()) != Some("tile") {
@@ -195,27 +399,129 @@ fn scan_region_tiles(
             None => continue,
         };
 
-        let tile_id = match tile::parse_tile_filename(tile_name) {
-            Some(tile_id) => tile_id,
-            None => {
-                issues.push(
-                    ValidationIssue::new(format!("invalid tile filename: {tile_name}"))
-                        .with_path(tile_path.clone()),
-                );
-                continue;
-            }
-        };
+        match tile::parse_tile_filename(tile_name) {
+            Some(tile_id) => jobs.push((tile_path, tile_id)),
+            None => issues.push(
+                ValidationIssue::error(
+                    codes::TILE_FILENAME_INVALID,
+                    format!("invalid tile filename: {tile_name}"),
+                )
+                .with_path(tile_path.clone()),
+            ),
+        }
+    }
+    jobs.sort_by(|a, b| a.0.cmp(&b.0));
 
-        tile::validate_tile_container(
-            layout,
+    let cached = cache.as_deref();
+    let results = parallel_map(&jobs, context.threads, |(tile_path, tile_id)| {
+        let key = cached.and_then(|_| TileKey::read(tile_path));
+        if let (Some(cache), Some(key)) = (cached, key) {
+            if let Some(tile_issues) = cache.tile_issues(tile_path, key) {
+                return (Some(key), tile_issues);
+            }
+        }
+        let tile_issues = tile::tile_issues(
+            tile_path,
+            *tile_id,
             &region.region_id,
-            tile_id,
-            &tile_path,
             expected_spec_hash,
             legacy_spec_hash,
             expected_spec,
-            quarantine,
-            issues,
+            context,
         );
+        (key, tile_issues)
+    });
+
+    // Quarantine moves files, so it runs here rather than on the workers.
+    let mut remaining = Vec::with_capacity(jobs.len());
+    for ((tile_path, tile_id), (key, tile_issues)) in jobs.into_iter().zip(results) {
+        if let (Some(cache), Some(key)) = (cache.as_deref_mut(), key) {
+            cache.store_tile(&tile_path, key, &tile_issues);
+        }
+        if !tile::quarantine_if_failed(layout, &region.region_id, tile_id, &tile_issues, context) {
+            remaining.push(ScannedTile {
+                location: InstanceLocation {
+                    region_id: region.region_id.clone(),
+                    tile: tile_id.coord,
+                    path: tile_path,
+                },
+                key,
+            });
+        }
+        issues.extend(tile_issues);
     }
+    remaining
+}
+
+/// Runs a cross-tile pass, reusing its cached result while every tile in `tiles` and `salt` are
+/// unchanged.
+fn run_cached_pass(
+    pass: &str,
+    salt: u64,
+    tiles: &[ScannedTile],
+    cache: Option<&mut WorldCache>,
+    issues: &mut Vec<ValidationIssue>,
+    run: impl FnOnce(&mut Vec<ValidationIssue>),
+) {
+    let keys: Option<Vec<(&Path, TileKey)>> = tiles
+        .iter()
+        .map(|tile| Some((tile.location.path.as_path(), tile.key?)))
+        .collect();
+    let (Some(cache), Some(keys)) = (cache, keys) else {
+        run(issues);
+        return;
+    };
+    let key = cache.tiles_key(salt, &keys);
+    let pass_issues = cache.pass_issues(pass, key).unwrap_or_else(|| {
+        let mut found = Vec::new();
+        run(&mut found);
+        found
+    });
+    cache.store_pass(pass, key, &pass_issues);
+    issues.extend(pass_issues);
+}
+
+/// Applies `f` to every item on up to `threads` scoped threads, returning the results in item
+/// order. Workers pull the next unclaimed item, so uneven tiles still balance out.
+fn parallel_map<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let threads = threads.min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = (0..items.len()).map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        done.push((index, f(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        for worker in workers {
+            let done = worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for (index, result) in done {
+                results[index] = Some(result);
+            }
+        }
+    });
+    results
+        .into_iter()
+        .map(|result| result.expect("every item is claimed by a worker"))
+        .collect()
 }
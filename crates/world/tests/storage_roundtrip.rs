@@ -2,13 +2,24 @@ use std::fs;
 use std::path::Path;
 
 use tempfile::tempdir;
+use world::migrations::{migrate_section_payload, migrate_world_sections};
 use world::schema::{
     ProjectManifest, RegionBounds, RegionManifest, WorldManifest, PROJECT_FORMAT_VERSION,
 };
 use world::storage::{
-    create_project, create_world, load_tile_stub, read_project_manifest, save_tile_stub,
-    tile_container_path, LiquidBody, LiquidKind, LiquidsMask, LiquidsMeta, PropInstance,
-    PropsInstances, TerrainHeight, TileMeta, TileStub,
+    create_project, create_world, find_legacy_tiles, list_quarantined, load_tile_stub,
+    migrate_legacy_tiles, purge_quarantined, read_project_manifest, restore_quarantined,
+    save_tile_stub, tile_container_path, write_liquids_mask, write_liquids_meta,
+    write_project_manifest, write_props_instances, write_terrain_height, write_tile_meta,
+    LegacyDisposition, LiquidBody, LiquidKind, LiquidsMask, LiquidsMeta, PropInstance,
+    PropsInstances, QuarantineKind, RestoreOutcome, TerrainHeight, TileMeta, TileStub, WorldLayout,
+    QUARANTINE_MANIFEST_FILE,
+};
+use world::tile_container::extensions::AddxRegistry;
+use world::tile_container::{
+    compact_tile, decode_current_section, decode_liqd, decode_prop, encode_prop, payload_version,
+    reclaimable_bytes, PropRecord, PropSection, TileContainerPatch, TileContainerReader,
+    TileSectionPayload, TileSectionTag, CODEC_LZ4, CODEC_RAW,
 };
 use world::{AssetId, InstanceId, TileCoord, TileId};
 
@@ -77,6 +88,409 @@ fn create_save_reload_roundtrip() {
     assert_eq!(loaded_manifest, project_manifest);
 }
 
+#[test]
+fn custom_liquid_kinds_roundtrip_and_validate_against_project_registry() {
+    let temp = tempdir().expect("tempdir");
+    let mut project_manifest = ProjectManifest::default();
+    let project_layout = create_project(temp.path(), &project_manifest).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+
+    let tile_id = TileId {
+        coord: TileCoord { x: 0, y: 0 },
+    };
+    let liquids_meta = LiquidsMeta::new(vec![
+        LiquidBody {
+            id: 1,
+            height: 2.0,
+            kind: LiquidKind::Custom("swamp".to_string()),
+        },
+        LiquidBody {
+            id: 2,
+            height: 3.0,
+            kind: LiquidKind::Water,
+        },
+        LiquidBody {
+            id: 3,
+            height: 4.0,
+            kind: LiquidKind::Custom("acid".to_string()),
+        },
+    ]);
+    let stub = TileStub {
+        meta: TileMeta::new(tile_id),
+        terrain: TerrainHeight::new(2, vec![0.0; 4]),
+        liquids_mask: LiquidsMask::new(2, vec![0, 1, 2, 0]),
+        liquids_meta: liquids_meta.clone(),
+        props: PropsInstances::new(Vec::new()),
+    };
+    save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+        .expect("save tile stub");
+
+    let loaded = load_tile_stub(&world_layout, "region_0", tile_id).expect("load tile stub");
+    assert_eq!(loaded.liquids_meta, liquids_meta);
+
+    let issues = world::validator::validate_project(temp.path());
+    assert!(
+        issues
+            .iter()
+            .any(|issue| issue.message.contains("unregistered liquid kind \"acid\"")),
+        "expected unregistered liquid kind issue"
+    );
+
+    project_manifest.liquid_kinds = vec!["acid".to_string(), "swamp".to_string()];
+    write_project_manifest(temp.path(), &project_manifest).expect("write manifest");
+    let issues = world::validator::validate_project(temp.path());
+    assert!(
+        !issues
+            .iter()
+            .any(|issue| issue.message.contains("unregistered liquid kind")),
+        "registered liquid kinds must validate"
+    );
+}
+
+#[test]
+fn patching_prop_section_leaves_other_sections_untouched() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let tile_id = TileId {
+        coord: TileCoord { x: 0, y: 0 },
+    };
+    let stub = TileStub {
+        meta: TileMeta::new(tile_id),
+        terrain: TerrainHeight::new(2, vec![0.0, 1.0, 2.0, 3.0]),
+        liquids_mask: LiquidsMask::new(2, vec![0; 4]),
+        liquids_meta: LiquidsMeta::new(Vec::new()),
+        props: PropsInstances::new(Vec::new()),
+    };
+    save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+        .expect("save tile stub");
+    let rock = project_layout.assets_dir.join("core").join("rock.glb");
+    std::fs::create_dir_all(rock.parent().unwrap()).expect("create asset dir");
+    std::fs::write(&rock, b"").expect("write asset");
+    let baseline_issues = world::validator::validate_project(temp.path()).len();
+
+    let path = tile_container_path(&world_layout, "region_0", tile_id);
+    let before = TileContainerReader::open_buffered(&path).expect("open tile");
+    let hmap_entry = before.section(TileSectionTag::HMAP).cloned().unwrap();
+    let hmap_bytes = before.section_bytes(TileSectionTag::HMAP).unwrap().to_vec();
+
+    let moved = PropSection {
+        instances: vec![PropRecord {
+            id: InstanceId(7),
+            asset: AssetId::new("core", "rock"),
+            translation: [4.0, 0.0, 2.0],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0, 1.0, 1.0],
+        }],
+    };
+    let mut patch = TileContainerPatch::new();
+    patch.replace_section(TileSectionPayload {
+        tag: TileSectionTag::PROP,
+        section_version: 1,
+        codec: CODEC_LZ4,
+        flags: 0,
+        decoded: encode_prop(&moved).expect("encode prop"),
+    });
+    patch.apply(&path).expect("apply patch");
+    assert!(!path.with_extension("tile.bak").exists());
+
+    let patched = TileContainerReader::open_buffered(&path).expect("open patched tile");
+    let patched_hmap = patched.section(TileSectionTag::HMAP).unwrap();
+    assert_eq!(patched_hmap.offset, hmap_entry.offset);
+    assert_eq!(patched_hmap.crc32, hmap_entry.crc32);
+    assert_eq!(
+        patched.section_bytes(TileSectionTag::HMAP).unwrap(),
+        hmap_bytes.as_slice()
+    );
+    let prop = decode_prop(&patched.decode_section(TileSectionTag::PROP).unwrap()).unwrap();
+    assert_eq!(prop, moved);
+    assert!(reclaimable_bytes(&patched) > 0);
+    assert_eq!(
+        world::validator::validate_project(temp.path()).len(),
+        baseline_issues
+    );
+
+    let reclaimed = compact_tile(&path).expect("compact tile");
+    assert!(reclaimed > 0);
+    let compacted = TileContainerReader::open_buffered(&path).expect("open compacted tile");
+    assert_eq!(reclaimable_bytes(&compacted), 0);
+    assert_eq!(
+        compacted.section_bytes(TileSectionTag::HMAP).unwrap(),
+        hmap_bytes.as_slice()
+    );
+    let loaded = load_tile_stub(&world_layout, "region_0", tile_id).expect("load tile stub");
+    assert_eq!(loaded.terrain, stub.terrain);
+    assert_eq!(loaded.props.instances.len(), 1);
+    assert_eq!(loaded.props.instances[0].id, InstanceId(7));
+}
+
+#[test]
+fn legacy_tile_folders_migrate_into_containers() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 3, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let tile = |x| TileId {
+        coord: TileCoord { x, y: 0 },
+    };
+    let stub = |x: i32| TileStub {
+        meta: TileMeta::new(tile(x)),
+        terrain: TerrainHeight::new(2, vec![x as f32; 4]),
+        liquids_mask: LiquidsMask::new(2, vec![0, 1, 1, 0]),
+        liquids_meta: LiquidsMeta::new(vec![LiquidBody {
+            id: 1,
+            height: 0.5,
+            kind: LiquidKind::Lava,
+        }]),
+        props: PropsInstances::new(vec![PropInstance {
+            id: InstanceId(x as u64 + 1),
+            asset: AssetId::new("core", "rock"),
+            translation: [0.0, 1.0, 2.0],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0, 1.0, 1.0],
+        }]),
+    };
+    for x in 0..3 {
+        write_legacy_tile(&world_layout, tile(x), &stub(x));
+    }
+    // Tile 1 was converted by an interrupted run; tile 2 has a container with different data.
+    save_tile_stub(
+        &world_layout,
+        &world_manifest,
+        "region_0",
+        tile(1),
+        &stub(1),
+    )
+    .expect("save tile 1");
+    save_tile_stub(
+        &world_layout,
+        &world_manifest,
+        "region_0",
+        tile(2),
+        &stub(5),
+    )
+    .expect("save tile 2");
+    assert_eq!(
+        find_legacy_tiles(&world_layout, &world_manifest)
+            .expect("find legacy tiles")
+            .len(),
+        3
+    );
+
+    let report = migrate_legacy_tiles(
+        &world_layout,
+        &world_manifest,
+        LegacyDisposition::Quarantine,
+    )
+    .expect("migrate");
+    assert_eq!(report.migrated.len(), 1);
+    assert_eq!(report.migrated[0].tile_id, tile(0));
+    assert_eq!(report.already_migrated.len(), 1);
+    assert_eq!(report.already_migrated[0].tile_id, tile(1));
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0.tile_id, tile(2));
+    assert!(!report.is_complete());
+
+    let loaded = load_tile_stub(&world_layout, "region_0", tile(0)).expect("load tile 0");
+    assert_eq!(loaded.terrain, stub(0).terrain);
+    assert_eq!(loaded.props, stub(0).props);
+    let remaining = find_legacy_tiles(&world_layout, &world_manifest).expect("find legacy tiles");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].tile_id, tile(2));
+
+    let report = migrate_legacy_tiles(&world_layout, &world_manifest, LegacyDisposition::Delete)
+        .expect("migrate again");
+    assert!(report.migrated.is_empty());
+    assert_eq!(report.failed.len(), 1);
+}
+
+fn write_legacy_tile(layout: &WorldLayout, tile_id: TileId, stub: &TileStub) {
+    write_tile_meta(layout, "region_0", tile_id, &stub.meta).expect("write meta");
+    write_terrain_height(layout, "region_0", tile_id, &stub.terrain).expect("write terrain");
+    write_liquids_mask(layout, "region_0", tile_id, &stub.liquids_mask).expect("write mask");
+    write_liquids_meta(layout, "region_0", tile_id, &stub.liquids_meta).expect("write liquids");
+    write_props_instances(layout, "region_0", tile_id, &stub.props).expect("write props");
+}
+
+#[test]
+fn old_section_versions_migrate_on_load_and_in_batch() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let tile_id = TileId {
+        coord: TileCoord { x: 0, y: 0 },
+    };
+    let stub = TileStub {
+        meta: TileMeta::new(tile_id),
+        terrain: TerrainHeight::new(2, vec![0.0; 4]),
+        liquids_mask: LiquidsMask::new(2, vec![0, 1, 1, 0]),
+        liquids_meta: LiquidsMeta::new(Vec::new()),
+        props: PropsInstances::new(Vec::new()),
+    };
+    save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+        .expect("save tile stub");
+
+    let path = tile_container_path(&world_layout, "region_0", tile_id);
+    write_liqd_v1(&path);
+
+    let reader = TileContainerReader::open_buffered(&path).expect("open tile");
+    let stored = reader.decode_section(TileSectionTag::LIQD).unwrap();
+    assert_eq!(payload_version(&stored).unwrap(), 1);
+    let migrated = decode_current_section(&reader, TileSectionTag::LIQD).unwrap();
+    assert_eq!(payload_version(&migrated).unwrap(), 2);
+    assert_eq!(decode_liqd(&migrated).unwrap().bodies[0].id, 9);
+    drop(reader);
+
+    let report = migrate_world_sections(&world_layout, &world_manifest).expect("migrate world");
+    assert_eq!(report.tiles_scanned, 1);
+    assert_eq!(report.tiles_rewritten, 1);
+    assert_eq!(report.sections.len(), 1);
+    assert_eq!(report.sections[0].tag, "LIQD");
+    assert_eq!((report.sections[0].from, report.sections[0].to), (1, 2));
+    assert!(report.failed.is_empty());
+
+    let reader = TileContainerReader::open_buffered(&path).expect("open migrated tile");
+    assert_eq!(
+        reader
+            .section(TileSectionTag::LIQD)
+            .unwrap()
+            .section_version,
+        2
+    );
+    let stored = reader.decode_section(TileSectionTag::LIQD).unwrap();
+    assert_eq!(payload_version(&stored).unwrap(), 2);
+    assert_eq!(reclaimable_bytes(&reader), 0);
+    drop(reader);
+
+    let report = migrate_world_sections(&world_layout, &world_manifest).expect("migrate again");
+    assert_eq!(report.tiles_rewritten, 0);
+
+    let mut future = 9u16.to_le_bytes().to_vec();
+    future.extend_from_slice(&[0; 10]);
+    assert!(migrate_section_payload(TileSectionTag::HMAP, &future).is_err());
+}
+
+/// Replaces LIQD with a v1 payload: 2x2 mask, one lava body with id 9.
+fn write_liqd_v1(path: &Path) {
+    let mut liqd_v1 = Vec::new();
+    for value in [1u16, 0, 2, 2, 1, 0] {
+        liqd_v1.extend_from_slice(&value.to_le_bytes());
+    }
+    liqd_v1.extend_from_slice(&[0, 1, 1, 0]);
+    liqd_v1.extend_from_slice(&9u32.to_le_bytes());
+    liqd_v1.extend_from_slice(&1.5f32.to_le_bytes());
+    liqd_v1.extend_from_slice(&1u16.to_le_bytes());
+    liqd_v1.extend_from_slice(&0u16.to_le_bytes());
+    let mut patch = TileContainerPatch::new();
+    patch.replace_section(TileSectionPayload {
+        tag: TileSectionTag::LIQD,
+        section_version: 1,
+        codec: CODEC_RAW,
+        flags: 0,
+        decoded: liqd_v1,
+    });
+    patch.apply(path).expect("write v1 LIQD");
+}
+
+#[test]
+fn dry_run_plan_reports_manifest_and_section_migrations_without_writing() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let tile_id = TileId {
+        coord: TileCoord { x: 0, y: 0 },
+    };
+    let stub = TileStub {
+        meta: TileMeta::new(tile_id),
+        terrain: TerrainHeight::new(2, vec![0.0; 4]),
+        liquids_mask: LiquidsMask::new(2, vec![0; 4]),
+        liquids_meta: LiquidsMeta::new(Vec::new()),
+        props: PropsInstances::new(Vec::new()),
+    };
+    save_tile_stub(&world_layout, &world_manifest, "region_0", tile_id, &stub)
+        .expect("save tile stub");
+    let tile_path = tile_container_path(&world_layout, "region_0", tile_id);
+    write_liqd_v1(&tile_path);
+
+    // Drop `world_name` and add a key the schema does not know.
+    let manifest_path = world_layout.world_root.join("world.toml");
+    let text = fs::read_to_string(&manifest_path).expect("read world manifest");
+    let text: String = text
+        .lines()
+        .filter(|line| !line.starts_with("world_name"))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    let text = format!("legacy_owner = \"someone\"\n{text}");
+    fs::write(&manifest_path, &text).expect("write world manifest");
+    let tile_bytes = fs::read(&tile_path).expect("read tile");
+
+    let plan = world::migrations::plan_project_migration(temp.path()).expect("plan");
+    assert!(!plan.is_noop());
+    assert!(!plan.has_errors());
+    assert!(!plan.project.needs_rewrite());
+    assert_eq!(plan.worlds.len(), 1);
+    let world = &plan.worlds[0];
+    assert_eq!(world.manifest.defaulted, vec!["world_name".to_string()]);
+    assert_eq!(world.manifest.dropped, vec!["legacy_owner".to_string()]);
+    assert_eq!(world.tiles_scanned, 1);
+    assert_eq!(world.sections.len(), 1);
+    assert_eq!(world.sections[0].tag, "LIQD");
+    assert_eq!((world.sections[0].from, world.sections[0].to), (1, 2));
+    assert_eq!(world.sections[0].notes.len(), 1);
+    serde_json::to_string(&plan).expect("serialize plan");
+
+    assert_eq!(fs::read_to_string(&manifest_path).unwrap(), text);
+    assert_eq!(fs::read(&tile_path).unwrap(), tile_bytes);
+}
+
 #[test]
 fn validator_flags_newer_manifest_version() {
     let temp = tempdir().expect("tempdir");
@@ -135,6 +549,108 @@ fn validator_quarantines_corrupt_tile() {
     );
 }
 
+#[test]
+fn quarantined_tiles_can_be_listed_inspected_restored_and_purged() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let tile_id = TileId {
+        coord: TileCoord { x: 1, y: 0 },
+    };
+    let tile_path = tile_container_path(&world_layout, "region_0", tile_id);
+    fs::create_dir_all(tile_path.parent().expect("tiles dir")).expect("create tiles dir");
+    fs::write(&tile_path, b"not-a-tile").expect("write corrupt tile");
+
+    world::validator::validate_project_and_quarantine(temp.path());
+    assert!(!tile_path.exists());
+    let entries = list_quarantined(&world_layout).expect("list quarantine");
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.region_id, "region_0");
+    assert_eq!(entry.tile, tile_id.coord);
+    assert_eq!(entry.kind, QuarantineKind::TileFile);
+    assert_eq!(entry.reason.as_deref(), Some("tile header read failed"));
+    assert!(entry.details[0].contains("TILE_HEADER_READ_FAILED"));
+
+    let issues = world::validator::validate_quarantined_tile(
+        temp.path(),
+        "world_0",
+        entry,
+        &AddxRegistry::default(),
+    )
+    .expect("inspect quarantined tile");
+    assert_eq!(
+        issues[0].code,
+        world::validator::codes::TILE_HEADER_READ_FAILED
+    );
+
+    // A tile written at the original location since is not silently replaced.
+    fs::write(&tile_path, b"newer-tile").expect("write newer tile");
+    let outcome = restore_quarantined(&world_layout, entry, false).expect("restore");
+    assert_eq!(
+        outcome,
+        RestoreOutcome::Conflict {
+            existing: tile_path.clone()
+        }
+    );
+    assert_eq!(fs::read(&tile_path).expect("read tile"), b"newer-tile");
+
+    let outcome = restore_quarantined(&world_layout, entry, true).expect("restore");
+    assert_eq!(outcome, RestoreOutcome::Restored(tile_path.clone()));
+    assert_eq!(fs::read(&tile_path).expect("read tile"), b"not-a-tile");
+    let entries = list_quarantined(&world_layout).expect("list quarantine");
+    assert_eq!(entries.len(), 1, "the replaced tile is quarantined");
+    assert!(entries[0]
+        .reason
+        .as_deref()
+        .is_some_and(|reason| reason.starts_with("replaced by restoring")));
+    assert_eq!(fs::read(&entries[0].path).expect("read"), b"newer-tile");
+
+    // A manifest region that is not a plain folder name is ignored in favour of the layout, and
+    // a restore never joins such a region into the target path.
+    let manifest_path = world_layout
+        .quarantine_dir
+        .join(&entries[0].id)
+        .join(QUARANTINE_MANIFEST_FILE);
+    let manifest = fs::read_to_string(&manifest_path).expect("read manifest");
+    assert!(manifest.contains("region_id = \"region_0\""), "{manifest}");
+    fs::write(
+        &manifest_path,
+        manifest.replace("region_id = \"region_0\"", "region_id = \"../../..\""),
+    )
+    .expect("edit manifest");
+    let entries = list_quarantined(&world_layout).expect("list quarantine");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].region_id, "region_0");
+    assert_eq!(entries[0].reason, None);
+    let mut escaping = entries[0].clone();
+    escaping.region_id = "../../..".to_string();
+    assert!(restore_quarantined(&world_layout, &escaping, true).is_err());
+    assert!(entries[0].path.exists());
+
+    // A damaged manifest does not hide the entry either.
+    fs::write(&manifest_path, "region_id = [").expect("corrupt manifest");
+    let entries = list_quarantined(&world_layout).expect("list quarantine");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].tile, tile_id.coord);
+    assert_eq!(entries[0].reason, None);
+
+    purge_quarantined(&world_layout, &entries[0]).expect("purge");
+    assert!(list_quarantined(&world_layout)
+        .expect("list quarantine")
+        .is_empty());
+}
+
 fn has_quarantined_tile(root: &Path) -> bool {
     let Ok(timestamps) = fs::read_dir(root) else {
         return false;
@@ -0,0 +1,149 @@
+use tempfile::tempdir;
+use world::tile_container::inspect::{diff_tiles, inspect_tile, SectionDiffStatus, SectionSummary};
+use world::tile_container::{
+    encode_hmap, encode_liqd, encode_prop, HmapSection, LiqdBody, LiqdKind, LiqdSection,
+    PropSection, TileContainerHeader, TileContainerPatch, TileContainerWriter, TileSectionPayload,
+    TileSectionTag, CODEC_LZ4, CODEC_RAW,
+};
+
+fn hmap_payload(codec: u16) -> TileSectionPayload {
+    TileSectionPayload {
+        tag: TileSectionTag::HMAP,
+        section_version: 1,
+        codec,
+        flags: 0,
+        decoded: encode_hmap(&HmapSection {
+            width: 2,
+            height: 2,
+            samples: vec![-1.0, 1.0, 2.0, 6.0],
+        }),
+    }
+}
+
+#[test]
+fn inspect_summarizes_sections() {
+    let temp = tempdir().expect("tempdir");
+    let path = temp.path().join("x0_y0.tile");
+    let mut writer = TileContainerWriter::new();
+    writer.add_section(hmap_payload(CODEC_LZ4));
+    writer
+        .write(&path, TileContainerHeader::new(3, -4, 1, 2))
+        .expect("write tile");
+
+    let inspection = inspect_tile(&path).expect("inspect tile");
+    assert_eq!(inspection.header.tile_x, 3);
+    assert_eq!(inspection.header.tile_y, -4);
+    assert_eq!(inspection.sections.len(), 1);
+    let hmap = &inspection.sections[0];
+    assert_eq!(hmap.tag, "HMAP");
+    assert_eq!(hmap.codec, "lz4");
+    assert!(hmap.crc_ok);
+    assert_eq!(
+        hmap.summary,
+        Some(SectionSummary::Hmap {
+            width: 2,
+            height: 2,
+            encoding: "F32".to_string(),
+            min: -1.0,
+            max: 6.0,
+            mean: 2.0,
+        })
+    );
+    let json = serde_json::to_value(&inspection).expect("serialize inspection");
+    assert_eq!(json["sections"][0]["summary"]["kind"], "hmap");
+}
+
+#[test]
+fn diff_reports_section_changes() {
+    let temp = tempdir().expect("tempdir");
+    let left = temp.path().join("left.tile");
+    let right = temp.path().join("right.tile");
+    let header = TileContainerHeader::new(0, 0, 1, 2);
+
+    let mut writer = TileContainerWriter::new();
+    writer.add_section(hmap_payload(CODEC_RAW));
+    writer.write(&left, header).expect("write left");
+    let mut writer = TileContainerWriter::new();
+    writer.add_section(hmap_payload(CODEC_LZ4));
+    writer.write(&right, header).expect("write right");
+
+    let mut patch = TileContainerPatch::new();
+    patch.replace_section(TileSectionPayload {
+        tag: TileSectionTag::PROP,
+        section_version: 1,
+        codec: CODEC_RAW,
+        flags: 0,
+        decoded: encode_prop(&PropSection {
+            instances: Vec::new(),
+        })
+        .expect("encode prop"),
+    });
+    patch.apply(&right).expect("patch right");
+
+    assert!(diff_tiles(&left, &left).expect("diff").is_identical());
+
+    // Re-saving the same content only moves the header timestamp.
+    let resaved = temp.path().join("resaved.tile");
+    let mut writer = TileContainerWriter::new();
+    writer.add_section(hmap_payload(CODEC_RAW));
+    let mut later = header;
+    later.created_timestamp += 1;
+    writer.write(&resaved, later).expect("write resaved");
+    let diff = diff_tiles(&left, &resaved).expect("diff");
+    assert_eq!(diff.header.len(), 1);
+    assert_eq!(diff.header[0].field, "created_timestamp");
+    assert!(diff.is_identical());
+
+    let diff = diff_tiles(&left, &right).expect("diff");
+    assert!(!diff.is_identical());
+    let hmap = diff.sections.iter().find(|s| s.tag == "HMAP").unwrap();
+    assert_eq!(hmap.status, SectionDiffStatus::Changed);
+    let fields: Vec<&str> = hmap.changes.iter().map(|c| c.field.as_str()).collect();
+    assert!(fields.contains(&"codec"));
+    assert!(!fields.contains(&"decoded"), "decoded bytes are identical");
+    let prop = diff.sections.iter().find(|s| s.tag == "PROP").unwrap();
+    assert_eq!(prop.status, SectionDiffStatus::Added);
+}
+
+#[test]
+fn liqd_summary_counts_cells_per_body() {
+    let temp = tempdir().expect("tempdir");
+    let path = temp.path().join("x0_y0.tile");
+    let body = |id| LiqdBody {
+        id,
+        height: 1.0,
+        kind: LiqdKind::Water,
+    };
+    // Mask values index the body list: body 0 owns two cells, body 1 one, and 7 is out of range.
+    let liqd = LiqdSection {
+        width: 2,
+        height: 2,
+        mask: vec![0, 1, 0, 7],
+        bodies: vec![body(10), body(11)],
+    };
+    let mut writer = TileContainerWriter::new();
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::LIQD,
+        section_version: 2,
+        codec: CODEC_RAW,
+        flags: 0,
+        decoded: encode_liqd(&liqd).expect("encode liqd"),
+    });
+    writer
+        .write(&path, TileContainerHeader::new(0, 0, 1, 2))
+        .expect("write tile");
+
+    let inspection = inspect_tile(&path).expect("inspect tile");
+    let Some(SectionSummary::Liqd {
+        wet_cells, bodies, ..
+    }) = &inspection.sections[0].summary
+    else {
+        panic!(
+            "expected LIQD summary: {:?}",
+            inspection.sections[0].summary
+        );
+    };
+    assert_eq!(*wet_cells, 3);
+    let cells: Vec<(u32, usize)> = bodies.iter().map(|body| (body.id, body.cells)).collect();
+    assert_eq!(cells, vec![(10, 2), (11, 1)]);
+}
@@ -5,9 +5,9 @@ use tempfile::tempdir;
 use world::schema::WORLD_FORMAT_VERSION;
 use world::tile_container::world_spec_hash::{hash_region, hash_world_spec, DEFAULT_WORLD_SPEC};
 use world::tile_container::{
-    decode_meta, encode_hmap, encode_meta, HmapSection, MetaSection, TileContainerHeader,
-    TileContainerReader, TileContainerWriter, TileSectionPayload, TileSectionTag,
-    DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE,
+    decode_liqd, decode_meta, encode_hmap, encode_meta, HmapSection, LiqdBody, LiqdKind,
+    LiqdSection, MetaSection, TileContainerHeader, TileContainerReader, TileContainerWriter,
+    TileSectionPayload, TileSectionTag, DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE,
 };
 use world::{TileCoord, TileId};
 
@@ -182,3 +182,114 @@ fn tile_container_reader_rejects_out_of_bounds_section() {
         "expected bounds failure"
     );
 }
+
+#[test]
+fn tile_container_writer_rejects_unknown_codec() {
+    let temp = tempdir().expect("tempdir");
+    let region_hash = hash_region("region_0");
+    let spec_hash = hash_world_spec(DEFAULT_WORLD_SPEC);
+    let header = TileContainerHeader::new(2, 2, region_hash, spec_hash);
+
+    let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::HMAP,
+        section_version: 1,
+        codec: 0x7F,
+        flags: 0,
+        decoded: b"payload".to_vec(),
+    });
+
+    let path = temp.path().join("x2_y2.tile");
+    assert!(
+        writer.write(&path, header).is_err(),
+        "expected writer to reject unknown codec"
+    );
+}
+
+#[test]
+fn liqd_v1_payload_still_decodes() {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&0u16.to_le_bytes());
+    bytes.extend_from_slice(&[0, 1]);
+    for (id, kind) in [(7u32, 1u16), (8u32, 255u16)] {
+        bytes.extend_from_slice(&id.to_le_bytes());
+        bytes.extend_from_slice(&5.0f32.to_le_bytes());
+        bytes.extend_from_slice(&kind.to_le_bytes());
+        bytes.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    let liqd = decode_liqd(&bytes).expect("decode v1 LIQD");
+    assert_eq!(
+        liqd,
+        LiqdSection {
+            width: 2,
+            height: 1,
+            mask: vec![0, 1],
+            bodies: vec![
+                LiqdBody {
+                    id: 7,
+                    height: 5.0,
+                    kind: LiqdKind::Lava,
+                },
+                LiqdBody {
+                    id: 8,
+                    height: 5.0,
+                    kind: LiqdKind::Custom("custom".to_string()),
+                },
+            ],
+        }
+    );
+}
+
+#[test]
+fn tile_container_reader_from_bytes_borrows_sections() {
+    let temp = tempdir().expect("tempdir");
+    let region_hash = hash_region("region_0");
+    let spec_hash = hash_world_spec(DEFAULT_WORLD_SPEC);
+    let header = TileContainerHeader::new(3, 4, region_hash, spec_hash);
+    let hmap = HmapSection {
+        width: 2,
+        height: 2,
+        samples: vec![0.0, 1.0, 2.0, 3.0],
+    };
+
+    let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::HMAP,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_hmap(&hmap),
+    });
+    let path = temp.path().join("x3_y4.tile");
+    writer.write(&path, header).expect("write tile");
+
+    let mut bytes = std::fs::read(&path).expect("read tile bytes");
+    let reader = TileContainerReader::from_bytes(bytes.clone()).expect("parse tile");
+    assert!(reader.is_buffered());
+    let borrowed = reader
+        .section_bytes(TileSectionTag::HMAP)
+        .expect("borrow section");
+    assert_eq!(borrowed, encode_hmap(&hmap).as_slice());
+    assert!(matches!(
+        reader.decode_section_cow(TileSectionTag::HMAP).unwrap(),
+        std::borrow::Cow::Borrowed(_)
+    ));
+
+    let file_reader = TileContainerReader::open(&path).expect("open tile");
+    assert!(file_reader.section_bytes(TileSectionTag::HMAP).is_err());
+    assert_eq!(
+        file_reader.decode_section(TileSectionTag::HMAP).unwrap(),
+        borrowed
+    );
+
+    let offset = reader.section(TileSectionTag::HMAP).unwrap().offset as usize;
+    bytes[offset] ^= 0xFF;
+    let corrupt = TileContainerReader::from_bytes(bytes).expect("parse tile");
+    assert!(corrupt.decode_section(TileSectionTag::HMAP).is_err());
+}
@@ -2,11 +2,13 @@ use tempfile::tempdir;
 use world::schema::WORLD_FORMAT_VERSION;
 use world::tile_container::world_spec_hash::{hash_region, hash_world_spec, DEFAULT_WORLD_SPEC};
 use world::tile_container::{
-    decode_hmap, decode_meta, encode_hmap, encode_meta, HmapSection, MetaSection,
+    decode_hmap, decode_liqd, decode_meta, decode_spln, encode_hmap, encode_hmap_with, encode_liqd,
+    encode_meta, encode_spln, hmap_encoding, hmap_quantization_error, HmapEncoding, HmapSection,
+    LiqdBody, LiqdKind, LiqdSection, MetaSection, SplinePoint, SplineRecord, SplnSection,
     TileContainerHeader, TileContainerReader, TileContainerWriter, TileSectionPayload,
-    TileSectionTag, DEFAULT_ALIGNMENT,
+    TileSectionTag, CODEC_LZ4, CODEC_RAW, CODEC_ZSTD, DEFAULT_ALIGNMENT, SECTION_FLAG_CHUNKED,
 };
-use world::{TileCoord, TileId};
+use world::{ChunkCoord, TileCoord, TileId};
 
 #[test]
 fn tile_container_roundtrip() {
@@ -108,3 +110,282 @@ fn tile_container_deterministic_output() {
     let bytes_b = std::fs::read(&path_b).expect("read tile b");
     assert_eq!(bytes_a, bytes_b);
 }
+
+#[test]
+fn tile_container_codecs_roundtrip() {
+    let temp = tempdir().expect("tempdir");
+    let region = "region_0";
+    let tile_id = TileId {
+        coord: TileCoord { x: 3, y: 1 },
+    };
+    let region_hash = hash_region(region);
+    let spec_hash = hash_world_spec(DEFAULT_WORLD_SPEC);
+    let header = TileContainerHeader::new(tile_id.coord.x, tile_id.coord.y, region_hash, spec_hash);
+
+    let hmap = HmapSection {
+        width: 33,
+        height: 33,
+        samples: (0..33 * 33)
+            .map(|index| (index % 33) as f32 * 0.5)
+            .collect(),
+    };
+    let decoded = encode_hmap(&hmap);
+
+    for codec in [CODEC_RAW, CODEC_LZ4, CODEC_ZSTD] {
+        let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+        writer.add_section(TileSectionPayload {
+            tag: TileSectionTag::HMAP,
+            section_version: 1,
+            codec,
+            flags: 0,
+            decoded: decoded.clone(),
+        });
+
+        let path = temp.path().join(format!("x3_y1_codec{codec}.tile"));
+        writer.write(&path, header).expect("write tile");
+
+        let reader = TileContainerReader::open(&path).expect("read tile");
+        let entry = reader.section(TileSectionTag::HMAP).expect("HMAP entry");
+        assert_eq!(entry.codec, codec);
+        assert_eq!(entry.decoded_len, decoded.len() as u64);
+        if codec != CODEC_RAW {
+            assert!(entry.stored_len < entry.decoded_len, "expected compression");
+        }
+
+        let read_hmap = decode_hmap(&reader.decode_section(TileSectionTag::HMAP).unwrap()).unwrap();
+        assert_eq!(read_hmap, hmap);
+    }
+}
+
+#[test]
+fn hmap_quantized_encodings_roundtrip_within_tolerance() {
+    let width = 17u16;
+    let hmap = HmapSection {
+        width,
+        height: 9,
+        samples: (0..17 * 9)
+            .map(|index| (index % 17) as f32 * 1.25 - 40.0)
+            .collect(),
+    };
+    let tolerance = (20.0 + 40.0) / f32::from(u16::MAX);
+
+    for encoding in [HmapEncoding::Quantized16, HmapEncoding::RowDelta16] {
+        let bytes = encode_hmap_with(&hmap, encoding).expect("encode hmap");
+        assert_eq!(hmap_encoding(&bytes).expect("encoding"), encoding);
+        assert!(bytes.len() < encode_hmap(&hmap).len() / 2 + 32);
+
+        let decoded = decode_hmap(&bytes).expect("decode hmap");
+        assert_eq!(decoded.width, hmap.width);
+        assert_eq!(decoded.height, hmap.height);
+        for (read, original) in decoded.samples.iter().zip(&hmap.samples) {
+            assert!(
+                (read - original).abs() <= tolerance,
+                "sample {read} differs from {original}"
+            );
+        }
+    }
+}
+
+#[test]
+fn hmap_quantized_encoding_flat_tile_is_exact() {
+    let hmap = HmapSection {
+        width: 3,
+        height: 3,
+        samples: vec![12.5; 9],
+    };
+    let bytes = encode_hmap_with(&hmap, HmapEncoding::RowDelta16).expect("encode hmap");
+    assert_eq!(decode_hmap(&bytes).expect("decode hmap"), hmap);
+
+    // Non-finite samples, or a range whose `max - min` overflows, would give params the
+    // decoder rejects; both quantized encoders refuse them instead.
+    let with = |edits: &[(usize, f32)]| {
+        let mut samples = hmap.samples.clone();
+        for (index, sample) in edits {
+            samples[*index] = *sample;
+        }
+        HmapSection {
+            samples,
+            ..hmap.clone()
+        }
+    };
+    let invalid = [
+        with(&[(4, f32::NAN)]),
+        with(&[(4, f32::INFINITY)]),
+        with(&[(0, -f32::MAX), (8, f32::MAX)]),
+        HmapSection {
+            samples: vec![f32::NAN; 9],
+            ..hmap.clone()
+        },
+    ];
+    for encoding in [HmapEncoding::Quantized16, HmapEncoding::RowDelta16] {
+        for hmap in &invalid {
+            assert!(
+                encode_hmap_with(hmap, encoding).is_err(),
+                "{encoding:?} accepted {:?}",
+                hmap.samples
+            );
+        }
+    }
+    // The widest finite range still encodes and decodes.
+    let wide = with(&[(0, -f32::MAX / 2.0), (8, f32::MAX / 2.0)]);
+    let bytes = encode_hmap_with(&wide, HmapEncoding::RowDelta16).expect("encode wide hmap");
+    assert!(hmap_quantization_error(&bytes).is_ok());
+    let decoded = decode_hmap(&bytes).expect("decode wide hmap");
+    assert_eq!(decoded.samples[0], wide.samples[0]);
+}
+
+#[test]
+fn spln_section_roundtrip_through_container() {
+    let temp = tempdir().expect("tempdir");
+    let region_hash = hash_region("region_0");
+    let spec_hash = hash_world_spec(DEFAULT_WORLD_SPEC);
+    let header = TileContainerHeader::new(5, 6, region_hash, spec_hash);
+
+    let point = |x: f32, width: f32| SplinePoint {
+        position: [x, 10.0, -x],
+        width,
+        tangent_in: [-1.0, 0.0, 0.0],
+        tangent_out: [1.0, 0.0, 0.0],
+    };
+    let road = SplineRecord {
+        id: 2,
+        closed: false,
+        tags: vec!["road".to_string(), "asphalt".to_string()],
+        points: vec![point(0.0, 6.0), point(32.0, 6.0), point(64.0, 4.5)],
+    };
+    let river = SplineRecord {
+        id: 1,
+        closed: true,
+        tags: vec!["river".to_string()],
+        points: vec![point(5.0, 12.0), point(9.0, 14.0)],
+    };
+    let spln = SplnSection {
+        splines: vec![road.clone(), river.clone()],
+    };
+
+    let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::SPLN,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_spln(&spln).expect("encode spln"),
+    });
+
+    let path = temp.path().join("x5_y6.tile");
+    writer.write(&path, header).expect("write tile");
+
+    let reader = TileContainerReader::open(&path).expect("read tile");
+    let read_spln = decode_spln(&reader.decode_section(TileSectionTag::SPLN).unwrap()).unwrap();
+    assert_eq!(
+        read_spln,
+        SplnSection {
+            splines: vec![river, road],
+        }
+    );
+}
+
+#[test]
+fn chunked_grid_sections_roundtrip_and_read_per_chunk() {
+    let temp = tempdir().expect("tempdir");
+    let region_hash = hash_region("region_0");
+    let spec_hash = hash_world_spec(DEFAULT_WORLD_SPEC);
+    let header = TileContainerHeader::new(0, 0, region_hash, spec_hash);
+
+    // 2x2 chunks: 9x9 samples share edge rows/columns, 8x8 liquid cells do not.
+    let hmap = HmapSection {
+        width: 9,
+        height: 9,
+        samples: (0..81).map(|index| index as f32).collect(),
+    };
+    let liqd = LiqdSection {
+        width: 8,
+        height: 8,
+        mask: (0..64).map(|index| (index % 2) as u8).collect(),
+        bodies: vec![
+            LiqdBody {
+                id: 1,
+                height: 2.0,
+                kind: LiqdKind::Water,
+            },
+            LiqdBody {
+                id: 2,
+                height: 3.0,
+                kind: LiqdKind::Custom("swamp".to_string()),
+            },
+        ],
+    };
+
+    let mut writer = TileContainerWriter::new()
+        .alignment(DEFAULT_ALIGNMENT)
+        .chunks_per_tile(2);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::HMAP,
+        section_version: 1,
+        codec: CODEC_LZ4,
+        flags: SECTION_FLAG_CHUNKED,
+        decoded: encode_hmap(&hmap),
+    });
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::LIQD,
+        section_version: 2,
+        codec: CODEC_RAW,
+        flags: SECTION_FLAG_CHUNKED,
+        decoded: encode_liqd(&liqd).expect("encode liqd"),
+    });
+
+    let path = temp.path().join("x0_y0.tile");
+    writer.write(&path, header).expect("write tile");
+
+    for reader in [
+        TileContainerReader::open(&path).expect("open tile"),
+        TileContainerReader::open_buffered(&path).expect("open buffered tile"),
+    ] {
+        assert!(reader.is_chunked(TileSectionTag::HMAP));
+        let read_hmap = decode_hmap(&reader.decode_section(TileSectionTag::HMAP).unwrap()).unwrap();
+        assert_eq!(read_hmap, hmap);
+        let read_liqd = decode_liqd(&reader.decode_section(TileSectionTag::LIQD).unwrap()).unwrap();
+        assert_eq!(read_liqd, liqd);
+
+        let chunk = reader
+            .read_chunk(TileSectionTag::HMAP, ChunkCoord { x: 1, y: 0 })
+            .expect("read HMAP chunk");
+        assert_eq!((chunk.width, chunk.height), (5, 5));
+        let samples = chunk.samples_f32().expect("f32 samples");
+        assert_eq!(samples[0], 4.0);
+        assert_eq!(samples[5], 13.0);
+        assert_eq!(samples[24], 44.0);
+
+        let chunk = reader
+            .read_chunk(TileSectionTag::LIQD, ChunkCoord { x: 0, y: 1 })
+            .expect("read LIQD chunk");
+        assert_eq!((chunk.width, chunk.height), (4, 4));
+        assert_eq!(chunk.data, [0, 1, 0, 1].repeat(4));
+
+        assert!(reader
+            .read_chunk(TileSectionTag::HMAP, ChunkCoord { x: 2, y: 0 })
+            .is_err());
+    }
+}
+
+#[test]
+fn chunked_section_requires_divisible_grid() {
+    let temp = tempdir().expect("tempdir");
+    let header = TileContainerHeader::new(0, 0, 0, 0);
+    let hmap = HmapSection {
+        width: 4,
+        height: 4,
+        samples: vec![0.0; 16],
+    };
+    let mut writer = TileContainerWriter::new().chunks_per_tile(2);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::HMAP,
+        section_version: 1,
+        codec: CODEC_RAW,
+        flags: SECTION_FLAG_CHUNKED,
+        decoded: encode_hmap(&hmap),
+    });
+    assert!(writer
+        .write(temp.path().join("x0_y0.tile"), header)
+        .is_err());
+}
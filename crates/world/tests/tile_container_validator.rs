@@ -2,16 +2,28 @@ use std::fs::OpenOptions;
 use std::io::{Seek, SeekFrom, Write};
 
 use tempfile::tempdir;
+use world::assets::scan_asset_catalog;
 use world::schema::{
-    ProjectManifest, RegionBounds, RegionManifest, WorldManifest, WORLD_FORMAT_VERSION,
+    ProjectManifest, RegionBounds, RegionManifest, WorldManifest, WorldSpec, WORLD_FORMAT_VERSION,
+};
+use world::storage::{create_project, create_world, tile_container_path, WorldLayout};
+use world::tile_container::extensions::{AddxExtension, AddxRegistry};
+use world::tile_container::world_spec_hash::{
+    hash_region, hash_world_spec, hash_world_spec_from_manifest, DEFAULT_WORLD_SPEC,
 };
-use world::storage::{create_project, create_world, tile_container_path};
-use world::tile_container::world_spec_hash::{hash_region, hash_world_spec, DEFAULT_WORLD_SPEC};
 use world::tile_container::{
-    encode_meta, encode_prop, MetaSection, PropSection, TileContainerHeader, TileContainerReader,
-    TileContainerWriter, TileSectionPayload, TileSectionTag, DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE,
+    decode_hmap, decode_liqd, decode_prop, decode_wmap, encode_addx, encode_hmap, encode_hmap_with,
+    encode_liqd, encode_meta, encode_prop, encode_wmap, AddxSection, HmapEncoding, HmapSection,
+    LiqdBody, LiqdKind, LiqdSection, MetaSection, PropRecord, PropSection, TileContainerHeader,
+    TileContainerReader, TileContainerWriter, TileSectionPayload, TileSectionTag, WmapSection,
+    DEFAULT_ALIGNMENT, DIR_ENTRY_SIZE,
+};
+use world::validator::{
+    codes, find_instance_collisions, find_world_seams, fix_project, junit_report,
+    list_project_tiles, reassign_duplicate_instances, repair_world_seams, sarif_report,
+    validate_project_with_options, SeamAxis, Severity, ValidationOptions, DEFAULT_SEAM_TOLERANCE,
 };
-use world::{TileCoord, TileId};
+use world::{AssetId, InstanceId, TileCoord, TileId};
 
 #[test]
 fn tile_container_overlap_detected_by_validator() {
@@ -157,3 +169,1056 @@ fn tile_container_bounds_detected_by_validator() {
         "expected bounds issue"
     );
 }
+
+#[test]
+fn addx_unknown_keys_skipped_and_registered_keys_checked() {
+    let temp = tempdir().expect("tempdir");
+    let project_manifest = ProjectManifest::default();
+    let project_layout = create_project(temp.path(), &project_manifest).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+
+    let region = "region_0";
+    let tile_id = TileId {
+        coord: TileCoord { x: 1, y: 1 },
+    };
+    let region_hash = hash_region(region);
+    let spec_hash = hash_world_spec(DEFAULT_WORLD_SPEC);
+    let header = TileContainerHeader::new(tile_id.coord.x, tile_id.coord.y, region_hash, spec_hash);
+
+    let meta = MetaSection {
+        format_version: WORLD_FORMAT_VERSION,
+        tile_id,
+        region_hash,
+        created_timestamp: 0,
+    };
+    let mut addx = AddxSection::default();
+    addx.insert("studio:spawn_points", 2, vec![1, 2, 3]);
+    addx.insert("vendor:audio_zones", 7, b"opaque".to_vec());
+
+    let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::META,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_meta(&meta),
+    });
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::ADDX,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_addx(&addx).expect("encode addx"),
+    });
+
+    let path = tile_container_path(&world_layout, region, tile_id);
+    writer.write(&path, header).expect("write tile");
+
+    let issues = world::validator::validate_project(temp.path());
+    assert!(
+        !issues.iter().any(|issue| issue.message.contains("ADDX")),
+        "unknown ADDX keys must be skipped: {issues:?}"
+    );
+
+    let mut registry = AddxRegistry::new();
+    registry
+        .register(
+            AddxExtension::new("studio:spawn_points", 1, 2).with_validator(|_, data| {
+                if data.len() % 4 != 0 {
+                    anyhow::bail!("spawn point data must be 4-byte aligned");
+                }
+                Ok(())
+            }),
+        )
+        .expect("register extension");
+    let issues = world::validator::validate_project_with_extensions(temp.path(), &registry, false);
+    assert!(
+        issues.iter().any(|issue| issue
+            .message
+            .contains("ADDX entry studio:spawn_points invalid")),
+        "expected registered ADDX entry to be checked"
+    );
+    assert!(!issues
+        .iter()
+        .any(|issue| issue.message.contains("vendor:audio_zones")));
+}
+
+#[test]
+fn issues_carry_severity_code_tile_and_section() {
+    let temp = tempdir().expect("tempdir");
+    let project_manifest = ProjectManifest::default();
+    let project_layout = create_project(temp.path(), &project_manifest).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    std::fs::create_dir_all(world_layout.regions_dir.join("stray")).expect("stray region");
+
+    let region = "region_0";
+    let tile_id = TileId {
+        coord: TileCoord { x: 2, y: -1 },
+    };
+    let region_hash = hash_region(region);
+    let spec_hash = hash_world_spec(DEFAULT_WORLD_SPEC);
+    let header = TileContainerHeader::new(tile_id.coord.x, tile_id.coord.y, region_hash, spec_hash);
+    let meta = MetaSection {
+        format_version: WORLD_FORMAT_VERSION,
+        tile_id,
+        region_hash,
+        created_timestamp: 0,
+    };
+    let hmap = HmapSection {
+        width: 3,
+        height: 3,
+        samples: vec![0.0; 9],
+    };
+
+    let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::META,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_meta(&meta),
+    });
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::HMAP,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_hmap(&hmap),
+    });
+    let path = tile_container_path(&world_layout, region, tile_id);
+    writer.write(&path, header).expect("write tile");
+
+    let issues = world::validator::validate_project(temp.path());
+    let stray = issues
+        .iter()
+        .find(|issue| issue.code == codes::REGION_NOT_LISTED)
+        .expect("stray region reported");
+    assert_eq!(stray.severity, Severity::Warning);
+    assert_eq!(stray.tile, None);
+
+    let dims = issues
+        .iter()
+        .find(|issue| issue.code == codes::HMAP_DIM_MISMATCH)
+        .expect("HMAP dimension issue");
+    assert_eq!(dims.severity, Severity::Error);
+    assert_eq!(dims.tile, Some(tile_id.coord));
+    assert_eq!(dims.section.as_deref(), Some("HMAP"));
+    assert_eq!(
+        world::validator::max_severity(&issues),
+        Some(Severity::Error)
+    );
+
+    let json = world::validator::validate_project_json(temp.path()).expect("json");
+    assert!(json.contains("\"code\": \"HMAP_DIM_MISMATCH\""));
+    assert!(json.contains("\"severity\": \"warning\""));
+
+    // Only errors quarantine: the tile goes, the stray region directory stays.
+    world::validator::validate_project_and_quarantine(temp.path());
+    assert!(!path.exists());
+    assert!(world_layout.regions_dir.join("stray").exists());
+}
+
+const SEAM_SAMPLES: u16 = 5;
+
+/// Height of a continuous slope at global sample (gx, gy), so untouched seams match exactly.
+fn seam_height(tile: TileCoord, x: u16, y: u16) -> f32 {
+    let step = (SEAM_SAMPLES - 1) as i32;
+    let gx = tile.x * step + x as i32;
+    let gy = tile.y * step + y as i32;
+    gx as f32 + gy as f32 * 0.5
+}
+
+fn write_hmap_tile(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+    coord: TileCoord,
+    edit: impl Fn(u16, u16, f32) -> f32,
+) {
+    write_hmap_tile_with(layout, manifest, coord, HmapEncoding::F32, edit);
+}
+
+fn write_hmap_tile_with(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+    coord: TileCoord,
+    encoding: HmapEncoding,
+    edit: impl Fn(u16, u16, f32) -> f32,
+) {
+    let region = "region_0";
+    let tile_id = TileId { coord };
+    let region_hash = hash_region(region);
+    let header = TileContainerHeader::new(
+        coord.x,
+        coord.y,
+        region_hash,
+        hash_world_spec_from_manifest(manifest),
+    );
+    let meta = MetaSection {
+        format_version: WORLD_FORMAT_VERSION,
+        tile_id,
+        region_hash,
+        created_timestamp: 0,
+    };
+    let mut samples = Vec::new();
+    for y in 0..SEAM_SAMPLES {
+        for x in 0..SEAM_SAMPLES {
+            samples.push(edit(x, y, seam_height(coord, x, y)));
+        }
+    }
+    let hmap = HmapSection {
+        width: SEAM_SAMPLES,
+        height: SEAM_SAMPLES,
+        samples,
+    };
+
+    let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::META,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_meta(&meta),
+    });
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::HMAP,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_hmap_with(&hmap, encoding).expect("encode HMAP"),
+    });
+    writer
+        .write(tile_container_path(layout, region, tile_id), header)
+        .expect("write tile");
+}
+
+fn read_hmap(layout: &WorldLayout, coord: TileCoord) -> HmapSection {
+    let path = tile_container_path(layout, "region_0", TileId { coord });
+    let reader = TileContainerReader::open(path).expect("read tile");
+    decode_hmap(&reader.decode_section(TileSectionTag::HMAP).expect("HMAP")).expect("decode")
+}
+
+#[test]
+fn hmap_seams_are_reported_and_repaired() {
+    let temp = tempdir().expect("tempdir");
+    let project_manifest = ProjectManifest::default();
+    let project_layout = create_project(temp.path(), &project_manifest).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            heightfield_samples: SEAM_SAMPLES,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+
+    let tiles = [
+        TileCoord { x: 0, y: 0 },
+        TileCoord { x: 1, y: 0 },
+        TileCoord { x: 0, y: 1 },
+        TileCoord { x: 1, y: 1 },
+    ];
+    for coord in tiles {
+        // Raise the west edge of (1, 0), including the corner it shares with the other three.
+        let raised = coord == tiles[1];
+        write_hmap_tile(&world_layout, &world_manifest, coord, |x, _, h| {
+            if raised && x == 0 {
+                h + 0.25
+            } else {
+                h
+            }
+        });
+    }
+
+    let issues = world::validator::validate_project(temp.path());
+    let seams: Vec<_> = issues
+        .iter()
+        .filter(|issue| issue.code == codes::HMAP_SEAM_MISMATCH)
+        .collect();
+    assert_eq!(seams.len(), 2, "{issues:?}");
+    assert!(seams
+        .iter()
+        .all(|issue| issue.severity == Severity::Warning));
+    let west = seams
+        .iter()
+        .find(|issue| issue.tile == Some(tiles[0]))
+        .expect("seam between (0, 0) and (1, 0)");
+    assert!(west
+        .message
+        .contains("with tile (1, 0) differs by up to 0.250 m"));
+    assert!(west.message.contains("5 samples above tolerance"));
+    assert!(issues
+        .iter()
+        .all(|issue| issue.code == codes::HMAP_SEAM_MISMATCH));
+
+    let found = find_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+        .expect("find seams");
+    let below = found
+        .iter()
+        .find(|seam| seam.tile == tiles[1])
+        .expect("seam between (1, 0) and (1, 1)");
+    assert_eq!(below.axis, SeamAxis::Y);
+    assert_eq!(below.max_index, 0);
+    assert_eq!(below.samples_over, 1);
+
+    let report = repair_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+        .expect("repair seams");
+    assert_eq!(report.repaired.len(), 2);
+    assert_eq!(report.tiles_rewritten.len(), 4);
+    assert!(
+        find_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+            .expect("find seams")
+            .is_empty()
+    );
+    assert!(world::validator::validate_project(temp.path()).is_empty());
+
+    // Interior seam samples meet halfway; the shared corner takes the mean of all four tiles.
+    let index = |x: u16, y: u16| (y * SEAM_SAMPLES + x) as usize;
+    let last = SEAM_SAMPLES - 1;
+    let west_tile = read_hmap(&world_layout, tiles[0]);
+    let east_tile = read_hmap(&world_layout, tiles[1]);
+    let expected = seam_height(tiles[0], last, 2) + 0.125;
+    assert_eq!(west_tile.samples[index(last, 2)], expected);
+    assert_eq!(east_tile.samples[index(0, 2)], expected);
+    let corner = seam_height(tiles[0], last, last) + 0.0625;
+    assert_eq!(west_tile.samples[index(last, last)], corner);
+    assert_eq!(east_tile.samples[index(0, last)], corner);
+    assert_eq!(
+        read_hmap(&world_layout, tiles[3]).samples[index(0, 0)],
+        corner
+    );
+    // The first corner of the west seam is shared by (0, 0) and (1, 0) only.
+    assert_eq!(
+        east_tile.samples[index(0, 0)],
+        seam_height(tiles[0], last, 0) + 0.125
+    );
+}
+
+#[test]
+fn quantized_hmap_seams_allow_for_rounding_and_repair_converges() {
+    let temp = tempdir().expect("tempdir");
+    let project_manifest = ProjectManifest::default();
+    let project_layout = create_project(temp.path(), &project_manifest).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            heightfield_samples: SEAM_SAMPLES,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 0),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+
+    // Identical edges before encoding; each tile is quantized over its own range (peaks of 3000 m
+    // and 4500 m), so the decoded edges differ by up to half of both steps.
+    let west = TileCoord { x: 0, y: 0 };
+    let east = TileCoord { x: 1, y: 0 };
+    let last = SEAM_SAMPLES - 1;
+    let edge = |y: u16| 1000.0 + y as f32 * 0.37;
+    let write = |raise: f32| {
+        for (coord, peak) in [(west, 3000.0), (east, 4500.0)] {
+            write_hmap_tile_with(
+                &world_layout,
+                &world_manifest,
+                coord,
+                HmapEncoding::Quantized16,
+                |x, y, _| match (x, y) {
+                    (2, 2) => peak,
+                    (0, _) if coord == east => edge(y) + raise,
+                    (x, _) if x == last && coord == west => edge(y),
+                    _ => 0.0,
+                },
+            );
+        }
+    };
+    let max_edge_delta = || {
+        let (west, east) = (
+            read_hmap(&world_layout, west),
+            read_hmap(&world_layout, east),
+        );
+        (0..SEAM_SAMPLES as usize)
+            .map(|y| {
+                let row = y * SEAM_SAMPLES as usize;
+                (west.samples[row + last as usize] - east.samples[row]).abs()
+            })
+            .fold(0.0f32, f32::max)
+    };
+
+    write(0.0);
+    assert!(max_edge_delta() > DEFAULT_SEAM_TOLERANCE);
+    assert!(
+        find_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+            .expect("find seams")
+            .is_empty()
+    );
+    let report = repair_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+        .expect("repair seams");
+    assert!(report.tiles_rewritten.is_empty());
+
+    // A real step is still found, and once repaired stays repaired.
+    write(0.5);
+    let found = find_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+        .expect("find seams");
+    assert_eq!(found.len(), 1);
+    let report = repair_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+        .expect("repair seams");
+    assert_eq!(report.tiles_rewritten.len(), 2);
+    assert!(max_edge_delta() < 0.2);
+    assert!(
+        find_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+            .expect("find seams")
+            .is_empty()
+    );
+    let report = repair_world_seams(&world_layout, &world_manifest, DEFAULT_SEAM_TOLERANCE)
+        .expect("repair seams");
+    assert!(report.tiles_rewritten.is_empty());
+}
+
+#[test]
+fn cached_parallel_validation_matches_and_detects_changes() {
+    let temp = tempdir().expect("tempdir");
+    let project_manifest = ProjectManifest::default();
+    let project_layout = create_project(temp.path(), &project_manifest).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            heightfield_samples: SEAM_SAMPLES,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let tiles = [
+        TileCoord { x: 0, y: 0 },
+        TileCoord { x: 1, y: 0 },
+        TileCoord { x: 0, y: 1 },
+        TileCoord { x: 1, y: 1 },
+    ];
+    for coord in tiles {
+        let raised = coord == tiles[1];
+        write_hmap_tile(&world_layout, &world_manifest, coord, |x, _, h| {
+            if raised && x == 0 {
+                h + 0.25
+            } else {
+                h
+            }
+        });
+    }
+
+    let registry = AddxRegistry::default();
+    let options = ValidationOptions {
+        cache: true,
+        threads: 3,
+        ..ValidationOptions::default()
+    };
+    let render = |issues: &[world::validator::ValidationIssue]| {
+        serde_json::to_string(issues).expect("serialize issues")
+    };
+    let uncached = render(&world::validator::validate_project(temp.path()));
+    let first = render(&validate_project_with_options(
+        temp.path(),
+        &registry,
+        options,
+    ));
+    assert_eq!(first, uncached);
+
+    let cache_file = project_layout
+        .cache_dir
+        .join("validation")
+        .join("world_0.json");
+    let cache: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&cache_file).expect("cache written"))
+            .expect("cache is JSON");
+    assert_eq!(cache["tiles"].as_object().map(|tiles| tiles.len()), Some(4));
+    assert!(cache["passes"].get("seams/region_0").is_some());
+    assert!(cache["passes"].get("instances").is_some());
+
+    let second = render(&validate_project_with_options(
+        temp.path(),
+        &registry,
+        options,
+    ));
+    assert_eq!(second, first);
+
+    // Rewriting a tile changes its directory CRCs, so the stale entry must not be reused.
+    write_hmap_tile(&world_layout, &world_manifest, tiles[3], |_, _, _| 9000.0);
+    let issues = validate_project_with_options(temp.path(), &registry, options);
+    assert!(
+        issues.iter().any(|issue| {
+            issue.code == codes::HMAP_SAMPLE_INVALID && issue.tile == Some(tiles[3])
+        }),
+        "{issues:?}"
+    );
+    assert_eq!(
+        render(&issues),
+        render(&world::validator::validate_project(temp.path()))
+    );
+}
+
+fn write_prop_tile(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+    region: &str,
+    coord: TileCoord,
+    ids: &[u64],
+) {
+    let records = ids
+        .iter()
+        .map(|id| PropRecord {
+            id: InstanceId(*id),
+            asset: AssetId::new("core", "rock"),
+            translation: [*id as f32, 0.0, 0.0],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+        })
+        .collect();
+    write_prop_records(layout, manifest, region, coord, records);
+}
+
+fn write_prop_records(
+    layout: &WorldLayout,
+    manifest: &WorldManifest,
+    region: &str,
+    coord: TileCoord,
+    instances: Vec<PropRecord>,
+) {
+    let tile_id = TileId { coord };
+    let region_hash = hash_region(region);
+    let header = TileContainerHeader::new(
+        coord.x,
+        coord.y,
+        region_hash,
+        hash_world_spec_from_manifest(manifest),
+    );
+    let meta = MetaSection {
+        format_version: WORLD_FORMAT_VERSION,
+        tile_id,
+        region_hash,
+        created_timestamp: 0,
+    };
+    let props = PropSection { instances };
+
+    let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::META,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_meta(&meta),
+    });
+    writer.add_section(TileSectionPayload {
+        tag: TileSectionTag::PROP,
+        section_version: 1,
+        codec: 0,
+        flags: 0,
+        decoded: encode_prop(&props).expect("encode PROP"),
+    });
+    writer
+        .write(tile_container_path(layout, region, tile_id), header)
+        .expect("write tile");
+}
+
+fn read_prop_ids(layout: &WorldLayout, region: &str, coord: TileCoord) -> Vec<u64> {
+    let path = tile_container_path(layout, region, TileId { coord });
+    let reader = TileContainerReader::open(path).expect("read tile");
+    let prop = decode_prop(&reader.decode_section(TileSectionTag::PROP).expect("PROP"))
+        .expect("decode PROP");
+    prop.instances
+        .iter()
+        .map(|instance| instance.id.0)
+        .collect()
+}
+
+#[test]
+fn instance_ids_shared_between_tiles_are_reported_and_reassigned() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let region = |id: &str| RegionManifest {
+        region_id: id.to_string(),
+        name: id.to_string(),
+        bounds: RegionBounds::new(0, 0, 1, 1),
+    };
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![region("region_0"), region("region_1")],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let a = TileCoord { x: 0, y: 0 };
+    let b = TileCoord { x: 1, y: 0 };
+    write_prop_tile(&world_layout, &world_manifest, "region_0", a, &[1, 2, 3]);
+    write_prop_tile(&world_layout, &world_manifest, "region_0", b, &[3, 4]);
+    write_prop_tile(&world_layout, &world_manifest, "region_1", a, &[1, 5]);
+
+    let issues = world::validator::validate_project(temp.path());
+    let collisions: Vec<_> = issues
+        .iter()
+        .filter(|issue| issue.code == codes::PROP_INSTANCE_COLLISION)
+        .collect();
+    assert_eq!(collisions.len(), 4, "{issues:?}");
+    assert!(collisions.iter().all(|issue| {
+        issue.severity == Severity::Error && issue.section.as_deref() == Some("PROP")
+    }));
+    assert!(collisions.iter().any(|issue| {
+        issue.tile == Some(a)
+            && issue
+                .path
+                .as_ref()
+                .is_some_and(|path| path.starts_with(world_layout.regions_dir.join("region_0")))
+            && issue.message == "PROP instance 1 is also used by tile (0, 0) in region_1"
+    }));
+
+    let found = find_instance_collisions(&world_layout, &world_manifest).expect("index");
+    let ids: Vec<u64> = found.iter().map(|collision| collision.id.0).collect();
+    assert_eq!(ids, vec![1, 3]);
+    assert_eq!(found[0].locations[0].region_id, "region_0");
+    assert_eq!(found[0].locations[1].region_id, "region_1");
+
+    let report = reassign_duplicate_instances(&world_layout, &world_manifest).expect("reassign");
+    assert_eq!(report.reassigned.len(), 2);
+    assert_eq!(report.tiles_rewritten.len(), 2);
+    // The first location keeps each id; the others get fresh ids above the world maximum.
+    assert_eq!(read_prop_ids(&world_layout, "region_0", a), vec![1, 2, 3]);
+    assert_eq!(read_prop_ids(&world_layout, "region_1", a), vec![5, 6]);
+    assert_eq!(read_prop_ids(&world_layout, "region_0", b), vec![4, 7]);
+    assert!(find_instance_collisions(&world_layout, &world_manifest)
+        .expect("index")
+        .is_empty());
+    assert!(world::validator::validate_project(temp.path())
+        .iter()
+        .all(|issue| issue.code != codes::PROP_INSTANCE_COLLISION));
+
+    // With u64::MAX taken there is no fresh id; reassignment fails without rewriting anything.
+    write_prop_tile(&world_layout, &world_manifest, "region_0", b, &[u64::MAX]);
+    write_prop_tile(&world_layout, &world_manifest, "region_1", b, &[u64::MAX]);
+    let err =
+        reassign_duplicate_instances(&world_layout, &world_manifest).expect_err("ids exhausted");
+    assert!(err.to_string().contains("no free instance id"), "{err:#}");
+    assert_eq!(read_prop_ids(&world_layout, "region_0", b), vec![u64::MAX]);
+    assert_eq!(read_prop_ids(&world_layout, "region_1", b), vec![u64::MAX]);
+}
+
+#[test]
+fn prop_references_to_missing_assets_are_reported_per_tile() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let a = TileCoord { x: 0, y: 0 };
+    let b = TileCoord { x: 1, y: 0 };
+    write_prop_tile(&world_layout, &world_manifest, "region_0", a, &[1, 2, 3]);
+    write_prop_tile(&world_layout, &world_manifest, "region_0", b, &[4]);
+
+    // Without an assets directory, one issue names the directory instead of every tile.
+    let assets_dir = &project_layout.assets_dir;
+    std::fs::remove_dir(assets_dir).expect("remove assets dir");
+    let issues = world::validator::validate_project(temp.path());
+    assert!(issues
+        .iter()
+        .all(|issue| issue.code != codes::PROP_ASSET_MISSING));
+    let dir_missing: Vec<_> = issues
+        .iter()
+        .filter(|issue| issue.code == codes::ASSETS_DIR_MISSING)
+        .collect();
+    assert_eq!(dir_missing.len(), 1, "{issues:?}");
+    assert_eq!(
+        dir_missing[0].message,
+        "assets directory missing; PROP sections of 2 tiles reference assets"
+    );
+    assert_eq!(dir_missing[0].path.as_deref(), Some(assets_dir.as_path()));
+
+    std::fs::create_dir_all(assets_dir.join("core/trees/.cache")).expect("create asset dirs");
+    std::fs::write(assets_dir.join("core/trees/oak.glb"), b"").expect("write asset");
+    std::fs::write(assets_dir.join("core/trees/.cache/rock.glb"), b"").expect("write asset");
+    std::fs::write(assets_dir.join("core/.rock.glb"), b"").expect("write asset");
+    let catalog = scan_asset_catalog(assets_dir).expect("scan assets");
+    assert!(catalog.contains(&AssetId::new("core", "trees/oak")));
+    assert_eq!(catalog.len(), 1);
+
+    let options = ValidationOptions {
+        cache: true,
+        ..ValidationOptions::default()
+    };
+    let missing = |issues: &[world::validator::ValidationIssue]| {
+        let mut found: Vec<(TileCoord, String)> = issues
+            .iter()
+            .filter(|issue| issue.code == codes::PROP_ASSET_MISSING)
+            .map(|issue| {
+                assert_eq!(issue.severity, Severity::Error);
+                assert_eq!(issue.section.as_deref(), Some("PROP"));
+                (issue.tile.expect("tile"), issue.message.clone())
+            })
+            .collect();
+        found.sort_by_key(|(tile, _)| tile.x);
+        found
+    };
+    let extensions = AddxRegistry::default();
+    let issues = validate_project_with_options(temp.path(), &extensions, options);
+    assert_eq!(
+        missing(&issues),
+        vec![
+            (
+                a,
+                "PROP references missing asset core/rock (3 instances)".to_string()
+            ),
+            (
+                b,
+                "PROP references missing asset core/rock (1 instance)".to_string()
+            ),
+        ]
+    );
+
+    // Only the changed tile's references are re-checked; the instance pass sees its new ids.
+    write_prop_tile(&world_layout, &world_manifest, "region_0", b, &[3, 5]);
+    let issues = validate_project_with_options(temp.path(), &extensions, options);
+    assert_eq!(
+        missing(&issues),
+        vec![
+            (
+                a,
+                "PROP references missing asset core/rock (3 instances)".to_string()
+            ),
+            (
+                b,
+                "PROP references missing asset core/rock (2 instances)".to_string()
+            ),
+        ]
+    );
+    assert_eq!(
+        issues
+            .iter()
+            .filter(|issue| issue.code == codes::PROP_INSTANCE_COLLISION)
+            .count(),
+        2
+    );
+
+    // Adding the asset invalidates the cached result even though no tile changed.
+    std::fs::write(assets_dir.join("core/rock.glb"), b"").expect("write asset");
+    let issues = validate_project_with_options(temp.path(), &extensions, options);
+    assert!(missing(&issues).is_empty(), "{issues:?}");
+}
+
+#[test]
+fn fix_mode_repairs_recoverable_tiles_instead_of_quarantining() {
+    let temp = tempdir().expect("tempdir");
+    let project_manifest = ProjectManifest::default();
+    let project_layout = create_project(temp.path(), &project_manifest).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            heightfield_samples: 2,
+            weightmap_resolution: 2,
+            liquids_resolution: 2,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+
+    let region = "region_0";
+    let tile_id = TileId {
+        coord: TileCoord { x: 0, y: 0 },
+    };
+    // Stamped with another region's hash.
+    let header = TileContainerHeader::new(
+        0,
+        0,
+        hash_region("region_9"),
+        hash_world_spec_from_manifest(&world_manifest),
+    );
+    let meta = MetaSection {
+        format_version: WORLD_FORMAT_VERSION,
+        tile_id,
+        region_hash: hash_region(region),
+        created_timestamp: 0,
+    };
+    let hmap = HmapSection {
+        width: 2,
+        height: 2,
+        samples: vec![0.0, 6000.0, -900.0, 10.0],
+    };
+    let wmap = WmapSection {
+        width: 2,
+        height: 2,
+        layers: 2,
+        weights: vec![255, 0, 100, 100, 0, 0, 10, 30],
+    };
+    let body = |id| LiqdBody {
+        id,
+        height: 1.0,
+        kind: LiqdKind::Water,
+    };
+    let liqd = LiqdSection {
+        width: 2,
+        height: 2,
+        mask: vec![0, 2, 2, 0],
+        bodies: vec![body(10), body(11), body(12)],
+    };
+    let prop = |id, x| PropRecord {
+        id: InstanceId(id),
+        asset: AssetId::new("core", "rock"),
+        translation: [x, 0.0, 0.0],
+        rotation: [0.0, 0.0, 0.0, 1.0],
+        scale: [1.0; 3],
+    };
+    let props = PropSection {
+        instances: vec![prop(1, 0.0), prop(1, 0.0), prop(1, 5.0), prop(4, 0.0)],
+    };
+
+    let mut writer = TileContainerWriter::new().alignment(DEFAULT_ALIGNMENT);
+    for (tag, decoded) in [
+        (TileSectionTag::META, encode_meta(&meta)),
+        (TileSectionTag::HMAP, encode_hmap(&hmap)),
+        (TileSectionTag::WMAP, encode_wmap(&wmap)),
+        (
+            TileSectionTag::LIQD,
+            encode_liqd(&liqd).expect("encode liqd"),
+        ),
+        (
+            TileSectionTag::PROP,
+            encode_prop(&props).expect("encode prop"),
+        ),
+    ] {
+        writer.add_section(TileSectionPayload {
+            tag,
+            section_version: 1,
+            codec: 0,
+            flags: 0,
+            decoded,
+        });
+    }
+    let path = tile_container_path(&world_layout, region, tile_id);
+    writer.write(&path, header).expect("write tile");
+    std::fs::create_dir_all(project_layout.assets_dir.join("core")).expect("create asset dir");
+    std::fs::write(project_layout.assets_dir.join("core/rock.glb"), b"").expect("write asset");
+
+    let issues = world::validator::validate_project(temp.path());
+    for code in [
+        codes::REGION_HASH_MISMATCH,
+        codes::HMAP_SAMPLE_INVALID,
+        codes::WMAP_WEIGHTS_UNNORMALIZED,
+        codes::LIQD_UNUSED_BODY,
+        codes::PROP_DUPLICATE_ID,
+    ] {
+        assert!(
+            issues.iter().any(|issue| issue.code == code),
+            "missing {code}: {issues:?}"
+        );
+    }
+
+    let report = fix_project(temp.path()).expect("fix project");
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert_eq!(report.tiles_rewritten, vec![path.clone()]);
+    let fixed_codes: Vec<_> = report.repairs.iter().map(|repair| repair.code).collect();
+    assert_eq!(
+        fixed_codes,
+        vec![
+            codes::HMAP_SAMPLE_INVALID,
+            codes::WMAP_WEIGHTS_UNNORMALIZED,
+            codes::LIQD_UNUSED_BODY,
+            codes::PROP_DUPLICATE_ID,
+            codes::PROP_DUPLICATE_ID,
+            codes::REGION_HASH_MISMATCH,
+        ]
+    );
+    assert!(path.with_extension("tile.bak").exists());
+
+    let issues = world::validator::validate_project_and_quarantine(temp.path());
+    assert!(issues.is_empty(), "{issues:?}");
+    assert!(path.exists());
+
+    let reader = TileContainerReader::open(&path).expect("read repaired tile");
+    assert_eq!(reader.header.region_hash, hash_region(region));
+    let hmap = decode_hmap(&reader.decode_section(TileSectionTag::HMAP).expect("HMAP"))
+        .expect("decode HMAP");
+    assert_eq!(hmap.samples, vec![0.0, 5000.0, -500.0, 10.0]);
+    let wmap = decode_wmap(&reader.decode_section(TileSectionTag::WMAP).expect("WMAP"))
+        .expect("decode WMAP");
+    assert_eq!(wmap.weights, vec![255, 0, 128, 127, 0, 0, 63, 192]);
+    let liqd = decode_liqd(&reader.decode_section(TileSectionTag::LIQD).expect("LIQD"))
+        .expect("decode LIQD");
+    assert_eq!(liqd.mask, vec![0, 1, 1, 0]);
+    let ids: Vec<u32> = liqd.bodies.iter().map(|body| body.id).collect();
+    assert_eq!(ids, vec![10, 12]);
+    let props = decode_prop(&reader.decode_section(TileSectionTag::PROP).expect("PROP"))
+        .expect("decode PROP");
+    let ids: Vec<u64> = props
+        .instances
+        .iter()
+        .map(|instance| instance.id.0)
+        .collect();
+    assert_eq!(ids, vec![1, 4, 5]);
+    assert_eq!(props.instances[2].translation, [5.0, 0.0, 0.0]);
+}
+
+#[test]
+fn fix_mode_fails_a_tile_when_duplicate_ids_cannot_be_reassigned() {
+    let temp = tempdir().expect("tempdir");
+    let project_layout =
+        create_project(temp.path(), &ProjectManifest::default()).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    let coord = TileCoord { x: 0, y: 0 };
+    let record = |x: f32| PropRecord {
+        id: InstanceId(u64::MAX),
+        asset: AssetId::new("core", "rock"),
+        translation: [x, 0.0, 0.0],
+        rotation: [0.0, 0.0, 0.0, 1.0],
+        scale: [1.0; 3],
+    };
+    write_prop_records(
+        &world_layout,
+        &world_manifest,
+        "region_0",
+        coord,
+        vec![record(0.0), record(1.0)],
+    );
+
+    // The clash needs an id above u64::MAX, so the tile is reported as failed and left as is.
+    let report = fix_project(temp.path()).expect("fix project");
+    assert!(report.tiles_rewritten.is_empty());
+    assert_eq!(report.failed.len(), 1, "{:?}", report.failed);
+    assert!(
+        report.failed[0].1.contains("no free instance id"),
+        "{:?}",
+        report.failed
+    );
+    assert_eq!(
+        read_prop_ids(&world_layout, "region_0", coord),
+        vec![u64::MAX, u64::MAX]
+    );
+}
+
+#[test]
+fn sarif_and_junit_reports_cover_issues_and_tiles() {
+    let temp = tempdir().expect("tempdir");
+    let project_manifest = ProjectManifest::default();
+    let project_layout = create_project(temp.path(), &project_manifest).expect("create project");
+    let world_manifest = WorldManifest {
+        world_id: "world_0".to_string(),
+        world_spec: WorldSpec {
+            heightfield_samples: SEAM_SAMPLES,
+            ..DEFAULT_WORLD_SPEC
+        },
+        regions: vec![RegionManifest {
+            region_id: "region_0".to_string(),
+            name: "Region 0".to_string(),
+            bounds: RegionBounds::new(0, 0, 1, 1),
+        }],
+        ..WorldManifest::default()
+    };
+    let world_layout = create_world(&project_layout, &world_manifest).expect("create world");
+    std::fs::create_dir_all(world_layout.regions_dir.join("stray")).expect("stray region");
+    let good = TileCoord { x: 0, y: 0 };
+    let bad = TileCoord { x: 3, y: 0 };
+    write_hmap_tile(&world_layout, &world_manifest, good, |_, _, h| h);
+    write_hmap_tile(&world_layout, &world_manifest, bad, |_, _, _| 9000.0);
+
+    let tiles = list_project_tiles(temp.path()).expect("list tiles");
+    assert_eq!(
+        tiles.iter().map(|tile| tile.tile).collect::<Vec<_>>(),
+        vec![good, bad]
+    );
+    let issues = world::validator::validate_project(temp.path());
+
+    let sarif = sarif_report(&issues, temp.path());
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    let rules: Vec<&str> = run["tool"]["driver"]["rules"]
+        .as_array()
+        .expect("rules")
+        .iter()
+        .map(|rule| rule["id"].as_str().expect("rule id"))
+        .collect();
+    assert_eq!(
+        rules,
+        vec![codes::HMAP_SAMPLE_INVALID, codes::REGION_NOT_LISTED]
+    );
+    let root_uri = run["originalUriBaseIds"]["PROJECTROOT"]["uri"]
+        .as_str()
+        .expect("root uri");
+    assert!(
+        root_uri.starts_with("file:///") && root_uri.ends_with('/'),
+        "{root_uri}"
+    );
+    let results = run["results"].as_array().expect("results");
+    let sample = results
+        .iter()
+        .find(|result| result["ruleId"] == codes::HMAP_SAMPLE_INVALID)
+        .expect("HMAP result");
+    assert_eq!(sample["level"], "error");
+    assert_eq!(
+        sample["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "worlds/world_0/regions/region_0/tiles/x3_y0.tile"
+    );
+    assert_eq!(sample["properties"]["tile"]["x"], 3);
+    assert_eq!(sample["properties"]["section"], "HMAP");
+    let stray = results
+        .iter()
+        .find(|result| result["ruleId"] == codes::REGION_NOT_LISTED)
+        .expect("stray result");
+    assert_eq!(stray["level"], "warning");
+
+    let junit = junit_report(&issues, &tiles, temp.path(), Severity::Error);
+    assert!(junit.contains("<testsuites name=\"validate_world\" tests=\"3\" failures=\"1\">"));
+    assert!(junit.contains("<testcase classname=\"world_0.region_0\" name=\"x0_y0\"/>"));
+    assert!(junit.contains("<failure type=\"HMAP_SAMPLE_INVALID\""));
+    // Below the threshold, the stray region is logged on the project case without failing it.
+    assert!(junit.contains("<system-out>warning[REGION_NOT_LISTED]"));
+}